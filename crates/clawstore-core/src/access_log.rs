@@ -0,0 +1,152 @@
+//! Per-key access log for audit trails
+//!
+//! When `Config::access_log` is set, the engine appends one line per
+//! `get`, `put` and `delete` after the operation completes:
+//!
+//! ```text
+//! {timestamp_ns},{operation},{key_hex}
+//! ```
+//!
+//! The file rotates to `<path>.1` once it reaches 10MB. Lines are buffered
+//! and reach disk on rotation, on `flush`, or when the engine is dropped.
+
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{ClawResult, ClawResultExt};
+
+/// Access log rotation threshold (10MB)
+pub const ACCESS_LOG_ROTATION_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Appends access records to a size-rotated log file.
+pub struct AccessLogWriter {
+    /// Active log file path
+    path: PathBuf,
+    /// Buffered handle on the active file
+    writer: BufWriter<File>,
+    /// Bytes in the active file, including buffered lines
+    written: u64,
+    /// Rotation threshold in bytes
+    max_size: u64,
+}
+
+impl AccessLogWriter {
+    /// Open (or create) the log at `path`, appending to any existing content.
+    pub fn open<P: AsRef<Path>>(path: P) -> ClawResult<Self> {
+        Self::with_max_size(path, ACCESS_LOG_ROTATION_BYTES)
+    }
+
+    /// Open the log with a custom rotation threshold.
+    pub fn with_max_size<P: AsRef<Path>>(path: P, max_size: u64) -> ClawResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (file, written) = open_append(&path)?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            written,
+            max_size,
+        })
+    }
+
+    /// Path of the active log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one `{timestamp_ns},{operation},{key_hex}` line.
+    ///
+    /// The timestamp is taken here, so callers holding the log lock get
+    /// lines in timestamp order.
+    pub fn record(&mut self, operation: &str, key: &[u8]) -> ClawResult<()> {
+        let timestamp_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        let mut line = String::with_capacity(32 + operation.len() + key.len() * 2);
+        let _ = write!(line, "{},{},", timestamp_ns, operation);
+        for byte in key {
+            let _ = write!(line, "{:02x}", byte);
+        }
+        line.push('\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.writer
+            .write_all(line.as_bytes())
+            .with_context("Failed to write access log")
+            .with_path(&self.path)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Flush buffered lines to the OS.
+    pub fn flush(&mut self) -> ClawResult<()> {
+        self.writer
+            .flush()
+            .with_context("Failed to flush access log")
+            .with_path(&self.path)
+    }
+
+    /// Move the active file to `<path>.1` and start a new one.
+    fn rotate(&mut self) -> ClawResult<()> {
+        self.flush()?;
+
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        let rotated = PathBuf::from(rotated);
+        std::fs::rename(&self.path, &rotated)
+            .with_context("Failed to rotate access log")
+            .with_path(&self.path)?;
+
+        let (file, written) = open_append(&self.path)?;
+        self.writer = BufWriter::new(file);
+        self.written = written;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> ClawResult<(File, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context("Failed to open access log")
+        .with_path(path)?;
+    let len = file
+        .metadata()
+        .with_context("Failed to stat access log")
+        .with_path(path)?
+        .len();
+    Ok((file, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotates_at_max_size() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("access.log");
+        let mut log = AccessLogWriter::with_max_size(&path, 64).unwrap();
+
+        for i in 0..10u8 {
+            log.record("get", &[i]).unwrap();
+        }
+        log.flush().unwrap();
+
+        let rotated = tmp.path().join("access.log.1");
+        assert!(rotated.exists());
+        assert!(std::fs::metadata(&path).unwrap().len() <= 64);
+
+        let last = std::fs::read_to_string(&path).unwrap();
+        assert!(last.lines().last().unwrap().ends_with(",get,09"));
+    }
+}
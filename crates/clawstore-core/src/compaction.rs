@@ -13,13 +13,19 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::datafile::{DataFileReader, DataEntry};
+use parking_lot::Mutex;
+
+use crate::datafile::{latest_data_file, parse_data_sequence, DataFileClaim, DataFileReader, DataEntry};
 use crate::error::{ClawError, ClawResult};
 use crate::platform_durability::durable_sync;
 
 /// Result of a compaction operation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CompactionResult {
     /// Path to the compacted file
     pub file_path: PathBuf,
@@ -33,6 +39,8 @@ pub struct CompactionResult {
     pub original_bytes: u64,
     /// Compacted file size in bytes
     pub compacted_bytes: u64,
+    /// Files whose entries went into `file_path` (just `file_path` for in-place compaction)
+    pub source_files: Vec<PathBuf>,
 }
 
 impl CompactionResult {
@@ -64,131 +72,365 @@ impl CompactionResult {
 /// - Before rename: original file is intact, temp file is orphaned (harmless)
 /// - After rename: new file is the compacted version (correct)
 pub fn compact_file(file_path: &Path) -> ClawResult<CompactionResult> {
-    let original_bytes = fs::metadata(file_path)
-        .map_err(|e| ClawError::Io {
+    // Step 1: Scan and deduplicate the original file
+    let scan = scan_latest_entries(file_path, false)?;
+    replace_with_live(file_path, scan)
+}
+
+/// `compact_file`, except that tombstones are kept while an older data file
+/// in the same directory may hold a value they shadow, as in
+/// `merge_files_to`. Returns `None`, leaving the file untouched, if nothing
+/// would be removed.
+fn compact_file_keeping_shadowing_tombstones(file_path: &Path) -> ClawResult<Option<CompactionResult>> {
+    let keep_tombstones = match (file_path.parent(), data_file_sequence(file_path)) {
+        (Some(dir), Some(seq)) => list_data_files(dir)?
+            .iter()
+            .any(|p| data_file_sequence(p).is_some_and(|other| other < seq)),
+        _ => true,
+    };
+    let scan = scan_latest_entries(file_path, keep_tombstones)?;
+    if scan.latest.len() == scan.original_entries {
+        return Ok(None);
+    }
+    replace_with_live(file_path, scan).map(Some)
+}
+
+/// Compact a data file holding DupSort entries stored as `[primary][subkey]`.
+///
+/// Each `(key[..primary_key_len], key[primary_key_len..])` pair keeps its
+/// latest value (last write wins, tombstones dropped), exactly as in
+/// `compact_file`; distinct subkeys of one primary are never merged. The
+/// survivors are written grouped by primary key and ordered by subkey
+/// within each group, the order DupSort cursors walk them in. An entry
+/// whose key is shorter than `primary_key_len` fails the whole call before
+/// anything is written. Crash safety is that of `compact_file`.
+pub fn compact_file_dupsort(file_path: &Path, primary_key_len: usize) -> ClawResult<CompactionResult> {
+    let mut scan = scan_latest_entries(file_path, false)?;
+    if let Some(short) = scan.latest.iter().find(|e| e.key.len() < primary_key_len) {
+        return Err(ClawError::Io {
             path: Some(file_path.to_path_buf()),
-            kind: e.kind(),
-            message: format!("Failed to stat file for compaction: {}", e),
-        })?
-        .len();
+            kind: std::io::ErrorKind::InvalidData,
+            message: format!(
+                "DupSort key of {} bytes is shorter than the {}-byte primary key",
+                short.key.len(), primary_key_len
+            ),
+            inner: None,
+        });
+    }
+    // The primary key has a fixed length, so byte order of the whole key is
+    // (primary, subkey) order
+    scan.latest.sort_by(|a, b| a.key.cmp(&b.key));
+    replace_with_live(file_path, scan)
+}
+
+/// Steps 2-5 of `compact_file`: write `scan.latest` over `file_path` in order.
+fn replace_with_live(file_path: &Path, scan: LiveScan) -> ClawResult<CompactionResult> {
+    let kept: Vec<&DataEntry> = scan.latest.iter().collect();
+    let live_entries = kept.iter().filter(|e| !e.is_tombstone).count();
+    let removed_entries = scan.original_entries - kept.len();
+
+    // Steps 2-3: Write kept entries to temp file and durable_sync it
+    let compact_path = file_path.with_extension("claw.compact");
+    let compacted_bytes = write_synced_file(&compact_path, &kept)?;
 
-    // Step 1: Scan all entries from the original file
+    // Step 4: Atomic rename — replace original with compacted
+    fs::rename(&compact_path, file_path).map_err(|e| ClawError::Io {
+        path: Some(file_path.to_path_buf()),
+        kind: e.kind(),
+        message: format!("Failed to rename compacted file: {}", e),
+        inner: Some(Arc::new(e)),
+    })?;
+
+    // Step 5: durable_sync the directory to ensure rename is persisted
+    if let Some(parent) = file_path.parent() {
+        sync_dir(parent)?;
+    }
+
+    Ok(CompactionResult {
+        file_path: file_path.to_path_buf(),
+        original_entries: scan.original_entries,
+        live_entries,
+        removed_entries,
+        original_bytes: scan.original_bytes,
+        compacted_bytes,
+        source_files: vec![file_path.to_path_buf()],
+    })
+}
+
+/// Report what `compact_file` would do, without writing or renaming anything.
+///
+/// Entry counts and `original_bytes` match what `compact_file` would
+/// return. `compacted_bytes` is estimated from the live entries' on-disk
+/// sizes rather than measured from a written file.
+pub fn compact_file_dry_run(file_path: &Path) -> ClawResult<CompactionResult> {
+    let scan = scan_latest_entries(file_path, false)?;
+    let live_entries = scan.latest.len();
+    Ok(CompactionResult {
+        file_path: file_path.to_path_buf(),
+        original_entries: scan.original_entries,
+        live_entries,
+        removed_entries: scan.original_entries - live_entries,
+        original_bytes: scan.original_bytes,
+        compacted_bytes: scan.latest.iter().map(|e| e.total_on_disk_size() as u64).sum(),
+        source_files: vec![file_path.to_path_buf()],
+    })
+}
+
+/// Entries of one data file after last-write-wins deduplication.
+struct LiveScan {
+    /// Entries read from the file
+    original_entries: usize,
+    /// Bytes held by readable entries — skipped corrupt regions are not counted
+    original_bytes: u64,
+    /// Latest entry for each key; tombstones only if the scan kept them
+    latest: Vec<DataEntry>,
+}
+
+fn scan_latest_entries(file_path: &Path, keep_tombstones: bool) -> ClawResult<LiveScan> {
     let all_entries = DataFileReader::scan_all(file_path)?;
     let original_entries = all_entries.len();
+    let original_bytes: u64 = all_entries.iter().map(|e| e.total_on_disk_size() as u64).sum();
 
     // Deduplicate: keep only the LAST entry for each key (last-write-wins)
     // Tombstones override previous values
     let mut latest: HashMap<Vec<u8>, DataEntry> = HashMap::new();
     for entry in all_entries {
-        latest.insert(entry.key.clone(), entry);
+        keep_latest(&mut latest, entry);
     }
 
-    // Filter out tombstones — they served their purpose
-    let live: Vec<&DataEntry> = latest.values()
-        .filter(|e| !e.is_tombstone)
-        .collect();
-    let live_entries = live.len();
-    let removed_entries = original_entries - live_entries;
+    // Filter out tombstones unless they may still shadow an older file
+    let latest = latest.into_values().filter(|e| keep_tombstones || !e.is_tombstone).collect();
+    Ok(LiveScan { original_entries, original_bytes, latest })
+}
 
-    // Step 2: Write live entries to temp file
-    let compact_path = file_path.with_extension("claw.compact");
+/// Merge several data files into one new file in `dest_dir`, then delete them.
+///
+/// Sources are read in sequence order and deduplicated (last write wins).
+/// The merged file is named with the highest source sequence + 1, so it
+/// still sorts after every file it replaces. That name must not exist yet.
+/// Tombstones are dropped only when `dest_dir` holds no data file older
+/// than the sources; otherwise they must keep shadowing older values.
+///
+/// Crash safety: the merged file is written to a `.compact` temp file,
+/// synced and renamed into place before any source is removed. A crash
+/// between rename and removal leaves duplicate entries, and the merged file
+/// still wins because it has the higher sequence.
+pub fn merge_files_to(sources: &[&Path], dest_dir: &Path) -> ClawResult<(PathBuf, CompactionResult)> {
+    let mut ordered: Vec<(u64, PathBuf)> = Vec::with_capacity(sources.len());
+    for source in sources {
+        let seq = data_file_sequence(source).ok_or_else(|| ClawError::Io {
+            path: Some(source.to_path_buf()),
+            kind: std::io::ErrorKind::InvalidInput,
+            message: "Not a data file name (expected data-<seq>.claw)".to_string(),
+            inner: None,
+        })?;
+        ordered.push((seq, source.to_path_buf()));
+    }
+    ordered.sort_by_key(|(seq, _)| *seq);
+    let (Some((min_seq, _)), Some((max_seq, _))) = (ordered.first(), ordered.last()) else {
+        return Err(ClawError::Io {
+            path: Some(dest_dir.to_path_buf()),
+            kind: std::io::ErrorKind::InvalidInput,
+            message: "merge_files_to needs at least one source file".to_string(),
+            inner: None,
+        });
+    };
+    let min_seq = *min_seq;
 
-    {
-        let mut compact_file = fs::OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&compact_path)
+    let dest_path = dest_dir.join(format!("data-{:016x}.claw", max_seq + 1));
+    if dest_path.exists() {
+        return Err(ClawError::Io {
+            path: Some(dest_path),
+            kind: std::io::ErrorKind::AlreadyExists,
+            message: "Merge target sequence is already in use".to_string(),
+            inner: None,
+        });
+    }
+
+    let keep_tombstones = list_data_files(dest_dir)?
+        .iter()
+        .any(|p| data_file_sequence(p).is_some_and(|seq| seq < min_seq));
+
+    // Step 1: Read all sources in order, last write wins (by entry sequence
+    // number where recorded, so files written concurrently resolve correctly)
+    let mut original_bytes = 0u64;
+    let mut original_entries = 0usize;
+    let mut latest: HashMap<Vec<u8>, DataEntry> = HashMap::new();
+    for (_, source) in &ordered {
+        original_bytes += fs::metadata(source)
             .map_err(|e| ClawError::Io {
-                path: Some(compact_path.clone()),
+                path: Some(source.clone()),
                 kind: e.kind(),
-                message: format!("Failed to create compact file: {}", e),
-            })?;
+                message: format!("Failed to stat file for merge: {}", e),
+                inner: Some(Arc::new(e)),
+            })?
+            .len();
+        original_entries += DataFileReader::stream_scan(source, |entry| {
+            keep_latest(&mut latest, entry);
+        })?;
+    }
 
-        for entry in &live {
-            // Reuse the datafile format: header + key + value
-            let key = &entry.key;
-            let value = &entry.value;
-
-            // Build header manually (same format as DataChunkHeader)
-            let checksum = crc32c::crc32c(&[key.as_slice(), value.as_slice()].concat());
-            let mut hdr = [0u8; 24];
-            hdr[0..4].copy_from_slice(&crate::format::MAGIC_ARRAY);
-            hdr[4..6].copy_from_slice(&(key.len() as u16).to_le_bytes());
-            hdr[6..10].copy_from_slice(&(value.len() as u32).to_le_bytes());
-            hdr[10..14].copy_from_slice(&checksum.to_le_bytes());
-            // flags = 0 (live entry), rest is zero padding
-
-            use std::io::Write;
-            compact_file.write_all(&hdr).map_err(|e| ClawError::Io {
-                path: Some(compact_path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to write compacted entry: {}", e),
-            })?;
-            compact_file.write_all(key).map_err(|e| ClawError::Io {
-                path: Some(compact_path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to write compacted key: {}", e),
-            })?;
-            compact_file.write_all(value).map_err(|e| ClawError::Io {
-                path: Some(compact_path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to write compacted value: {}", e),
-            })?;
-        }
+    let mut kept: Vec<&DataEntry> = latest.values()
+        .filter(|e| keep_tombstones || !e.is_tombstone)
+        .collect();
+    kept.sort_by(|a, b| a.key.cmp(&b.key));
+    let live_entries = kept.iter().filter(|e| !e.is_tombstone).count();
 
-        // Step 3: durable_sync the compacted file
-        durable_sync(&compact_file).map_err(|e| ClawError::Io {
-            path: Some(compact_path.clone()),
+    // Steps 2-4: temp file, sync, rename into place, sync directory
+    let compact_path = dest_path.with_extension("claw.compact");
+    let compacted_bytes = write_synced_file(&compact_path, &kept)?;
+    fs::rename(&compact_path, &dest_path).map_err(|e| ClawError::Io {
+        path: Some(dest_path.clone()),
+        kind: e.kind(),
+        message: format!("Failed to rename merged file: {}", e),
+        inner: Some(Arc::new(e)),
+    })?;
+    sync_dir(dest_dir)?;
+
+    // Step 5: Only now remove the sources
+    for (_, source) in &ordered {
+        fs::remove_file(source).map_err(|e| ClawError::Io {
+            path: Some(source.clone()),
             kind: e.kind(),
-            message: format!("Failed to sync compacted file: {}", e),
+            message: format!("Failed to remove merged source file: {}", e),
+            inner: Some(Arc::new(e)),
         })?;
+        if let Some(parent) = source.parent() {
+            sync_dir(parent)?;
+        }
+    }
+
+    eprintln!(
+        "[COMPACTION] merged {} files -> {} ({} -> {} entries)",
+        ordered.len(),
+        dest_path.display(),
+        original_entries,
+        live_entries
+    );
+
+    let result = CompactionResult {
+        file_path: dest_path.clone(),
+        original_entries,
+        live_entries,
+        removed_entries: original_entries - kept.len(),
+        original_bytes,
+        compacted_bytes,
+        source_files: ordered.into_iter().map(|(_, p)| p).collect(),
+    };
+    Ok((dest_path, result))
+}
+
+/// Record `entry` as its key's latest unless an entry already seen supersedes it.
+fn keep_latest(latest: &mut HashMap<Vec<u8>, DataEntry>, entry: DataEntry) {
+    match latest.get(&entry.key) {
+        Some(earlier) if !entry.supersedes(earlier) => {}
+        _ => {
+            latest.insert(entry.key.clone(), entry);
+        }
     }
+}
+
+/// Sequence number of a `data-{seq:016x}.claw` path.
+fn data_file_sequence(path: &Path) -> Option<u64> {
+    path.file_name().and_then(|n| n.to_str()).and_then(parse_data_sequence)
+}
 
-    let compacted_bytes = fs::metadata(&compact_path)
+/// Write entries in data file format to `path`, durable_sync it, return its size.
+fn write_synced_file(path: &Path, entries: &[&DataEntry]) -> ClawResult<u64> {
+    use std::io::Write;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
         .map_err(|e| ClawError::Io {
-            path: Some(compact_path.clone()),
+            path: Some(path.to_path_buf()),
             kind: e.kind(),
-            message: format!("Failed to stat compacted file: {}", e),
-        })?
-        .len();
+            message: format!("Failed to create compact file: {}", e),
+            inner: Some(Arc::new(e)),
+        })?;
 
-    // Step 4: Atomic rename — replace original with compacted
-    fs::rename(&compact_path, file_path).map_err(|e| ClawError::Io {
-        path: Some(file_path.to_path_buf()),
-        kind: e.kind(),
-        message: format!("Failed to rename compacted file: {}", e),
-    })?;
+    for entry in entries {
+        // Reuse the datafile format: header + key + value
+        let key = &entry.key;
+        let value = &entry.value;
 
-    // Step 5: durable_sync the directory to ensure rename is persisted
-    if let Some(parent) = file_path.parent() {
-        let dir = fs::File::open(parent).map_err(|e| ClawError::Io {
-            path: Some(parent.to_path_buf()),
+        // Build header manually (same format as DataChunkHeader)
+        let checksum = crc32c::crc32c(&[key.as_slice(), value.as_slice()].concat());
+        let mut hdr = [0u8; 24];
+        hdr[0..4].copy_from_slice(&crate::format::MAGIC_ARRAY);
+        hdr[4..6].copy_from_slice(&(key.len() as u16).to_le_bytes());
+        hdr[6..10].copy_from_slice(&(value.len() as u32).to_le_bytes());
+        hdr[10..14].copy_from_slice(&checksum.to_le_bytes());
+        hdr[14] = entry.is_tombstone as u8; // flags bit 0 = tombstone
+        hdr[17..24].copy_from_slice(&entry.sequence_number.to_le_bytes()[..7]);
+
+        file.write_all(&hdr).map_err(|e| ClawError::Io {
+            path: Some(path.to_path_buf()),
+            kind: e.kind(),
+            message: format!("Failed to write compacted entry: {}", e),
+            inner: Some(Arc::new(e)),
+        })?;
+        file.write_all(key).map_err(|e| ClawError::Io {
+            path: Some(path.to_path_buf()),
             kind: e.kind(),
-            message: format!("Failed to open directory for sync: {}", e),
+            message: format!("Failed to write compacted key: {}", e),
+            inner: Some(Arc::new(e)),
         })?;
-        durable_sync(&dir).map_err(|e| ClawError::Io {
-            path: Some(parent.to_path_buf()),
+        file.write_all(value).map_err(|e| ClawError::Io {
+            path: Some(path.to_path_buf()),
             kind: e.kind(),
-            message: format!("Failed to sync directory after compaction: {}", e),
+            message: format!("Failed to write compacted value: {}", e),
+            inner: Some(Arc::new(e)),
         })?;
     }
 
-    Ok(CompactionResult {
-        file_path: file_path.to_path_buf(),
-        original_entries,
-        live_entries,
-        removed_entries,
-        original_bytes,
-        compacted_bytes,
+    durable_sync(&file).map_err(|e| ClawError::Io {
+        path: Some(path.to_path_buf()),
+        kind: e.kind(),
+        message: format!("Failed to sync compacted file: {}", e),
+        inner: Some(Arc::new(e)),
+    })?;
+
+    let size = fs::metadata(path)
+        .map_err(|e| ClawError::Io {
+            path: Some(path.to_path_buf()),
+            kind: e.kind(),
+            message: format!("Failed to stat compacted file: {}", e),
+            inner: Some(Arc::new(e)),
+        })?
+        .len();
+    Ok(size)
+}
+
+/// durable_sync a directory so renames and removals in it are persisted.
+fn sync_dir(dir: &Path) -> ClawResult<()> {
+    let handle = fs::File::open(dir).map_err(|e| ClawError::Io {
+        path: Some(dir.to_path_buf()),
+        kind: e.kind(),
+        message: format!("Failed to open directory for sync: {}", e),
+        inner: Some(Arc::new(e)),
+    })?;
+    durable_sync(&handle).map_err(|e| ClawError::Io {
+        path: Some(dir.to_path_buf()),
+        kind: e.kind(),
+        message: format!("Failed to sync directory after compaction: {}", e),
+        inner: Some(Arc::new(e)),
     })
 }
 
 /// Check if a data file needs compaction based on dead space ratio.
 pub fn needs_compaction(file_path: &Path, threshold: f64) -> ClawResult<bool> {
+    Ok(file_dead_space_ratio(file_path)?.is_some_and(|ratio| ratio >= threshold))
+}
+
+/// Fraction of a data file's entries that compaction would remove, or
+/// `None` for a file without entries.
+fn file_dead_space_ratio(file_path: &Path) -> ClawResult<Option<f64>> {
     let entries = DataFileReader::scan_all(file_path)?;
     if entries.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
 
     // Count unique live keys (last-write-wins dedup)
@@ -198,19 +440,72 @@ pub fn needs_compaction(file_path: &Path, threshold: f64) -> ClawResult<bool> {
     }
 
     let live_count = latest.values().filter(|&&is_tomb| !is_tomb).count();
-    let dead_ratio = 1.0 - (live_count as f64 / entries.len() as f64);
+    Ok(Some(1.0 - (live_count as f64 / entries.len() as f64)))
+}
 
-    Ok(dead_ratio >= threshold)
+/// Data files at or above `threshold` dead space, worst first.
+///
+/// Returns up to `max_count` `(path, dead_space_ratio)` pairs sorted by
+/// ratio descending; files with equal ratios stay in sequence order.
+pub fn ranked_compaction_candidates(
+    data_dir: &Path,
+    threshold: f64,
+    max_count: usize,
+) -> ClawResult<Vec<(PathBuf, f64)>> {
+    ranked_candidates(data_dir, threshold, max_count, 0)
 }
 
-/// Compact all data files in a directory that exceed the dead space threshold.
-pub fn compact_directory(data_dir: &Path, threshold: f64) -> ClawResult<Vec<CompactionResult>> {
-    let mut results = Vec::new();
+/// `ranked_compaction_candidates`, skipping files younger than `min_age_secs`.
+fn ranked_candidates(
+    data_dir: &Path,
+    threshold: f64,
+    max_count: usize,
+    min_age_secs: u64,
+) -> ClawResult<Vec<(PathBuf, f64)>> {
+    let mut candidates = Vec::new();
+    for file_path in list_data_files(data_dir)? {
+        if is_too_recent(&file_path, min_age_secs)? {
+            continue;
+        }
+        if let Some(ratio) = file_dead_space_ratio(&file_path)? {
+            if ratio >= threshold {
+                candidates.push((file_path, ratio));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+    candidates.truncate(max_count);
+    Ok(candidates)
+}
+
+/// Tuning knobs for a directory compaction pass.
+#[derive(Debug, Clone)]
+pub struct CompactionOptions {
+    /// Compact files whose dead space ratio is at least this value
+    pub threshold: f64,
+    /// Skip files modified less than this many seconds ago (still warm)
+    pub min_age_secs: u64,
+    /// Upper bound on files compacted per invocation, to limit I/O
+    pub max_files_per_run: usize,
+}
+
+impl Default for CompactionOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.3,
+            min_age_secs: 0,
+            max_files_per_run: usize::MAX,
+        }
+    }
+}
 
+/// List all data files (excluding in-progress `.compact` files) in a directory.
+pub(crate) fn list_data_files(data_dir: &Path) -> ClawResult<Vec<PathBuf>> {
     let dir_entries = fs::read_dir(data_dir).map_err(|e| ClawError::Io {
         path: Some(data_dir.to_path_buf()),
         kind: e.kind(),
         message: format!("Failed to read data directory: {}", e),
+        inner: Some(Arc::new(e)),
     })?;
 
     let mut data_files: Vec<PathBuf> = Vec::new();
@@ -221,28 +516,250 @@ pub fn compact_directory(data_dir: &Path, threshold: f64) -> ClawResult<Vec<Comp
             }
         }
     }
+    data_files.sort(); // lexicographic sort = sequence order (hex-padded)
+
+    Ok(data_files)
+}
+
+/// Returns true if the file was modified less than `min_age_secs` ago.
+fn is_too_recent(file_path: &Path, min_age_secs: u64) -> ClawResult<bool> {
+    if min_age_secs == 0 {
+        return Ok(false);
+    }
+    let modified = fs::metadata(file_path)
+        .and_then(|m| m.modified())
+        .map_err(|e| ClawError::Io {
+            path: Some(file_path.to_path_buf()),
+            kind: e.kind(),
+            message: format!("Failed to read modification time: {}", e),
+            inner: Some(Arc::new(e)),
+        })?;
+    // A modification time in the future (clock skew) counts as brand new
+    let age = modified.elapsed().unwrap_or_default();
+    Ok(age.as_secs() < min_age_secs)
+}
+
+/// Compact all data files in a directory that exceed the dead space threshold.
+pub fn compact_directory(data_dir: &Path, threshold: f64) -> ClawResult<Vec<CompactionResult>> {
+    let opts = CompactionOptions { threshold, ..CompactionOptions::default() };
+    compact_directory_with_options(data_dir, &opts)
+}
 
-    for file_path in data_files {
+/// Dry-run counterpart of `compact_directory`: reports the files that
+/// exceed `threshold` via `compact_file_dry_run`, leaving them untouched.
+pub fn compact_directory_dry_run(data_dir: &Path, threshold: f64) -> ClawResult<Vec<CompactionResult>> {
+    let mut results = Vec::new();
+    for file_path in list_data_files(data_dir)? {
         if needs_compaction(&file_path, threshold)? {
-            let result = compact_file(&file_path)?;
-            eprintln!(
-                "[COMPACTION] {} : {} -> {} entries ({} bytes saved)",
-                file_path.display(),
-                result.original_entries,
-                result.live_entries,
-                result.bytes_saved()
-            );
-            results.push(result);
+            results.push(compact_file_dry_run(&file_path)?);
         }
     }
+    Ok(results)
+}
+
+/// Compact data files in a directory, honouring age and per-run limits.
+///
+/// Files written within the last `min_age_secs` are skipped even if they
+/// exceed the threshold: the trickle engine may still be overwriting those
+/// keys, so compacting them now would only be repeated shortly after.
+/// Files are compacted worst first, so `max_files_per_run` spends its
+/// budget where the most space is reclaimed.
+pub fn compact_directory_with_options(
+    data_dir: &Path,
+    opts: &CompactionOptions,
+) -> ClawResult<Vec<CompactionResult>> {
+    let candidates = ranked_candidates(data_dir, opts.threshold, opts.max_files_per_run, opts.min_age_secs)?;
+    compact_candidates(candidates.iter().map(|(path, _)| path.as_path()))
+}
+
+/// Compact the `n` data files with the most dead space, whatever their ratio.
+///
+/// Files without any dead space are never rewritten.
+pub fn compact_top_n(data_dir: &Path, n: usize) -> ClawResult<Vec<CompactionResult>> {
+    let candidates = ranked_compaction_candidates(data_dir, f64::MIN_POSITIVE, n)?;
+    compact_candidates(candidates.iter().map(|(path, _)| path.as_path()))
+}
 
+fn compact_candidates<'a>(candidates: impl IntoIterator<Item = &'a Path>) -> ClawResult<Vec<CompactionResult>> {
+    let mut results = Vec::new();
+    for file_path in candidates {
+        let result = compact_file(file_path)?;
+        log_compaction(file_path, &result);
+        results.push(result);
+    }
     Ok(results)
 }
 
+fn log_compaction(file_path: &Path, result: &CompactionResult) {
+    eprintln!(
+        "[COMPACTION] {} : {} -> {} entries ({} bytes saved)",
+        file_path.display(),
+        result.original_entries,
+        result.live_entries,
+        result.bytes_saved()
+    );
+}
+
+/// Policy deciding which data files a `CompactionScheduler` run compacts.
+pub trait CompactionStrategy: Send + Sync {
+    /// Data files in `dir` to compact now, in the order to compact them.
+    fn should_compact(&self, dir: &Path) -> ClawResult<Vec<PathBuf>>;
+}
+
+/// Compact every file whose dead space ratio is at least `ratio`, worst first.
+#[derive(Debug, Clone, Copy)]
+pub struct ThresholdStrategy {
+    pub ratio: f64,
+}
+
+impl CompactionStrategy for ThresholdStrategy {
+    fn should_compact(&self, dir: &Path) -> ClawResult<Vec<PathBuf>> {
+        let candidates = ranked_candidates(dir, self.ratio, usize::MAX, 0)?;
+        Ok(candidates.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+/// Compact up to `max_files` files with any dead space that were last
+/// modified at least `min_age` ago (whole seconds), worst first.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeBasedStrategy {
+    pub min_age: Duration,
+    pub max_files: usize,
+}
+
+impl CompactionStrategy for TimeBasedStrategy {
+    fn should_compact(&self, dir: &Path) -> ClawResult<Vec<PathBuf>> {
+        let candidates = ranked_candidates(dir, f64::MIN_POSITIVE, self.max_files, self.min_age.as_secs())?;
+        Ok(candidates.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+/// Runs compaction on a background thread every `cadence`, compacting
+/// whatever files its `CompactionStrategy` picks.
+///
+/// The newest data file is never compacted, even if the strategy picks it:
+/// the trickle engine may still be appending to it or reopen it on restart.
+/// Nor is any file a `DataFileWriter` in this process is appending to,
+/// such as those of parallel trickle workers. Files are compacted one
+/// at a time, so a tombstone is only dropped from the oldest data file;
+/// elsewhere it may shadow a value in an older file, which `vacuum` can
+/// have made the only copy of that key's history. Files where nothing
+/// would be removed are left untouched.
+pub struct CompactionScheduler {
+    data_dir: PathBuf,
+    strategy: Box<dyn CompactionStrategy>,
+    cadence: Duration,
+}
+
+impl CompactionScheduler {
+    /// Create a scheduler for `data_dir` (engine_path/data/). Nothing runs
+    /// until `start` is called.
+    pub fn new(data_dir: PathBuf, strategy: Box<dyn CompactionStrategy>, cadence: Duration) -> Self {
+        Self { data_dir, strategy, cadence }
+    }
+
+    /// Spawn the background thread. The first run happens one `cadence`
+    /// after this call.
+    pub fn start(self) -> ClawResult<CompactionHandle> {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let last_result = Arc::new(Mutex::new(None));
+        let data_dir = self.data_dir.clone();
+
+        let shutdown_clone = Arc::clone(&shutdown);
+        let last_result_clone = Arc::clone(&last_result);
+        let thread = thread::Builder::new()
+            .name("clawstore-compaction".to_string())
+            .spawn(move || self.run(&shutdown_clone, &last_result_clone))
+            .map_err(|e| ClawError::Io {
+                path: Some(data_dir),
+                kind: std::io::ErrorKind::Other,
+                message: format!("Failed to spawn compaction thread: {}", e),
+                inner: Some(Arc::new(e)),
+            })?;
+
+        Ok(CompactionHandle { shutdown, thread: Some(thread), last_result })
+    }
+
+    fn run(&self, shutdown: &AtomicBool, last_result: &Mutex<Option<Vec<CompactionResult>>>) {
+        loop {
+            let cycle_start = Instant::now();
+            while cycle_start.elapsed() < self.cadence {
+                if shutdown.load(Ordering::Acquire) {
+                    return;
+                }
+                let remaining = self.cadence.saturating_sub(cycle_start.elapsed());
+                thread::sleep(remaining.min(Duration::from_millis(100)));
+            }
+            if shutdown.load(Ordering::Acquire) {
+                return;
+            }
+
+            match self.run_once() {
+                Ok(results) => *last_result.lock() = Some(results),
+                Err(e) => eprintln!("[COMPACTION] Scheduled compaction failed: {}", e),
+            }
+        }
+    }
+
+    /// One scheduled run: compact the strategy's picks, minus the newest
+    /// file and files held by a writer.
+    fn run_once(&self) -> ClawResult<Vec<CompactionResult>> {
+        let newest = latest_data_file(&self.data_dir);
+        let candidates = self.strategy.should_compact(&self.data_dir)?;
+        let mut results = Vec::new();
+        for file_path in candidates.iter().filter(|path| Some(*path) != newest.as_ref()) {
+            // Held until the rewrite is renamed into place
+            let Some(_claim) = DataFileClaim::try_claim(file_path) else {
+                continue;
+            };
+            if let Some(result) = compact_file_keeping_shadowing_tombstones(file_path)? {
+                log_compaction(file_path, &result);
+                results.push(result);
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Handle to a running `CompactionScheduler`.
+/// Dropping this handle stops the scheduler.
+pub struct CompactionHandle {
+    shutdown: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+    /// Results of the most recent successful run
+    last_result: Arc<Mutex<Option<Vec<CompactionResult>>>>,
+}
+
+impl CompactionHandle {
+    /// Stop the scheduler and wait for an in-progress run to finish.
+    pub fn stop(mut self) {
+        self.stop_and_join();
+    }
+
+    /// Results of the most recent successful run, `None` before the first.
+    /// A run that found nothing to compact yields an empty list.
+    pub fn last_result(&self) -> Option<Vec<CompactionResult>> {
+        self.last_result.lock().clone()
+    }
+
+    fn stop_and_join(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for CompactionHandle {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::datafile::DataFileWriter;
+    use crate::datafile::{DataFileWriter, DEFAULT_MAX_DATA_FILE_SIZE};
     use tempfile::TempDir;
 
     fn find_data_file(dir: &Path) -> PathBuf {
@@ -262,18 +779,21 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
 
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
         writer.write_entry(b"keep", b"alive").unwrap();
         writer.write_entry(b"dead", b"temporary").unwrap();
         writer.write_tombstone(b"dead").unwrap();
         drop(writer);
 
         let file = find_data_file(&dir);
+        let file_size = fs::metadata(&file).unwrap().len();
         let result = compact_file(&file).unwrap();
 
         assert_eq!(result.original_entries, 3);
         assert_eq!(result.live_entries, 1);
         assert_eq!(result.removed_entries, 2);
+        assert_eq!(result.original_bytes, file_size);
+        assert_eq!(result.compacted_bytes, (24 + b"keep".len() + b"alive".len()) as u64);
         assert!(result.compacted_bytes < result.original_bytes);
 
         // Verify the compacted file only has the live entry
@@ -283,12 +803,48 @@ mod tests {
         assert_eq!(entries[0].value, b"alive");
     }
 
+    #[test]
+    fn test_dry_run_reports_without_changing_files() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(b"keep", b"alive").unwrap();
+        writer.write_entry(b"k", b"v1").unwrap();
+        writer.write_entry(b"k", b"v2").unwrap();
+        writer.write_entry(b"dead", b"temporary").unwrap();
+        writer.write_tombstone(b"dead").unwrap();
+        drop(writer);
+
+        let file = find_data_file(&dir);
+        let before = fs::read(&file).unwrap();
+
+        let result = compact_file_dry_run(&file).unwrap();
+        assert_eq!(result.original_entries, 5);
+        assert_eq!(result.live_entries, 2);
+        assert_eq!(result.removed_entries, 3);
+        assert_eq!(result.original_bytes, before.len() as u64);
+        assert_eq!(result.compacted_bytes, (2 * 24 + b"keepalive".len() + b"kv2".len()) as u64);
+
+        let dir_results = compact_directory_dry_run(&dir, 0.5).unwrap();
+        assert_eq!(dir_results.len(), 1);
+        assert_eq!(dir_results[0].removed_entries, 3);
+
+        // Nothing on disk changed and no temp file was left behind
+        assert_eq!(fs::read(&file).unwrap(), before);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // The real compaction agrees with the projection
+        let real = compact_file(&file).unwrap();
+        assert_eq!(real.compacted_bytes, result.compacted_bytes);
+    }
+
     #[test]
     fn test_compact_deduplicates_overwrites() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
 
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
         writer.write_entry(b"k", b"v1").unwrap();
         writer.write_entry(b"k", b"v2").unwrap();
         writer.write_entry(b"k", b"v3_final").unwrap();
@@ -306,12 +862,51 @@ mod tests {
         assert_eq!(entries[0].value, b"v3_final");
     }
 
+    #[test]
+    fn test_compact_dupsort_keeps_latest_per_subkey() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        // Storage slots: [20-byte address][32-byte slot]
+        let slot_key = |addr: u8, slot: u8| {
+            let mut key = vec![addr; 20];
+            key.extend_from_slice(&[slot; 32]);
+            key
+        };
+
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(&slot_key(0xBB, 2), b"b2").unwrap();
+        writer.write_entry(&slot_key(0xAA, 2), b"a2-old").unwrap();
+        writer.write_entry(&slot_key(0xAA, 1), b"a1").unwrap();
+        writer.write_entry(&slot_key(0xAA, 2), b"a2-new").unwrap();
+        writer.write_entry(&slot_key(0xBB, 1), b"b1").unwrap();
+        writer.write_tombstone(&slot_key(0xBB, 2)).unwrap();
+        drop(writer);
+
+        let file = find_data_file(&dir);
+        let result = compact_file_dupsort(&file, 20).unwrap();
+        assert_eq!(result.original_entries, 6);
+        assert_eq!(result.live_entries, 3);
+
+        let entries: Vec<_> = DataFileReader::scan_all(&file).unwrap().into_iter()
+            .map(|e| (e.key, e.value))
+            .collect();
+        assert_eq!(entries, vec![
+            (slot_key(0xAA, 1), b"a1".to_vec()),
+            (slot_key(0xAA, 2), b"a2-new".to_vec()),
+            (slot_key(0xBB, 1), b"b1".to_vec()),
+        ]);
+
+        // A key shorter than the primary leaves the file as it was
+        assert!(compact_file_dupsort(&file, 64).is_err());
+        assert_eq!(DataFileReader::scan_all(&file).unwrap().len(), 3);
+    }
+
     #[test]
     fn test_compact_empty_file_after_all_deleted() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
 
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
         writer.write_entry(b"a", b"1").unwrap();
         writer.write_tombstone(b"a").unwrap();
         drop(writer);
@@ -328,7 +923,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
 
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
         // 5 entries, 2 will be overwritten = 2/5 = 40% dead
         writer.write_entry(b"a", b"1").unwrap();
         writer.write_entry(b"b", b"2").unwrap();
@@ -351,7 +946,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
 
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
         writer.write_entry(b"important", b"data").unwrap();
         writer.write_entry(b"stale", b"old").unwrap();
         writer.write_entry(b"stale", b"new").unwrap();
@@ -379,9 +974,284 @@ mod tests {
             removed_entries: 40,
             original_bytes: 10000,
             compacted_bytes: 6000,
+            source_files: Vec::new(),
         };
 
         assert!((result.dead_space_ratio() - 0.4).abs() < f64::EPSILON);
         assert_eq!(result.bytes_saved(), 4000);
     }
+
+    fn data_file_at(dir: &Path, seq: u64) -> DataFileWriter {
+        let path = dir.join(format!("data-{:016x}.claw", seq));
+        fs::write(&path, b"").unwrap();
+        DataFileWriter::open_existing(&path, DEFAULT_MAX_DATA_FILE_SIZE).unwrap()
+    }
+
+    #[test]
+    fn test_merge_files_to_next_sequence() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        let mut w3 = data_file_at(dir, 3);
+        w3.write_entry(b"a", b"a1").unwrap();
+        w3.write_entry(b"b", b"b1").unwrap();
+        w3.write_entry(b"c", b"c1").unwrap();
+        let mut w4 = data_file_at(dir, 4);
+        w4.write_entry(b"a", b"a2").unwrap();
+        w4.write_tombstone(b"c").unwrap();
+        w4.write_entry(b"d", b"d1").unwrap();
+        drop((w3, w4));
+
+        let seq3 = dir.join("data-0000000000000003.claw");
+        let seq4 = dir.join("data-0000000000000004.claw");
+        let (merged, result) = merge_files_to(&[&seq4, &seq3], dir).unwrap();
+
+        assert_eq!(merged, dir.join("data-0000000000000005.claw"));
+        assert!(!seq3.exists() && !seq4.exists());
+        assert_eq!(result.source_files, vec![seq3, seq4]);
+        assert_eq!(result.original_entries, 6);
+        assert_eq!(result.live_entries, 3);
+
+        let entries = DataFileReader::scan_all(&merged).unwrap();
+        let pairs: Vec<(&[u8], &[u8])> = entries.iter()
+            .map(|e| (e.key.as_slice(), e.value.as_slice()))
+            .collect();
+        assert_eq!(pairs, vec![(&b"a"[..], &b"a2"[..]), (b"b", b"b1"), (b"d", b"d1")]);
+        assert_eq!(latest_data_file(dir), Some(merged));
+    }
+
+    #[test]
+    fn test_merge_prefers_higher_entry_sequence() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let counter = Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+        // Two concurrent writers: the later file holds the older value of "k"
+        let mut w1 = data_file_at(dir, 1);
+        let mut w2 = data_file_at(dir, 2);
+        w1.set_sequence_source(Arc::clone(&counter));
+        w2.set_sequence_source(Arc::clone(&counter));
+        w2.write_entry(b"k", b"old").unwrap();
+        w1.write_entry(b"x", b"1").unwrap();
+        w1.write_entry(b"k", b"new").unwrap();
+        w2.write_tombstone(b"x").unwrap();
+        drop((w1, w2));
+
+        let seq1 = dir.join("data-0000000000000001.claw");
+        let seq2 = dir.join("data-0000000000000002.claw");
+        let sequences: Vec<u64> = DataFileReader::scan_all(&seq1).unwrap()
+            .iter().map(|e| e.sequence_number).collect();
+        assert_eq!(sequences, vec![2, 3]);
+        assert_eq!(DataFileReader::max_sequence_number(&seq2).unwrap(), 4);
+
+        let (merged, result) = merge_files_to(&[&seq1, &seq2], dir).unwrap();
+        assert_eq!(result.live_entries, 1);
+        let entries = DataFileReader::scan_all(&merged).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].key.as_slice(), entries[0].value.as_slice()), (&b"k"[..], &b"new"[..]));
+        // The merged file keeps the winning entry's sequence number
+        assert_eq!(entries[0].sequence_number, 3);
+    }
+
+    fn write_dead_file(dir: &Path) {
+        let mut writer = DataFileWriter::new(dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(b"k", b"v1").unwrap();
+        writer.write_entry(b"k", b"v2").unwrap();
+        writer.write_tombstone(b"gone").unwrap();
+    }
+
+    #[test]
+    fn test_compact_directory_skips_recent_files() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        write_dead_file(&dir);
+
+        let opts = CompactionOptions {
+            threshold: 0.1,
+            min_age_secs: 3600,
+            max_files_per_run: 10,
+        };
+        let results = compact_directory_with_options(&dir, &opts).unwrap();
+        assert!(results.is_empty(), "Freshly written file must not be compacted");
+
+        // Same file is compacted once the age guard is lifted
+        let opts = CompactionOptions { min_age_secs: 0, ..opts };
+        let results = compact_directory_with_options(&dir, &opts).unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[test]
+    fn test_compact_directory_max_files_per_run() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        for _ in 0..3 {
+            write_dead_file(&dir); // each writer opens a new sequence
+        }
+
+        let opts = CompactionOptions {
+            threshold: 0.1,
+            min_age_secs: 0,
+            max_files_per_run: 2,
+        };
+        let results = compact_directory_with_options(&dir, &opts).unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    /// New data file with `total` entries, `dead` of them stale overwrites.
+    fn write_file_with_dead(dir: &Path, total: usize, dead: usize) -> PathBuf {
+        let mut writer = DataFileWriter::new(dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        for i in 0..=dead {
+            writer.write_entry(b"dup", format!("v{i}").as_bytes()).unwrap();
+        }
+        for i in 0..total - dead - 1 {
+            writer.write_entry(format!("live-{i}").as_bytes(), b"v").unwrap();
+        }
+        latest_data_file(dir).unwrap()
+    }
+
+    #[test]
+    fn test_ranked_compaction_candidates_worst_first() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let half = write_file_with_dead(&dir, 4, 2);
+        let clean = write_file_with_dead(&dir, 4, 0);
+        let most = write_file_with_dead(&dir, 4, 3);
+        let fifth = write_file_with_dead(&dir, 5, 1);
+        let quarter = write_file_with_dead(&dir, 4, 1);
+
+        let ranked = ranked_compaction_candidates(&dir, 0.0, 10).unwrap();
+        let paths: Vec<&PathBuf> = ranked.iter().map(|(p, _)| p).collect();
+        assert_eq!(paths, vec![&most, &half, &quarter, &fifth, &clean]);
+        for ((_, ratio), expected) in ranked.iter().zip([0.75, 0.5, 0.25, 0.2, 0.0]) {
+            assert!((ratio - expected).abs() < 1e-9, "{ratio} != {expected}");
+        }
+
+        // Threshold filters, max_count keeps the worst
+        assert_eq!(ranked_compaction_candidates(&dir, 0.3, 10).unwrap().len(), 2);
+        let top: Vec<PathBuf> = ranked_compaction_candidates(&dir, 0.0, 3).unwrap()
+            .into_iter().map(|(p, _)| p).collect();
+        assert_eq!(top, vec![most.clone(), half.clone(), quarter.clone()]);
+
+        // A one-file run compacts the worst file, not the first one
+        let opts = CompactionOptions { threshold: 0.1, min_age_secs: 0, max_files_per_run: 1 };
+        let results = compact_directory_with_options(&dir, &opts).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_path, most);
+
+        // compact_top_n ignores the threshold but skips clean files
+        let results = compact_top_n(&dir, 10).unwrap();
+        let compacted: Vec<&PathBuf> = results.iter().map(|r| &r.file_path).collect();
+        assert_eq!(compacted, vec![&half, &quarter, &fifth]);
+        assert!(ranked_compaction_candidates(&dir, f64::MIN_POSITIVE, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scheduler_compacts_strategy_picks() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let half = write_file_with_dead(&dir, 4, 2);
+        let quarter = write_file_with_dead(&dir, 4, 1);
+        let newest = write_file_with_dead(&dir, 4, 3);
+
+        let strategy = ThresholdStrategy { ratio: 0.4 };
+        assert_eq!(strategy.should_compact(&dir).unwrap(), vec![newest.clone(), half.clone()]);
+        let strategy = TimeBasedStrategy { min_age: Duration::ZERO, max_files: 2 };
+        assert_eq!(strategy.should_compact(&dir).unwrap(), vec![newest.clone(), half.clone()]);
+        let strategy = TimeBasedStrategy { min_age: Duration::from_secs(3600), max_files: 2 };
+        assert!(strategy.should_compact(&dir).unwrap().is_empty());
+
+        let scheduler = CompactionScheduler::new(
+            dir.clone(),
+            Box::new(ThresholdStrategy { ratio: 0.1 }),
+            Duration::from_millis(200),
+        );
+        let handle = scheduler.start().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let results = loop {
+            if let Some(results) = handle.last_result() {
+                break results;
+            }
+            assert!(Instant::now() < deadline, "scheduler never ran");
+            thread::sleep(Duration::from_millis(5));
+        };
+        handle.stop();
+
+        // The newest file is left alone despite having the most dead space
+        let compacted: Vec<&PathBuf> = results.iter().map(|r| &r.file_path).collect();
+        assert_eq!(compacted, vec![&half, &quarter]);
+        assert!(!needs_compaction(&half, 0.1).unwrap());
+        assert!(needs_compaction(&newest, 0.1).unwrap());
+    }
+
+    #[test]
+    fn test_scheduler_keeps_tombstones_shadowing_older_files() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(b"gone", b"old").unwrap();
+        writer.write_entry(b"dropped", b"v").unwrap();
+        writer.write_tombstone(b"dropped").unwrap();
+        let oldest = writer.force_rotate().unwrap();
+        writer.write_entry(b"gone", b"newer").unwrap();
+        writer.write_tombstone(b"gone").unwrap();
+        writer.write_entry(b"k", b"v").unwrap();
+        let shadowing = writer.force_rotate().unwrap();
+        writer.write_entry(b"latest", b"v").unwrap();
+        drop(writer);
+
+        let scheduler = CompactionScheduler::new(
+            dir.clone(),
+            Box::new(ThresholdStrategy { ratio: 0.1 }),
+            Duration::from_secs(3600),
+        );
+        let results = scheduler.run_once().unwrap();
+        let compacted: Vec<&PathBuf> = results.iter().map(|r| &r.file_path).collect();
+        assert_eq!(compacted, vec![&oldest, &shadowing]);
+
+        // The oldest file has nothing below it to shadow
+        let keys: Vec<Vec<u8>> = DataFileReader::scan_all(&oldest).unwrap().into_iter().map(|e| e.key).collect();
+        assert_eq!(keys, vec![b"gone".to_vec()]);
+        // The later file keeps its tombstone over the oldest file's value
+        let tombstones: Vec<Vec<u8>> = DataFileReader::scan_all(&shadowing).unwrap()
+            .into_iter()
+            .filter(|e| e.is_tombstone)
+            .map(|e| e.key)
+            .collect();
+        assert_eq!(tombstones, vec![b"gone".to_vec()]);
+
+        // Kept tombstones still count as dead space, but are not rewritten again
+        assert!(scheduler.run_once().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scheduler_skips_files_held_by_writers() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        // Two writers appending to their own files, as parallel trickle workers do
+        let mut first = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        let mut second = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        for writer in [&mut first, &mut second] {
+            writer.write_entry(b"dup", b"v1").unwrap();
+            writer.write_entry(b"dup", b"v2").unwrap();
+        }
+        let held = list_data_files(&dir).unwrap().remove(0);
+        assert!(matches!(
+            DataFileWriter::open_existing(&held, DEFAULT_MAX_DATA_FILE_SIZE),
+            Err(ClawError::Io { kind: std::io::ErrorKind::WouldBlock, .. })
+        ));
+
+        let scheduler = CompactionScheduler::new(
+            dir.clone(),
+            Box::new(ThresholdStrategy { ratio: 0.1 }),
+            Duration::from_secs(3600),
+        );
+        assert!(scheduler.run_once().unwrap().is_empty());
+
+        // Once the writer lets go, the file is compacted and can be reopened
+        drop(first);
+        let compacted: Vec<PathBuf> = scheduler.run_once().unwrap().into_iter().map(|r| r.file_path).collect();
+        assert_eq!(compacted, vec![held.clone()]);
+        DataFileWriter::open_existing(&held, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        drop(second);
+    }
 }
@@ -3,8 +3,23 @@
 //! Provides memory tier presets for different hardware classes
 //! and a builder for custom configurations.
 
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::datafile::DEFAULT_MAX_DATA_FILE_SIZE;
+use crate::error::{ClawError, ClawResult};
+use crate::platform_durability::{available_space, durable_sync, total_memory, try_lock_exclusive};
+use crate::trickle::TrickleMode;
+use crate::wal::WalRecoveryMode;
+
+/// Advisory lock file name inside a store directory
+pub const LOCK_FILE_NAME: &str = "clawstore.lock";
+
 /// ClawStore configuration with memory tier presets
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -14,14 +29,40 @@ pub struct Config {
     pub max_snapshot_ttl_secs: u64,
     /// WAL file rotation threshold (bytes)
     pub wal_rotation_size_bytes: u64,
+    /// Data file rotation threshold (bytes)
+    pub max_data_file_size_bytes: u64,
+    /// Pad trickle-flushed data file entries to 4096-byte sector boundaries
+    pub sector_aligned_writes: bool,
     /// Compaction trigger: compact when dead space ratio exceeds this
     pub compaction_trigger_ratio: f64,
-    /// Background trickle flush cadence
-    pub trickle_cadence: Duration,
+    /// Background trickle flush cadence, fixed or driven by the dirty key count
+    pub trickle_mode: TrickleMode,
+    /// Trickle threads, each flushing its own key partition to its own data file
+    pub trickle_workers: usize,
     /// Maximum key size in bytes
     pub max_key_size: usize,
     /// Maximum value size in bytes
     pub max_value_size: usize,
+    /// AES-256-GCM key for WAL entries at rest (None = plaintext WAL)
+    pub encryption_key: Option<[u8; 32]>,
+    /// Load data files into RAM on open, before WAL replay
+    pub warm_on_open: bool,
+    /// Read back and checksum each trickle cycle's entries after writing them
+    pub verify_writes_after_flush: bool,
+    /// Append a line per get/put/delete to this file (None = no access log)
+    pub access_log: Option<PathBuf>,
+    /// Cap RAM at this many keys, evicting the least recently used on `put`
+    /// (None = unbounded)
+    pub cache_mode: Option<usize>,
+    /// How WAL replay on open handles corrupted entries
+    pub wal_recovery_mode: WalRecoveryMode,
+    /// Idle read transactions kept by adapters that pool them (0 = no pooling)
+    pub read_tx_pool_size: usize,
+    /// Table names whose sorted snapshots pooled read transactions keep warm
+    pub pre_warm_tables: Vec<String>,
+    /// Keep RAM in a sharded `DashMap` so `get` takes no engine-wide lock
+    /// (a `get` may then see part of a batch write; multi-key reads don't)
+    pub use_concurrent_hashmap: bool,
 }
 
 impl Config {
@@ -31,10 +72,22 @@ impl Config {
             max_snapshot_memory_bytes: 39 * 1024 * 1024 * 1024,
             max_snapshot_ttl_secs: 3600,
             wal_rotation_size_bytes: 100 * 1024 * 1024,
+            max_data_file_size_bytes: DEFAULT_MAX_DATA_FILE_SIZE,
+            sector_aligned_writes: false,
             compaction_trigger_ratio: 0.3,
-            trickle_cadence: Duration::from_secs(12),
+            trickle_mode: TrickleMode::Fixed(Duration::from_secs(12)),
+            trickle_workers: 1,
             max_key_size: 128,
             max_value_size: 32 * 1024 * 1024,
+            encryption_key: None,
+            warm_on_open: false,
+            verify_writes_after_flush: false,
+            access_log: None,
+            cache_mode: None,
+            wal_recovery_mode: WalRecoveryMode::Lenient,
+            read_tx_pool_size: 8,
+            pre_warm_tables: Vec::new(),
+            use_concurrent_hashmap: false,
         }
     }
 
@@ -44,10 +97,22 @@ impl Config {
             max_snapshot_memory_bytes: 1536 * 1024 * 1024,
             max_snapshot_ttl_secs: 1800,
             wal_rotation_size_bytes: 50 * 1024 * 1024,
+            max_data_file_size_bytes: DEFAULT_MAX_DATA_FILE_SIZE,
+            sector_aligned_writes: false,
             compaction_trigger_ratio: 0.25,
-            trickle_cadence: Duration::from_secs(15),
+            trickle_mode: TrickleMode::Fixed(Duration::from_secs(15)),
+            trickle_workers: 1,
             max_key_size: 128,
             max_value_size: 16 * 1024 * 1024,
+            encryption_key: None,
+            warm_on_open: false,
+            verify_writes_after_flush: false,
+            access_log: None,
+            cache_mode: None,
+            wal_recovery_mode: WalRecoveryMode::Lenient,
+            read_tx_pool_size: 4,
+            pre_warm_tables: Vec::new(),
+            use_concurrent_hashmap: false,
         }
     }
 
@@ -57,13 +122,80 @@ impl Config {
             max_snapshot_memory_bytes: 400 * 1024 * 1024,
             max_snapshot_ttl_secs: 900,
             wal_rotation_size_bytes: 25 * 1024 * 1024,
+            max_data_file_size_bytes: DEFAULT_MAX_DATA_FILE_SIZE,
+            sector_aligned_writes: false,
             compaction_trigger_ratio: 0.2,
-            trickle_cadence: Duration::from_secs(20),
+            trickle_mode: TrickleMode::Fixed(Duration::from_secs(20)),
+            trickle_workers: 1,
             max_key_size: 64,
             max_value_size: 8 * 1024 * 1024,
+            encryption_key: None,
+            warm_on_open: false,
+            verify_writes_after_flush: false,
+            access_log: None,
+            cache_mode: None,
+            wal_recovery_mode: WalRecoveryMode::Lenient,
+            read_tx_pool_size: 2,
+            pre_warm_tables: Vec::new(),
+            use_concurrent_hashmap: false,
+        }
+    }
+
+    /// Pick a preset from total system RAM: `budget` below 8GB, `phone`
+    /// from 8GB to 32GB, `server` above 32GB.
+    ///
+    /// Falls back to `phone` (with a warning) if RAM can't be detected.
+    pub fn auto_detect() -> Self {
+        Self::for_memory(detect_memory(total_memory))
+    }
+
+    /// `auto_detect`, with `max_snapshot_memory_bytes` set to `fraction`
+    /// (in (0, 1]) of the detected RAM. Without detection the fallback
+    /// preset's snapshot budget is kept.
+    pub fn auto_detect_with_ratio(fraction: f64) -> Self {
+        Self::auto_detect_with_ratio_using(fraction, total_memory)
+    }
+
+    /// `auto_detect_with_ratio` with an injectable memory probe (for tests).
+    fn auto_detect_with_ratio_using(fraction: f64, probe: impl Fn() -> io::Result<u64>) -> Self {
+        let ram = detect_memory(probe);
+        let mut config = Self::for_memory(ram);
+        if let Some(ram) = ram {
+            config.max_snapshot_memory_bytes = (ram as f64 * fraction) as u64;
+        }
+        config
+    }
+
+    /// Preset for a machine with `ram` bytes (`phone` if unknown).
+    fn for_memory(ram: Option<u64>) -> Self {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        match ram {
+            Some(bytes) if bytes < 8 * GIB => Self::budget(),
+            Some(bytes) if bytes > 32 * GIB => Self::server(),
+            _ => Self::phone(),
+        }
+    }
+
+    /// Fast settings for tests: 50ms trickle cadence, the smallest WAL and
+    /// data file sizes `validate` accepts (1MB), a 0.1 compaction trigger
+    /// and `warm_on_open`. Everything else comes from `Config::default()`.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn for_testing() -> Self {
+        Self {
+            wal_rotation_size_bytes: 1024 * 1024,
+            max_data_file_size_bytes: 1024 * 1024,
+            compaction_trigger_ratio: 0.1,
+            trickle_mode: TrickleMode::Fixed(Duration::from_millis(50)),
+            warm_on_open: true,
+            ..Self::default()
         }
     }
 
+    /// Start a builder from the server preset.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
     /// Validate all configuration parameters
     pub fn validate(&self) -> Result<(), String> {
         if self.max_snapshot_memory_bytes == 0 {
@@ -75,11 +207,15 @@ impl Config {
         if self.wal_rotation_size_bytes < 1024 * 1024 {
             return Err("wal_rotation_size_bytes must be >= 1MB".into());
         }
+        if self.max_data_file_size_bytes < 1024 * 1024 || self.max_data_file_size_bytes > 16 * 1024 * 1024 * 1024 {
+            return Err("max_data_file_size_bytes must be in [1MB, 16GB]".into());
+        }
         if self.compaction_trigger_ratio <= 0.0 || self.compaction_trigger_ratio >= 1.0 {
             return Err("compaction_trigger_ratio must be in (0.0, 1.0)".into());
         }
-        if self.trickle_cadence.as_millis() == 0 {
-            return Err("trickle_cadence must be > 0".into());
+        self.trickle_mode.validate()?;
+        if self.trickle_workers == 0 || self.trickle_workers > u8::MAX as usize {
+            return Err("trickle_workers must be in [1, 255]".into());
         }
         if self.max_key_size == 0 || self.max_key_size > 1024 {
             return Err("max_key_size must be in [1, 1024]".into());
@@ -87,14 +223,220 @@ impl Config {
         if self.max_value_size == 0 || self.max_value_size > 128 * 1024 * 1024 {
             return Err("max_value_size must be in [1, 128MB]".into());
         }
+        if self.cache_mode == Some(0) {
+            return Err("cache_mode must be > 0 when set".into());
+        }
+        Ok(())
+    }
+
+    /// Pre-flight checks for a store directory before `ClawStoreEngine::open`.
+    ///
+    /// Verifies that the directory exists or can be created, that a probe
+    /// file can be written and durably synced, that free space covers one
+    /// WAL segment plus one data file, and that no other process holds the
+    /// `clawstore.lock` advisory lock.
+    pub fn validate_path(&self, path: &Path) -> ClawResult<()> {
+        self.validate_path_with(path, available_space)
+    }
+
+    /// `validate_path` with an injectable free-space probe (for tests).
+    fn validate_path_with(
+        &self,
+        path: &Path,
+        free_space: impl Fn(&Path) -> io::Result<u64>,
+    ) -> ClawResult<()> {
+        let not_writable = |e: io::Error| ClawError::PathNotWritable {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        };
+
+        // 1. Directory exists or can be created
+        std::fs::create_dir_all(path).map_err(not_writable)?;
+
+        // 2. Writable and supports durable_sync
+        let probe_path = path.join(".clawstore-probe");
+        let mut probe = File::create(&probe_path).map_err(not_writable)?;
+        probe.write_all(b"CLAW").map_err(not_writable)?;
+        let synced = durable_sync(&probe);
+        drop(probe);
+        let _ = std::fs::remove_file(&probe_path);
+        synced.map_err(|e| ClawError::DurableSyncUnsupported {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+
+        // 3. Room for a full WAL segment and a full data file
+        let required_bytes = self.wal_rotation_size_bytes + self.max_data_file_size_bytes;
+        let available_bytes = free_space(path).map_err(|e| ClawError::Io {
+            path: Some(path.to_path_buf()),
+            kind: e.kind(),
+            message: format!("Failed to query free disk space: {}", e),
+            inner: Some(Arc::new(e)),
+        })?;
+        if available_bytes < required_bytes {
+            return Err(ClawError::InsufficientDiskSpace {
+                path: path.to_path_buf(),
+                available_bytes,
+                required_bytes,
+            });
+        }
+
+        // 4. Not locked by another process (lock released when file drops)
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.join(LOCK_FILE_NAME))
+            .map_err(not_writable)?;
+        if !try_lock_exclusive(&lock_file).map_err(not_writable)? {
+            return Err(ClawError::DirectoryLocked { path: path.to_path_buf() });
+        }
+
         Ok(())
     }
 }
 
+/// Total RAM from `probe`, logging a warning and returning `None` on failure.
+fn detect_memory(probe: impl Fn() -> io::Result<u64>) -> Option<u64> {
+    match probe() {
+        Ok(bytes) if bytes > 0 => Some(bytes),
+        Ok(_) => {
+            eprintln!("[ClawStore] Detected 0 bytes of RAM, falling back to the phone preset");
+            None
+        }
+        Err(e) => {
+            eprintln!("[ClawStore] RAM detection failed ({}), falling back to the phone preset", e);
+            None
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self { Self::server() }
 }
 
+/// Errors from [`ConfigBuilder::build`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// An override environment variable could not be parsed
+    EnvVarParse {
+        /// Full variable name, including the prefix
+        var: String,
+        /// Raw value found in the environment
+        value: String,
+        /// Why parsing failed
+        reason: String,
+    },
+    /// The resulting configuration failed `Config::validate`
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::EnvVarParse { var, value, reason } => {
+                write!(f, "Invalid value {:?} for {}: {}", value, var, reason)
+            }
+            ConfigError::Invalid(reason) => write!(f, "Invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Builds a [`Config`] from a base preset plus optional environment overrides.
+///
+/// With an env prefix of `CLAWSTORE`, `build` reads these variables and
+/// applies any that are set on top of the base preset:
+///
+/// | Variable                          | Field                       |
+/// |-----------------------------------|-----------------------------|
+/// | `CLAWSTORE_TRICKLE_CADENCE_MS`    | `trickle_mode` (fixed)      |
+/// | `CLAWSTORE_WAL_ROTATION_MB`       | `wal_rotation_size_bytes`   |
+/// | `CLAWSTORE_DATA_FILE_MB`          | `max_data_file_size_bytes`  |
+/// | `CLAWSTORE_COMPACTION_RATIO`      | `compaction_trigger_ratio`  |
+/// | `CLAWSTORE_SNAPSHOT_MEMORY_MB`    | `max_snapshot_memory_bytes` |
+/// | `CLAWSTORE_SNAPSHOT_TTL_SECS`     | `max_snapshot_ttl_secs`     |
+/// | `CLAWSTORE_MAX_KEY_SIZE`          | `max_key_size`              |
+/// | `CLAWSTORE_MAX_VALUE_SIZE`        | `max_value_size`            |
+///
+/// The encryption key is never read from the environment.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    base: Config,
+    env_prefix: Option<String>,
+}
+
+impl ConfigBuilder {
+    /// Builder that applies `{prefix}_*` environment overrides on `build`.
+    pub fn from_env_prefix(prefix: &str) -> Self {
+        Self { base: Config::default(), env_prefix: Some(prefix.to_string()) }
+    }
+
+    /// Replace the base configuration (e.g. `Config::phone()`).
+    pub fn base(mut self, config: Config) -> Self {
+        self.base = config;
+        self
+    }
+
+    /// Apply environment overrides to the base and validate the result.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let mut config = self.base;
+
+        if let Some(prefix) = &self.env_prefix {
+            if let Some(ms) = env_override::<u64>(prefix, "TRICKLE_CADENCE_MS")? {
+                config.trickle_mode = TrickleMode::Fixed(Duration::from_millis(ms));
+            }
+            if let Some(mb) = env_override::<u64>(prefix, "WAL_ROTATION_MB")? {
+                config.wal_rotation_size_bytes = mb * 1024 * 1024;
+            }
+            if let Some(mb) = env_override::<u64>(prefix, "DATA_FILE_MB")? {
+                config.max_data_file_size_bytes = mb * 1024 * 1024;
+            }
+            if let Some(ratio) = env_override::<f64>(prefix, "COMPACTION_RATIO")? {
+                config.compaction_trigger_ratio = ratio;
+            }
+            if let Some(mb) = env_override::<u64>(prefix, "SNAPSHOT_MEMORY_MB")? {
+                config.max_snapshot_memory_bytes = mb * 1024 * 1024;
+            }
+            if let Some(secs) = env_override::<u64>(prefix, "SNAPSHOT_TTL_SECS")? {
+                config.max_snapshot_ttl_secs = secs;
+            }
+            if let Some(size) = env_override::<usize>(prefix, "MAX_KEY_SIZE")? {
+                config.max_key_size = size;
+            }
+            if let Some(size) = env_override::<usize>(prefix, "MAX_VALUE_SIZE")? {
+                config.max_value_size = size;
+            }
+        }
+
+        config.validate().map_err(ConfigError::Invalid)?;
+        Ok(config)
+    }
+}
+
+/// Read and parse `{prefix}_{name}`; `Ok(None)` if the variable is unset.
+fn env_override<T>(prefix: &str, name: &str) -> Result<Option<T>, ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let var = format!("{}_{}", prefix, name);
+    match std::env::var(&var) {
+        Ok(value) => value.trim().parse().map(Some).map_err(|e: T::Err| ConfigError::EnvVarParse {
+            var,
+            value,
+            reason: e.to_string(),
+        }),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(raw)) => Err(ConfigError::EnvVarParse {
+            var,
+            value: raw.to_string_lossy().into_owned(),
+            reason: "not valid UTF-8".to_string(),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +456,111 @@ mod tests {
         assert!(s.max_snapshot_memory_bytes > p.max_snapshot_memory_bytes);
         assert!(p.max_snapshot_memory_bytes > b.max_snapshot_memory_bytes);
     }
+
+    // Each env test uses its own prefix: tests share one process environment.
+    #[test]
+    fn test_auto_detect_preset_boundaries() {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        let preset_for = |bytes: u64| {
+            Config::auto_detect_with_ratio_using(0.5, move || Ok(bytes)).max_snapshot_ttl_secs
+        };
+        assert_eq!(preset_for(4 * GIB), Config::budget().max_snapshot_ttl_secs);
+        assert_eq!(preset_for(8 * GIB - 1), Config::budget().max_snapshot_ttl_secs);
+        assert_eq!(preset_for(8 * GIB), Config::phone().max_snapshot_ttl_secs);
+        assert_eq!(preset_for(32 * GIB), Config::phone().max_snapshot_ttl_secs);
+        assert_eq!(preset_for(32 * GIB + 1), Config::server().max_snapshot_ttl_secs);
+        assert_eq!(preset_for(256 * GIB), Config::server().max_snapshot_ttl_secs);
+
+        let config = Config::auto_detect_with_ratio_using(0.25, || Ok(16 * GIB));
+        assert_eq!(config.max_snapshot_memory_bytes, 4 * GIB);
+        assert!(config.validate().is_ok());
+
+        // Detection failure: phone preset, untouched snapshot budget
+        let failed = Config::auto_detect_with_ratio_using(0.25, || {
+            Err(io::Error::new(io::ErrorKind::Unsupported, "no meminfo"))
+        });
+        assert_eq!(failed.max_snapshot_memory_bytes, Config::phone().max_snapshot_memory_bytes);
+        assert!(Config::auto_detect().validate().is_ok());
+    }
+
+    #[test]
+    fn test_builder_env_overrides() {
+        std::env::set_var("CLAWTEST_OVR_TRICKLE_CADENCE_MS", "250");
+        std::env::set_var("CLAWTEST_OVR_WAL_ROTATION_MB", "8");
+        std::env::set_var("CLAWTEST_OVR_COMPACTION_RATIO", "0.5");
+
+        let config = ConfigBuilder::from_env_prefix("CLAWTEST_OVR")
+            .base(Config::phone())
+            .build()
+            .unwrap();
+        assert_eq!(config.trickle_mode, TrickleMode::Fixed(Duration::from_millis(250)));
+        assert_eq!(config.wal_rotation_size_bytes, 8 * 1024 * 1024);
+        assert_eq!(config.compaction_trigger_ratio, 0.5);
+        // Unset variables fall through to the base preset
+        assert_eq!(config.max_value_size, Config::phone().max_value_size);
+        assert_eq!(config.max_snapshot_ttl_secs, Config::phone().max_snapshot_ttl_secs);
+    }
+
+    #[test]
+    fn test_builder_env_parse_error() {
+        std::env::set_var("CLAWTEST_BAD_TRICKLE_CADENCE_MS", "fast");
+        let err = ConfigBuilder::from_env_prefix("CLAWTEST_BAD").build().unwrap_err();
+        match err {
+            ConfigError::EnvVarParse { var, value, .. } => {
+                assert_eq!(var, "CLAWTEST_BAD_TRICKLE_CADENCE_MS");
+                assert_eq!(value, "fast");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_builder_without_env_matches_default() {
+        let config = Config::builder().build().unwrap();
+        assert_eq!(config.wal_rotation_size_bytes, Config::default().wal_rotation_size_bytes);
+    }
+
+    #[test]
+    fn test_validate_path_ok() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = dir.path().join("store");
+        assert!(Config::default().validate_path(&store).is_ok());
+        assert!(store.is_dir());
+    }
+
+    #[test]
+    fn test_validate_path_insufficient_space() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let result = Config::default().validate_path_with(dir.path(), |_| Ok(1024));
+        assert!(matches!(
+            result,
+            Err(ClawError::InsufficientDiskSpace { available_bytes: 1024, .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_path_locked() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let held = File::create(dir.path().join(LOCK_FILE_NAME)).unwrap();
+        assert!(try_lock_exclusive(&held).unwrap());
+        assert!(matches!(
+            Config::default().validate_path(dir.path()),
+            Err(ClawError::DirectoryLocked { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_path_read_only() {
+        use std::os::unix::fs::PermissionsExt;
+        // Permission bits are not enforced for root
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+        let result = Config::default().validate_path(dir.path());
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(matches!(result, Err(ClawError::PathNotWritable { .. })));
+    }
 }
@@ -3,24 +3,78 @@
 //! Data files store key-value pairs flushed from RAM by the trickle engine.
 //! Each entry has a CRC32C checksum for silent SSD corruption detection (bit rot).
 //!
-//! File format: DataChunkHeader (24 bytes) + key_bytes + value_bytes
+//! File format: DataChunkHeader (24 bytes) + key_bytes + value_bytes [+ zero padding]
+//!
+//! Padding is only present after sector-aligned writes; its length is in
+//! the header so readers step over it.
 
+use std::collections::VecDeque;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
 
-use crate::error::{ClawError, ClawResult};
+use crate::error::{ClawError, ClawResult, ClawResultExt};
 use crate::format::{MAGIC_ARRAY, MAX_KEY_SIZE, MAX_VALUE_SIZE};
 use crate::platform_durability::durable_sync;
 
 /// Data chunk header size in bytes
-const DATA_HEADER_SIZE: usize = 24;
+pub(crate) const DATA_HEADER_SIZE: usize = 24;
 
 /// Tombstone flag in the flags byte
 const FLAG_TOMBSTONE: u8 = 0x01;
 
-/// Maximum data file size before rotation (256MB)
-const MAX_DATA_FILE_SIZE: u64 = 256 * 1024 * 1024;
+/// Default data file size before rotation (256MB)
+pub(crate) const DEFAULT_MAX_DATA_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Smallest number of recent entry offsets kept for `verify_last_n_entries`
+const MIN_VERIFY_WINDOW: usize = 100;
+
+/// Boundary that sector-aligned writes pad entries to
+pub const SECTOR_SIZE: u64 = 4096;
+
+/// Largest entry sequence number the header can hold (56 bits)
+pub const MAX_ENTRY_SEQUENCE: u64 = (1 << 56) - 1;
+
+/// Data files claimed through `DataFileClaim` in this process
+static CLAIMED_DATA_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// In-process claim on one data file, released on drop.
+///
+/// A `DataFileWriter` claims each file it appends to, and
+/// `CompactionScheduler` claims a file before rewriting it, so a scheduled
+/// compaction never renames a file out from under a writer (e.g. one of
+/// several trickle workers) that still holds it open.
+pub(crate) struct DataFileClaim {
+    path: PathBuf,
+}
+
+impl DataFileClaim {
+    /// Claim `path`, or `None` if it is already claimed.
+    pub(crate) fn try_claim(path: &Path) -> Option<Self> {
+        // The directory always exists, the file may not yet; resolve the
+        // directory so differently spelled paths to one file match
+        let path = match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) => dir.canonicalize().map_or_else(|_| path.to_path_buf(), |dir| dir.join(name)),
+            _ => path.to_path_buf(),
+        };
+        let mut claimed = CLAIMED_DATA_FILES.lock();
+        if claimed.contains(&path) {
+            return None;
+        }
+        claimed.push(path.clone());
+        Some(Self { path })
+    }
+}
+
+impl Drop for DataFileClaim {
+    fn drop(&mut self) {
+        CLAIMED_DATA_FILES.lock().retain(|claimed| *claimed != self.path);
+    }
+}
 
 /// Data chunk header for on-disk entries.
 /// Size: 24 bytes, alignment: 4
@@ -31,8 +85,14 @@ const MAX_DATA_FILE_SIZE: u64 = 256 * 1024 * 1024;
 ///   [6..10]  value_len: u32 LE
 ///   [10..14] checksum:  u32 LE - CRC32C of (key_bytes + value_bytes)
 ///   [14]     flags:     u8     - bit 0 = tombstone
-///   [15..18] reserved:  [u8;3]
-///   [18..24] padding:   [u8;6]
+///   [15..17] pad_len:   u16 LE - zero bytes following the value (sector alignment)
+///   [17..24] sequence:  u56 LE - global write order across files, 0 = not recorded
+///
+/// `pad_len` takes two of the formerly reserved bytes: padding to a 4096-byte
+/// boundary can be up to 4095 bytes. `sequence_number` takes the remaining
+/// seven, so it is limited to `MAX_ENTRY_SEQUENCE`. Files written before
+/// either have 0 there, and older builds ignore both, so no format version
+/// change was needed. The checksum does not cover the header.
 #[derive(Debug, Clone, Copy)]
 struct DataChunkHeader {
     magic: [u8; 4],
@@ -40,11 +100,13 @@ struct DataChunkHeader {
     value_len: u32,
     checksum: u32,
     flags: u8,
+    pad_len: u16,
+    sequence_number: u64,
 }
 
 impl DataChunkHeader {
     fn new(key_len: u16, value_len: u32, checksum: u32, flags: u8) -> Self {
-        Self { magic: MAGIC_ARRAY, key_len, value_len, checksum, flags }
+        Self { magic: MAGIC_ARRAY, key_len, value_len, checksum, flags, pad_len: 0, sequence_number: 0 }
     }
 
     fn to_bytes(&self) -> [u8; DATA_HEADER_SIZE] {
@@ -54,7 +116,8 @@ impl DataChunkHeader {
         buf[6..10].copy_from_slice(&self.value_len.to_le_bytes());
         buf[10..14].copy_from_slice(&self.checksum.to_le_bytes());
         buf[14] = self.flags;
-        // bytes 15..24 are reserved/padding, already zero
+        buf[15..17].copy_from_slice(&self.pad_len.to_le_bytes());
+        buf[17..24].copy_from_slice(&(self.sequence_number & MAX_ENTRY_SEQUENCE).to_le_bytes()[..7]);
         buf
     }
 
@@ -67,12 +130,24 @@ impl DataChunkHeader {
             value_len: u32::from_le_bytes([buf[6], buf[7], buf[8], buf[9]]),
             checksum: u32::from_le_bytes([buf[10], buf[11], buf[12], buf[13]]),
             flags: buf[14],
+            pad_len: u16::from_le_bytes([buf[15], buf[16]]),
+            sequence_number: u64::from_le_bytes([buf[17], buf[18], buf[19], buf[20], buf[21], buf[22], buf[23], 0]),
         }
     }
 
     fn is_tombstone(&self) -> bool {
         (self.flags & FLAG_TOMBSTONE) != 0
     }
+
+    /// Header + key + value, without trailing padding.
+    fn entry_len(&self) -> u64 {
+        DATA_HEADER_SIZE as u64 + self.key_len as u64 + self.value_len as u64
+    }
+
+    /// Distance from this entry's start to the next entry's.
+    fn span(&self) -> u64 {
+        self.entry_len() + self.pad_len as u64
+    }
 }
 
 /// A data entry read from a data file.
@@ -82,6 +157,27 @@ pub struct DataEntry {
     pub value: Vec<u8>,
     pub offset: u64,
     pub is_tombstone: bool,
+    /// Global write order from the header, 0 if the writer recorded none
+    pub sequence_number: u64,
+}
+
+impl DataEntry {
+    /// True if this entry should replace `earlier`, an entry for the same key
+    /// seen before it in scan order: the higher sequence number wins, and
+    /// scan order decides when either has none.
+    pub fn supersedes(&self, earlier: &DataEntry) -> bool {
+        supersedes(self.sequence_number, earlier.sequence_number)
+    }
+
+    /// Key plus value bytes.
+    pub fn byte_size(&self) -> usize {
+        self.key.len() + self.value.len()
+    }
+
+    /// Bytes this entry occupies in a data file, header included.
+    pub fn total_on_disk_size(&self) -> usize {
+        DATA_HEADER_SIZE + self.byte_size()
+    }
 }
 
 /// Writes entries to data files with CRC32C checksums and durable sync.
@@ -91,68 +187,162 @@ pub struct DataFileWriter {
     size: u64,
     data_dir: PathBuf,
     sequence: u64,
+    /// Rotate to a new file once the current one would exceed this size
+    max_size: u64,
+    /// Pad every entry to a `SECTOR_SIZE` boundary (`Config::sector_aligned_writes`)
+    sector_aligned: bool,
+    /// File offset covered by the most recent `durable_sync`
+    last_flush_offset: u64,
+    /// Entries (tombstones included) written to the current file by this writer
+    entries_in_file: usize,
+    /// Counter stamping each entry's sequence number, shared by every writer
+    /// of one store (None = entries carry no sequence number)
+    sequence_source: Option<Arc<AtomicU64>>,
+    /// Offsets of the most recent entries in the current file, oldest first
+    recent_offsets: VecDeque<u64>,
+    /// How many offsets `recent_offsets` keeps
+    verify_window: usize,
+    /// Keeps scheduled compaction off the current file
+    _claim: DataFileClaim,
 }
 
 impl DataFileWriter {
     /// Create a new data file writer in the given directory.
-    pub fn new(data_dir: &Path) -> ClawResult<Self> {
-        std::fs::create_dir_all(data_dir).map_err(|e| ClawError::Io {
-            path: Some(data_dir.to_path_buf()),
-            kind: e.kind(),
-            message: format!("Failed to create data directory: {}", e),
-        })?;
+    ///
+    /// `max_size` is the rotation threshold, normally
+    /// `Config::max_data_file_size_bytes`.
+    pub fn new(data_dir: &Path, max_size: u64) -> ClawResult<Self> {
+        std::fs::create_dir_all(data_dir)
+            .with_context("Failed to create data directory")
+            .with_path(data_dir)?;
 
         // Find highest existing sequence
-        let mut max_seq = 0u64;
-        if let Ok(entries) = std::fs::read_dir(data_dir) {
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.starts_with("data-") && name.ends_with(".claw") {
-                        let hex = &name[5..name.len() - 5];
-                        if let Ok(seq) = u64::from_str_radix(hex, 16) {
-                            max_seq = max_seq.max(seq);
-                        }
-                    }
-                }
-            }
-        }
+        let max_seq = latest_data_file(data_dir)
+            .and_then(|p| p.file_name().and_then(|n| n.to_str()).and_then(parse_data_sequence))
+            .unwrap_or(0);
+
+        let (file, path, sequence, claim) = create_next_data_file(data_dir, max_seq + 1)
+            .with_context("Failed to open data file")?;
+
+        Ok(Self {
+            file, path, size: 0, data_dir: data_dir.to_path_buf(), sequence, max_size,
+            sector_aligned: false, last_flush_offset: 0, entries_in_file: 0,
+            sequence_source: None, recent_offsets: VecDeque::new(), verify_window: MIN_VERIFY_WINDOW,
+            _claim: claim,
+        })
+    }
 
-        let sequence = max_seq + 1;
-        let path = data_dir.join(format!("data-{:016x}.claw", sequence));
+    /// Re-open an existing data file and continue appending to it.
+    ///
+    /// The sequence number is parsed from the `data-{seq:016x}.claw` file
+    /// name, so later rotations continue from there. Fails with
+    /// `WouldBlock` if another writer or a scheduled compaction in this
+    /// process holds the file.
+    pub fn open_existing(path: &Path, max_size: u64) -> ClawResult<Self> {
+        let sequence = path.file_name()
+            .and_then(|n| n.to_str())
+            .and_then(parse_data_sequence)
+            .ok_or_else(|| ClawError::Io {
+                path: Some(path.to_path_buf()),
+                kind: std::io::ErrorKind::InvalidInput,
+                message: "Not a data file name (expected data-<seq>.claw)".to_string(),
+                inner: None,
+            })?;
+        let data_dir = path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+        let claim = DataFileClaim::try_claim(path).ok_or_else(|| ClawError::Io {
+            path: Some(path.to_path_buf()),
+            kind: std::io::ErrorKind::WouldBlock,
+            message: "Data file is already being written or compacted".to_string(),
+            inner: None,
+        })?;
 
         let file = OpenOptions::new()
-            .create(true)
             .append(true)
-            .open(&path)
-            .map_err(|e| ClawError::Io {
-                path: Some(path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to open data file: {}", e),
-            })?;
+            .open(path)
+            .with_context("Failed to open existing data file").with_path(path)?;
 
         let size = file.metadata()
-            .map_err(|e| ClawError::Io {
-                path: Some(path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to stat data file: {}", e),
-            })?
+            .with_context("Failed to stat data file").with_path(path)?
             .len();
 
-        Ok(Self { file, path, size, data_dir: data_dir.to_path_buf(), sequence })
+        Ok(Self {
+            file, path: path.to_path_buf(), size, data_dir, sequence, max_size,
+            sector_aligned: false, last_flush_offset: size, entries_in_file: 0,
+            sequence_source: None, recent_offsets: VecDeque::new(), verify_window: MIN_VERIFY_WINDOW,
+            _claim: claim,
+        })
+    }
+
+    /// Change the rotation threshold; takes effect from the next write.
+    pub fn set_max_size(&mut self, bytes: u64) {
+        self.max_size = bytes;
+    }
+
+    /// Make `write_entry` and `write_tombstone` pad every entry to a
+    /// `SECTOR_SIZE` boundary, as `write_entry_aligned` does.
+    pub fn set_sector_aligned(&mut self, aligned: bool) {
+        self.sector_aligned = aligned;
+    }
+
+    /// Stamp every entry written from now on with `source.fetch_add(1) + 1`.
+    ///
+    /// Writers that share `source` produce sequence numbers that order
+    /// their entries across files; compaction keeps the higher one.
+    pub fn set_sequence_source(&mut self, source: Arc<AtomicU64>) {
+        self.sequence_source = Some(source);
+    }
+
+    /// Remember the offsets of at least the last `n` entries (never fewer
+    /// than 100) for `verify_last_n_entries`.
+    pub fn set_verify_window(&mut self, n: usize) {
+        self.verify_window = n.max(MIN_VERIFY_WINDOW);
+        while self.recent_offsets.len() > self.verify_window {
+            self.recent_offsets.pop_front();
+        }
+    }
+
+    /// Read back the last `n` entries written to the current file and check
+    /// their headers and CRC32C, to catch writes a faulty device dropped or
+    /// garbled.
+    ///
+    /// Returns `Ok(false)` on a bad header or checksum, `Err` only if the
+    /// file cannot be read. Covers at most the verify window (see
+    /// `set_verify_window`) and only entries since the last rotation.
+    pub fn verify_last_n_entries(&self, n: usize) -> ClawResult<bool> {
+        let skip = self.recent_offsets.len().saturating_sub(n);
+        for &offset in self.recent_offsets.iter().skip(skip) {
+            match DataFileReader::read_entry(&self.path, offset) {
+                Ok(_) => {}
+                Err(ClawError::ChecksumMismatch { .. })
+                | Err(ClawError::NoMagicFound { .. })
+                | Err(ClawError::WalCorrupted { .. }) => return Ok(false),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(true)
     }
 
     /// Write a key-value entry. Returns the byte offset where entry was written.
     pub fn write_entry(&mut self, key: &[u8], value: &[u8]) -> ClawResult<u64> {
-        self.write_internal(key, value, false)
+        self.write_internal(key, value, false, self.sector_aligned)
+    }
+
+    /// Write a key-value entry followed by zero padding up to the next
+    /// `SECTOR_SIZE` boundary, so the following entry starts sector-aligned.
+    ///
+    /// Returns the entry's offset, which is itself aligned when the previous
+    /// write was. The padding length is stored in the header for readers.
+    pub fn write_entry_aligned(&mut self, key: &[u8], value: &[u8]) -> ClawResult<u64> {
+        self.write_internal(key, value, false, true)
     }
 
     /// Write a tombstone (deletion marker). Returns byte offset.
     pub fn write_tombstone(&mut self, key: &[u8]) -> ClawResult<u64> {
-        self.write_internal(key, &[], true)
+        self.write_internal(key, &[], true, self.sector_aligned)
     }
 
-    /// Internal write with optional tombstone flag.
-    fn write_internal(&mut self, key: &[u8], value: &[u8], tombstone: bool) -> ClawResult<u64> {
+    /// Internal write with optional tombstone flag and sector padding.
+    fn write_internal(&mut self, key: &[u8], value: &[u8], tombstone: bool, aligned: bool) -> ClawResult<u64> {
         // Validate sizes before allocation
         if key.len() > MAX_KEY_SIZE {
             return Err(ClawError::OversizedEntry {
@@ -173,9 +363,10 @@ impl DataFileWriter {
         let entry_size = DATA_HEADER_SIZE as u64 + key.len() as u64 + actual_value.len() as u64;
 
         // Rotate if needed
-        if self.size + entry_size > MAX_DATA_FILE_SIZE {
+        if self.size + entry_size + Self::pad_after(self.size, entry_size, aligned) > self.max_size {
             self.rotate()?;
         }
+        let pad_len = Self::pad_after(self.size, entry_size, aligned);
 
         // Compute CRC32C over key + value
         let mut payload = Vec::with_capacity(key.len() + actual_value.len());
@@ -184,107 +375,244 @@ impl DataFileWriter {
         let checksum = crc32c::crc32c(&payload);
 
         let flags = if tombstone { FLAG_TOMBSTONE } else { 0 };
-        let header = DataChunkHeader::new(key.len() as u16, actual_value.len() as u32, checksum, flags);
+        let mut header = DataChunkHeader::new(key.len() as u16, actual_value.len() as u32, checksum, flags);
+        header.pad_len = pad_len as u16;
+        if let Some(source) = &self.sequence_source {
+            header.sequence_number = source.fetch_add(1, Ordering::Relaxed) + 1;
+        }
 
         let offset = self.size;
 
         // Write header + key + value
-        self.file.write_all(&header.to_bytes()).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()), kind: e.kind(),
-            message: format!("Data file write failed: {}", e),
-        })?;
-        self.file.write_all(key).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()), kind: e.kind(),
-            message: format!("Data file write key failed: {}", e),
-        })?;
-        self.file.write_all(actual_value).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()), kind: e.kind(),
-            message: format!("Data file write value failed: {}", e),
-        })?;
+        self.file.write_all(&header.to_bytes())
+            .with_context("Data file write failed")
+            .with_path(&self.path)?;
+        self.file.write_all(key).with_context("Data file write key failed").with_path(&self.path)?;
+        self.file.write_all(actual_value)
+            .with_context("Data file write value failed")
+            .with_path(&self.path)?;
+        if pad_len > 0 {
+            self.file.write_all(&[0u8; SECTOR_SIZE as usize][..pad_len as usize])
+                .with_context("Data file write padding failed")
+                .with_path(&self.path)?;
+        }
 
         // Durable sync — data must survive power loss
-        durable_sync(&self.file).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()), kind: e.kind(),
-            message: format!("Data file durable_sync failed: {}", e),
-        })?;
-
-        self.size += entry_size;
+        durable_sync(&self.file)
+            .with_context("Data file durable_sync failed")
+            .with_path(&self.path)?;
+
+        self.size += entry_size + pad_len;
+        self.last_flush_offset = self.size;
+        self.entries_in_file += 1;
+        if self.recent_offsets.len() == self.verify_window {
+            self.recent_offsets.pop_front();
+        }
+        self.recent_offsets.push_back(offset);
         Ok(offset)
     }
 
+    /// Replace the value of the entry at `offset` in the current file without
+    /// appending a new entry.
+    ///
+    /// `key` must be the stored key and `value` must be the same size as the
+    /// stored value, otherwise `ClawError::RewriteSizeMismatch` is returned
+    /// and the file is untouched. The value is written before the header
+    /// carrying its new checksum, so a crash in between leaves a CRC
+    /// mismatch that readers detect, never a silently wrong value.
+    pub fn rewrite_entry(&mut self, offset: u64, key: &[u8], value: &[u8]) -> ClawResult<()> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.path)
+            .with_context("Failed to open data file for rewrite")
+            .with_path(&self.path)?;
+
+        file.seek(SeekFrom::Start(offset))?;
+        let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+        file.read_exact(&mut hdr_buf)
+            .with_context(&format!("Failed to read data chunk header at offset {}", offset))
+            .with_path(&self.path)?;
+        let mut hdr = DataChunkHeader::from_bytes(&hdr_buf);
+        validate_header(&hdr, &self.path, offset)?;
+
+        let mismatch = |component: &str, expected: u64, actual: usize| ClawError::RewriteSizeMismatch {
+            path: self.path.clone(),
+            offset,
+            component: component.to_string(),
+            expected,
+            actual: actual as u64,
+        };
+        if key.len() != hdr.key_len as usize {
+            return Err(mismatch("key", hdr.key_len as u64, key.len()));
+        }
+        if value.len() != hdr.value_len as usize {
+            return Err(mismatch("value", hdr.value_len as u64, value.len()));
+        }
+
+        let mut stored_key = vec![0u8; key.len()];
+        file.read_exact(&mut stored_key)?;
+        if stored_key != key || hdr.is_tombstone() {
+            return Err(ClawError::Io {
+                path: Some(self.path.clone()),
+                kind: std::io::ErrorKind::InvalidInput,
+                message: format!("No live entry for this key at offset {}", offset),
+                inner: None,
+            });
+        }
+
+        let mut payload = Vec::with_capacity(key.len() + value.len());
+        payload.extend_from_slice(key);
+        payload.extend_from_slice(value);
+        hdr.checksum = crc32c::crc32c(&payload);
+
+        file.seek(SeekFrom::Start(offset + DATA_HEADER_SIZE as u64 + key.len() as u64))?;
+        file.write_all(value)
+            .with_context("Data file rewrite value failed")
+            .with_path(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(&hdr.to_bytes())
+            .with_context("Data file rewrite header failed")
+            .with_path(&self.path)?;
+
+        durable_sync(&file)
+            .with_context("Data file durable_sync failed")
+            .with_path(&self.path)?;
+        Ok(())
+    }
+
+    /// Zero bytes needed after an `entry_size` entry at `offset` to reach
+    /// the next sector boundary (0 for unaligned writes).
+    fn pad_after(offset: u64, entry_size: u64, aligned: bool) -> u64 {
+        if !aligned {
+            return 0;
+        }
+        (SECTOR_SIZE - (offset + entry_size) % SECTOR_SIZE) % SECTOR_SIZE
+    }
+
     /// Current file size in bytes.
     pub fn current_size(&self) -> u64 {
         self.size
     }
 
+    /// Offset just past the last entry made durable in the current file.
+    ///
+    /// A checkpoint for `DataFileReader::scan_since_offset`: entries at or
+    /// beyond it were written after the checkpoint was taken. Resets to 0
+    /// on rotation.
+    pub fn last_flush_offset(&self) -> u64 {
+        self.last_flush_offset
+    }
+
+    /// Entries written to the current file since this writer opened or
+    /// rotated to it. Entries already in a file passed to `open_existing`
+    /// are not counted; `rewrite_entry` does not add one.
+    pub fn current_file_entry_count(&self) -> usize {
+        self.entries_in_file
+    }
+
+    /// Sync and close the current file, continuing in a new one, and return
+    /// the closed file's path.
+    ///
+    /// Unlike the size-triggered rotation, this happens on demand, e.g. to
+    /// hand a complete file to a backup. An empty current file is rotated
+    /// all the same.
+    pub fn force_rotate(&mut self) -> ClawResult<PathBuf> {
+        let sealed = self.path.clone();
+        self.rotate()?;
+        Ok(sealed)
+    }
+
     /// Rotate to a new data file.
     pub fn rotate(&mut self) -> ClawResult<()> {
-        durable_sync(&self.file).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()), kind: e.kind(),
-            message: format!("Data file sync before rotation failed: {}", e),
-        })?;
+        durable_sync(&self.file)
+            .with_context("Data file sync before rotation failed")
+            .with_path(&self.path)?;
 
-        self.sequence += 1;
-        let new_path = self.data_dir.join(format!("data-{:016x}.claw", self.sequence));
-
-        let new_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&new_path)
-            .map_err(|e| ClawError::Io {
-                path: Some(new_path.clone()), kind: e.kind(),
-                message: format!("Failed to create rotated data file: {}", e),
-            })?;
+        let (new_file, new_path, sequence, claim) = create_next_data_file(&self.data_dir, self.sequence + 1)
+            .with_context("Failed to create rotated data file")?;
 
+        self._claim = claim;
         self.file = new_file;
         self.path = new_path;
+        self.sequence = sequence;
         self.size = 0;
+        self.last_flush_offset = 0;
+        self.entries_in_file = 0;
+        self.recent_offsets.clear();
         Ok(())
     }
 }
 
+/// Create and claim a fresh data file at the first free sequence `>= start`.
+///
+/// Uses `create_new`, so several writers in the same directory (parallel
+/// trickle workers) never end up appending to the same file.
+fn create_next_data_file(data_dir: &Path, start: u64) -> ClawResult<(File, PathBuf, u64, DataFileClaim)> {
+    let mut sequence = start;
+    loop {
+        let path = data_dir.join(format!("data-{:016x}.claw", sequence));
+        let Some(claim) = DataFileClaim::try_claim(&path) else {
+            sequence += 1;
+            continue;
+        };
+        match OpenOptions::new().create_new(true).append(true).open(&path) {
+            Ok(file) => return Ok((file, path, sequence, claim)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => sequence += 1,
+            Err(e) => return Err(e).with_path(&path),
+        }
+    }
+}
+
+/// Parse the sequence number from a `data-{seq:016x}.claw` file name.
+pub(crate) fn parse_data_sequence(name: &str) -> Option<u64> {
+    let hex = name.strip_prefix("data-")?.strip_suffix(".claw")?;
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// Path of the highest-sequence data file in `data_dir`, if any.
+pub fn latest_data_file(data_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(data_dir).ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let seq = entry.file_name().to_str().and_then(parse_data_sequence)?;
+            Some((seq, entry.path()))
+        })
+        .max_by_key(|(seq, _)| *seq)
+        .map(|(_, path)| path)
+}
+
+/// Result of [`DataFileReader::verify_file_integrity`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Entries whose CRC32C matched
+    pub entries_verified: usize,
+    /// Entries whose CRC32C did not match
+    pub checksum_errors: usize,
+    /// Positions with bad magic or impossible header sizes (each resync counts once)
+    pub magic_errors: usize,
+}
+
 /// Reads entries from data files with CRC32C verification.
 pub struct DataFileReader;
 
 impl DataFileReader {
     /// Read a single entry at a given offset. Returns None for tombstones.
     pub fn read_entry(file_path: &Path, offset: u64) -> ClawResult<Option<DataEntry>> {
-        let mut file = File::open(file_path).map_err(|e| ClawError::Io {
-            path: Some(file_path.to_path_buf()), kind: e.kind(),
-            message: format!("Failed to open data file: {}", e),
-        })?;
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file")
+            .with_path(file_path)?;
         file.seek(SeekFrom::Start(offset))?;
 
         // Read header
         let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
-        file.read_exact(&mut hdr_buf).map_err(|e| ClawError::Io {
-            path: Some(file_path.to_path_buf()), kind: e.kind(),
-            message: format!("Failed to read data chunk header at offset {}: {}", offset, e),
-        })?;
+        file.read_exact(&mut hdr_buf)
+            .with_context(&format!("Failed to read data chunk header at offset {}", offset))
+            .with_path(file_path)?;
         let hdr = DataChunkHeader::from_bytes(&hdr_buf);
 
-        // Validate magic
-        if hdr.magic != MAGIC_ARRAY {
-            return Err(ClawError::NoMagicFound {
-                path: file_path.to_path_buf(),
-                offset,
-                found_bytes: hdr.magic,
-            });
-        }
-
-        // Validate sizes
-        if hdr.key_len as usize > MAX_KEY_SIZE {
-            return Err(ClawError::WalCorrupted {
-                path: file_path.to_path_buf(), offset,
-                reason: format!("key_len {} exceeds MAX_KEY_SIZE {}", hdr.key_len, MAX_KEY_SIZE),
-            });
-        }
-        if hdr.value_len as usize > MAX_VALUE_SIZE {
-            return Err(ClawError::WalCorrupted {
-                path: file_path.to_path_buf(), offset,
-                reason: format!("value_len {} exceeds MAX_VALUE_SIZE {}", hdr.value_len, MAX_VALUE_SIZE),
-            });
-        }
+        // Validate magic and sizes
+        validate_header(&hdr, file_path, offset)?;
 
         // Read key + value
         let mut key = vec![0u8; hdr.key_len as usize];
@@ -311,20 +639,223 @@ impl DataFileReader {
             return Ok(None);
         }
 
-        Ok(Some(DataEntry { key, value, offset, is_tombstone: false }))
+        Ok(Some(DataEntry { key, value, offset, is_tombstone: false, sequence_number: hdr.sequence_number }))
+    }
+
+    /// Read every entry (tombstones included) starting in `[start_offset, end_offset)`.
+    ///
+    /// Opens the file once and reads sequentially from `start_offset`, which
+    /// must be an entry boundary. The last entry may extend past
+    /// `end_offset`; reading also stops at EOF or a truncated tail. Unlike
+    /// `scan_all`, a bad header or CRC mismatch is an error rather than
+    /// skipped, so a worker never silently drops part of its slice.
+    pub fn read_entry_range(file_path: &Path, start_offset: u64, end_offset: u64) -> ClawResult<Vec<DataEntry>> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for range read")
+            .with_path(file_path)?;
+        let file_len = file.metadata()?.len();
+        file.seek(SeekFrom::Start(start_offset))?;
+
+        let mut entries = Vec::new();
+        let mut offset = start_offset;
+        while offset < end_offset && offset + DATA_HEADER_SIZE as u64 <= file_len {
+            let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+            file.read_exact(&mut hdr_buf)
+                .with_context(&format!("Failed to read data chunk header at offset {}", offset))
+                .with_path(file_path)?;
+            let hdr = DataChunkHeader::from_bytes(&hdr_buf);
+            validate_header(&hdr, file_path, offset)?;
+
+            let entry_total = hdr.entry_len();
+            if offset + entry_total > file_len {
+                break; // truncated tail
+            }
+
+            let mut key = vec![0u8; hdr.key_len as usize];
+            file.read_exact(&mut key)?;
+            let mut value = vec![0u8; hdr.value_len as usize];
+            file.read_exact(&mut value)?;
+
+            let computed = crc32c::crc32c(&[key.as_slice(), value.as_slice()].concat());
+            if computed != hdr.checksum {
+                return Err(ClawError::ChecksumMismatch {
+                    path: file_path.to_path_buf(),
+                    expected: hdr.checksum,
+                    actual: computed,
+                    offset,
+                });
+            }
+
+            entries.push(DataEntry {
+                key, value, offset,
+                is_tombstone: hdr.is_tombstone(),
+                sequence_number: hdr.sequence_number,
+            });
+            offset += hdr.span();
+            if hdr.pad_len > 0 {
+                file.seek(SeekFrom::Start(offset))?;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Divide a data file into at most `num_workers` contiguous byte ranges
+    /// of roughly equal size, for use with `read_entry_range`.
+    ///
+    /// Walks entry headers (payloads are skipped, not checksummed) so every
+    /// range starts on an entry boundary. The ranges cover the file up to
+    /// its first bad header or truncated tail. An empty file yields no
+    /// ranges; fewer ranges than workers are returned when entries are large.
+    pub fn split_into_ranges(file_path: &Path, num_workers: usize) -> ClawResult<Vec<(u64, u64)>> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for splitting")
+            .with_path(file_path)?;
+        let file_len = file.metadata()?.len();
+
+        // Offsets of every readable entry, in file order
+        let mut boundaries = Vec::new();
+        let mut offset = 0u64;
+        while offset + DATA_HEADER_SIZE as u64 <= file_len {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+            file.read_exact(&mut hdr_buf)
+                .with_context(&format!("Failed to read data chunk header at offset {}", offset))
+                .with_path(file_path)?;
+            let hdr = DataChunkHeader::from_bytes(&hdr_buf);
+            if validate_header(&hdr, file_path, offset).is_err() {
+                break;
+            }
+            let entry_total = hdr.entry_len();
+            if offset + entry_total > file_len {
+                break;
+            }
+            boundaries.push(offset);
+            offset += hdr.span();
+        }
+        let end = offset;
+        if boundaries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let workers = num_workers.max(1) as u64;
+        let mut ranges = Vec::with_capacity(workers as usize);
+        let mut start = 0u64;
+        for worker in 1..workers {
+            let target = end * worker / workers;
+            let idx = boundaries.partition_point(|&b| b < target);
+            if let Some(&cut) = boundaries.get(idx) {
+                if cut > start {
+                    ranges.push((start, cut));
+                    start = cut;
+                }
+            }
+        }
+        ranges.push((start, end));
+        Ok(ranges)
     }
 
     /// Scan all entries from a data file. Used during compaction.
     pub fn scan_all(file_path: &Path) -> ClawResult<Vec<DataEntry>> {
-        let mut file = File::open(file_path).map_err(|e| ClawError::Io {
-            path: Some(file_path.to_path_buf()), kind: e.kind(),
-            message: format!("Failed to open data file for scan: {}", e),
-        })?;
+        let mut entries = Vec::new();
+        Self::stream_scan(file_path, |entry| entries.push(entry))?;
+        Ok(entries)
+    }
+
+    /// Keys of every entry (tombstones included) in file order, without
+    /// reading values — for rebuilding indexes.
+    ///
+    /// Value bytes are skipped with a seek, so they are never read from
+    /// disk. That also means the CRC cannot be checked: a key whose value is
+    /// corrupt is still returned. Bad headers resync and a truncated tail
+    /// stops the scan, as in `scan_all`.
+    pub fn scan_keys_only(file_path: &Path) -> ClawResult<Vec<Vec<u8>>> {
+        Self::scan_keys(file_path, false)
+    }
+
+    /// `scan_keys_only`, leaving out tombstones.
+    ///
+    /// A key deleted by a later entry in the same file is still returned
+    /// from its earlier put; callers needing the latest state per key
+    /// should use `scan_all`.
+    pub fn scan_live_keys_only(file_path: &Path) -> ClawResult<Vec<Vec<u8>>> {
+        Self::scan_keys(file_path, true)
+    }
+
+    fn scan_keys(file_path: &Path, skip_tombstones: bool) -> ClawResult<Vec<Vec<u8>>> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for key scan")
+            .with_path(file_path)?;
 
         let file_len = file.metadata()?.len();
-        let mut entries = Vec::new();
+        let mut keys = Vec::new();
         let mut offset = 0u64;
 
+        while offset + DATA_HEADER_SIZE as u64 <= file_len {
+            let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+            file.read_exact(&mut hdr_buf)
+                .with_context("Failed to read data chunk header")
+                .with_path(file_path)?;
+            let hdr = DataChunkHeader::from_bytes(&hdr_buf);
+
+            if validate_header(&hdr, file_path, offset).is_err() {
+                match find_next_magic(&mut file, offset + 1, file_len) {
+                    Some(next) => {
+                        offset = next;
+                        file.seek(SeekFrom::Start(offset))?;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            if offset + hdr.entry_len() > file_len {
+                break; // truncated entry
+            }
+
+            let mut key = vec![0u8; hdr.key_len as usize];
+            file.read_exact(&mut key)
+                .with_context("Failed to read data entry key")
+                .with_path(file_path)?;
+            // Step over the value and any sector padding without reading them
+            file.seek(SeekFrom::Current(hdr.value_len as i64 + hdr.pad_len as i64))?;
+
+            if !(skip_tombstones && hdr.is_tombstone()) {
+                keys.push(key);
+            }
+            offset += hdr.span();
+        }
+
+        Ok(keys)
+    }
+
+    /// Scan entries starting at `start_offset`, e.g. a
+    /// `DataFileWriter::last_flush_offset` checkpoint.
+    ///
+    /// An offset that falls inside an entry resyncs forward to the next
+    /// magic bytes; otherwise the same rules as `scan_all` apply.
+    pub fn scan_since_offset(file_path: &Path, start_offset: u64) -> ClawResult<Vec<DataEntry>> {
+        let mut entries = Vec::new();
+        Self::stream_scan_from(file_path, start_offset, |entry| entries.push(entry))?;
+        Ok(entries)
+    }
+
+    /// Visit every valid entry (tombstones included) in file order without
+    /// collecting them. Returns the number of entries visited.
+    ///
+    /// Corrupt entries are skipped with the same resync rules as `scan_all`.
+    pub fn stream_scan<F: FnMut(DataEntry)>(file_path: &Path, visit: F) -> ClawResult<usize> {
+        Self::stream_scan_from(file_path, 0, visit)
+    }
+
+    fn stream_scan_from<F: FnMut(DataEntry)>(file_path: &Path, start_offset: u64, mut visit: F) -> ClawResult<usize> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for scan")
+            .with_path(file_path)?;
+
+        let file_len = file.metadata()?.len();
+        let mut visited = 0usize;
+        let mut offset = start_offset;
+
         while offset + DATA_HEADER_SIZE as u64 <= file_len {
             file.seek(SeekFrom::Start(offset))?;
 
@@ -352,7 +883,7 @@ impl DataFileReader {
                 }
             }
 
-            let entry_total = DATA_HEADER_SIZE as u64 + hdr.key_len as u64 + hdr.value_len as u64;
+            let entry_total = hdr.entry_len();
             if offset + entry_total > file_len {
                 break; // truncated entry
             }
@@ -371,21 +902,164 @@ impl DataFileReader {
             let computed = crc32c::crc32c(&payload);
 
             if computed == hdr.checksum {
-                entries.push(DataEntry {
+                visit(DataEntry {
                     key,
                     value,
                     offset,
                     is_tombstone: hdr.is_tombstone(),
+                    sequence_number: hdr.sequence_number,
                 });
+                visited += 1;
             } else {
                 eprintln!("[DATA SCAN] CRC mismatch at offset {}, skipping", offset);
             }
 
-            offset += entry_total;
+            offset += hdr.span();
         }
 
-        Ok(entries)
+        Ok(visited)
+    }
+
+    /// Highest entry sequence number in a data file (0 if none is recorded).
+    ///
+    /// Walks entry headers only, like `split_into_ranges`, stopping at the
+    /// first bad header or truncated tail.
+    pub fn max_sequence_number(file_path: &Path) -> ClawResult<u64> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for sequence scan")
+            .with_path(file_path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut max = 0u64;
+        let mut offset = 0u64;
+        while offset + DATA_HEADER_SIZE as u64 <= file_len {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+            file.read_exact(&mut hdr_buf)
+                .with_context(&format!("Failed to read data chunk header at offset {}", offset))
+                .with_path(file_path)?;
+            let hdr = DataChunkHeader::from_bytes(&hdr_buf);
+            if validate_header(&hdr, file_path, offset).is_err() || offset + hdr.entry_len() > file_len {
+                break;
+            }
+            max = max.max(hdr.sequence_number);
+            offset += hdr.span();
+        }
+        Ok(max)
+    }
+
+    /// Whether the file is a run of whole entries with valid headers right
+    /// up to its last byte.
+    ///
+    /// False after a crash tore the last write (or if a header is damaged):
+    /// header walks such as `max_sequence_number` stop there, so nothing
+    /// should be appended after it. Payload checksums are not checked.
+    pub fn ends_cleanly(file_path: &Path) -> ClawResult<bool> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for tail check")
+            .with_path(file_path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut offset = 0u64;
+        while offset + DATA_HEADER_SIZE as u64 <= file_len {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+            file.read_exact(&mut hdr_buf)
+                .with_context(&format!("Failed to read data chunk header at offset {}", offset))
+                .with_path(file_path)?;
+            let hdr = DataChunkHeader::from_bytes(&hdr_buf);
+            if validate_header(&hdr, file_path, offset).is_err() || offset + hdr.span() > file_len {
+                return Ok(false);
+            }
+            offset += hdr.span();
+        }
+        Ok(offset == file_len)
+    }
+
+    /// Walk a data file and count valid entries, CRC mismatches and bad headers.
+    ///
+    /// Same resync rules as `scan_all`, but nothing is skipped silently and
+    /// no entry data is kept. Only I/O failures return `Err`.
+    pub fn verify_file_integrity(file_path: &Path) -> ClawResult<IntegrityReport> {
+        let mut file = File::open(file_path)
+            .with_context("Failed to open data file for verification")
+            .with_path(file_path)?;
+
+        let file_len = file.metadata()?.len();
+        let mut report = IntegrityReport::default();
+        let mut offset = 0u64;
+
+        while offset + DATA_HEADER_SIZE as u64 <= file_len {
+            file.seek(SeekFrom::Start(offset))?;
+
+            let mut hdr_buf = [0u8; DATA_HEADER_SIZE];
+            file.read_exact(&mut hdr_buf)
+                .with_context("Failed to read data chunk header")
+                .with_path(file_path)?;
+            let hdr = DataChunkHeader::from_bytes(&hdr_buf);
+
+            if hdr.magic != MAGIC_ARRAY
+                || hdr.key_len as usize > MAX_KEY_SIZE
+                || hdr.value_len as usize > MAX_VALUE_SIZE
+            {
+                report.magic_errors += 1;
+                match find_next_magic(&mut file, offset + 1, file_len) {
+                    Some(next) => { offset = next; continue; }
+                    None => break,
+                }
+            }
+
+            let entry_total = hdr.entry_len();
+            if offset + entry_total > file_len {
+                break; // truncated tail — an interrupted write, not corruption
+            }
+
+            let mut payload = vec![0u8; hdr.key_len as usize + hdr.value_len as usize];
+            file.read_exact(&mut payload)
+                .with_context("Failed to read data entry")
+                .with_path(file_path)?;
+
+            if crc32c::crc32c(&payload) == hdr.checksum {
+                report.entries_verified += 1;
+            } else {
+                report.checksum_errors += 1;
+            }
+
+            offset += hdr.span();
+        }
+
+        Ok(report)
+    }
+}
+
+/// `DataEntry::supersedes` on bare sequence numbers, for callers that
+/// don't keep whole entries.
+pub(crate) fn supersedes(sequence_number: u64, earlier_sequence_number: u64) -> bool {
+    sequence_number == 0 || earlier_sequence_number == 0 || sequence_number >= earlier_sequence_number
+}
+
+/// Check a header's magic and size limits before trusting its lengths.
+fn validate_header(hdr: &DataChunkHeader, file_path: &Path, offset: u64) -> ClawResult<()> {
+    if hdr.magic != MAGIC_ARRAY {
+        return Err(ClawError::NoMagicFound {
+            path: file_path.to_path_buf(),
+            offset,
+            found_bytes: hdr.magic,
+        });
+    }
+    if hdr.key_len as usize > MAX_KEY_SIZE {
+        return Err(ClawError::WalCorrupted {
+            path: file_path.to_path_buf(), offset,
+            reason: format!("key_len {} exceeds MAX_KEY_SIZE {}", hdr.key_len, MAX_KEY_SIZE),
+        });
     }
+    if hdr.value_len as usize > MAX_VALUE_SIZE {
+        return Err(ClawError::WalCorrupted {
+            path: file_path.to_path_buf(), offset,
+            reason: format!("value_len {} exceeds MAX_VALUE_SIZE {}", hdr.value_len, MAX_VALUE_SIZE),
+        });
+    }
+    Ok(())
 }
 
 /// Scan forward in file to find next CLAW magic bytes (corruption recovery).
@@ -425,7 +1099,7 @@ mod tests {
     fn test_write_read_roundtrip() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
 
         let offset = writer.write_entry(b"mykey", b"myvalue").unwrap();
         let file = find_data_file(&dir);
@@ -440,7 +1114,7 @@ mod tests {
     fn test_tombstone() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
 
         let offset = writer.write_tombstone(b"gone").unwrap();
         let file = find_data_file(&dir);
@@ -449,11 +1123,56 @@ mod tests {
         assert!(entry.is_none(), "Tombstone should return None");
     }
 
+    #[test]
+    fn test_split_and_read_entry_ranges() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        for i in 0..40u32 {
+            writer.write_entry(format!("key-{:02}", i).as_bytes(), &vec![i as u8; i as usize * 3]).unwrap();
+        }
+        writer.write_tombstone(b"key-00").unwrap();
+        drop(writer);
+        let file = find_data_file(&dir);
+
+        let ranges = DataFileReader::split_into_ranges(&file, 4).unwrap();
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[3].1, std::fs::metadata(&file).unwrap().len());
+        assert!(ranges.windows(2).all(|w| w[0].1 == w[1].0));
+
+        // Concatenated ranges match a full scan, entry for entry
+        let mut from_ranges = Vec::new();
+        for (start, end) in &ranges {
+            let entries = DataFileReader::read_entry_range(&file, *start, *end).unwrap();
+            assert!(!entries.is_empty());
+            assert!(entries.iter().all(|e| e.offset >= *start && e.offset < *end));
+            from_ranges.extend(entries);
+        }
+        let all = DataFileReader::scan_all(&file).unwrap();
+        assert_eq!(from_ranges.len(), 41);
+        for (a, b) in from_ranges.iter().zip(&all) {
+            assert_eq!((&a.key, &a.value, a.offset, a.is_tombstone), (&b.key, &b.value, b.offset, b.is_tombstone));
+        }
+
+        // A flipped value byte inside the second range fails that range only
+        let (start, end) = ranges[1];
+        let victim = DataFileReader::read_entry_range(&file, start, end).unwrap()[0].offset;
+        let mut bytes = std::fs::read(&file).unwrap();
+        bytes[victim as usize + DATA_HEADER_SIZE + 7] ^= 0xFF;
+        std::fs::write(&file, &bytes).unwrap();
+        assert!(matches!(
+            DataFileReader::read_entry_range(&file, start, end),
+            Err(ClawError::ChecksumMismatch { .. })
+        ));
+        assert!(DataFileReader::read_entry_range(&file, ranges[0].0, ranges[0].1).is_ok());
+    }
+
     #[test]
     fn test_checksum_validation() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
 
         let offset = writer.write_entry(b"key", b"value").unwrap();
         let file = find_data_file(&dir);
@@ -469,11 +1188,54 @@ mod tests {
         assert!(matches!(result, Err(ClawError::ChecksumMismatch { .. })));
     }
 
+    #[test]
+    fn test_rewrite_entry_in_place() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+
+        writer.write_entry(b"before", b"x").unwrap();
+        let offset = writer.write_entry(b"hash", &[0xAA; 32]).unwrap();
+        writer.write_entry(b"after", b"y").unwrap();
+        let size = writer.current_size();
+        let file = find_data_file(&dir);
+
+        writer.rewrite_entry(offset, b"hash", &[0xBB; 32]).unwrap();
+        assert_eq!(writer.current_size(), size);
+        assert_eq!(std::fs::metadata(&file).unwrap().len(), size);
+
+        // read_entry verifies the new checksum; neighbours are unchanged
+        let entry = DataFileReader::read_entry(&file, offset).unwrap().unwrap();
+        assert_eq!(entry.value, vec![0xBB; 32]);
+        let report = DataFileReader::verify_file_integrity(&file).unwrap();
+        assert_eq!((report.entries_verified, report.checksum_errors, report.magic_errors), (3, 0, 0));
+        let keys: Vec<_> = DataFileReader::scan_all(&file).unwrap().into_iter().map(|e| e.key).collect();
+        assert_eq!(keys, vec![b"before".to_vec(), b"hash".to_vec(), b"after".to_vec()]);
+
+        // Size changes are rejected and leave the entry alone
+        let err = writer.rewrite_entry(offset, b"hash", &[0xCC; 33]).unwrap_err();
+        assert!(matches!(
+            err,
+            ClawError::RewriteSizeMismatch { ref component, expected: 32, actual: 33, .. } if component == "value"
+        ));
+        assert!(matches!(
+            writer.rewrite_entry(offset, b"hashes", &[0xCC; 32]),
+            Err(ClawError::RewriteSizeMismatch { .. })
+        ));
+        assert!(writer.rewrite_entry(offset, b"HASH", &[0xCC; 32]).is_err());
+        let entry = DataFileReader::read_entry(&file, offset).unwrap().unwrap();
+        assert_eq!(entry.value, vec![0xBB; 32]);
+
+        // Appends continue after the rewrite
+        let next = writer.write_entry(b"later", b"z").unwrap();
+        assert_eq!(next, size);
+    }
+
     #[test]
     fn test_scan_all() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
 
         writer.write_entry(b"k1", b"v1").unwrap();
         writer.write_entry(b"k2", b"v2").unwrap();
@@ -491,11 +1253,220 @@ mod tests {
         assert_eq!(entries[3].key, b"k4");
     }
 
+    #[test]
+    fn test_scan_keys_only_matches_scan_all() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, 256 * 1024 * 1024).unwrap();
+        let large_value = vec![0x5A; 1024 * 1024];
+        for i in 0..64u32 {
+            if i % 8 == 7 {
+                writer.write_tombstone(&i.to_be_bytes()).unwrap();
+            } else {
+                writer.write_entry(&i.to_be_bytes(), &large_value).unwrap();
+            }
+        }
+        writer.write_entry_aligned(b"aligned", b"v").unwrap();
+        writer.write_entry(b"last", b"v").unwrap();
+
+        let file = find_data_file(&dir);
+        let start = std::time::Instant::now();
+        let entries = DataFileReader::scan_all(&file).unwrap();
+        let scan_all_time = start.elapsed();
+        let start = std::time::Instant::now();
+        let keys = DataFileReader::scan_keys_only(&file).unwrap();
+        let keys_only_time = start.elapsed();
+
+        assert_eq!(keys, entries.iter().map(|e| e.key.clone()).collect::<Vec<_>>());
+        let live: Vec<Vec<u8>> = entries.iter().filter(|e| !e.is_tombstone).map(|e| e.key.clone()).collect();
+        assert_eq!(DataFileReader::scan_live_keys_only(&file).unwrap(), live);
+        assert_eq!(live.len(), 58);
+
+        assert!(
+            keys_only_time * 4 < scan_all_time,
+            "keys-only scan should skip value reads: {:?} vs {:?}",
+            keys_only_time,
+            scan_all_time
+        );
+    }
+
+    #[test]
+    fn test_scan_since_offset() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        assert_eq!(writer.last_flush_offset(), 0);
+
+        let mut checkpoint = 0;
+        for i in 0..10u8 {
+            writer.write_entry(&[b'k', i], &[i; 16]).unwrap();
+            if i == 4 {
+                checkpoint = writer.last_flush_offset();
+            }
+        }
+        assert_eq!(writer.last_flush_offset(), writer.current_size());
+
+        let file = find_data_file(&dir);
+        let since = DataFileReader::scan_since_offset(&file, checkpoint).unwrap();
+        let keys: Vec<u8> = since.iter().map(|e| e.key[1]).collect();
+        assert_eq!(keys, vec![5, 6, 7, 8, 9]);
+        assert_eq!(since[0].offset, checkpoint);
+
+        // Mid-entry start resyncs to the next entry
+        let mid = DataFileReader::scan_since_offset(&file, checkpoint + 3).unwrap();
+        assert_eq!(mid.len(), 4);
+        assert_eq!(mid[0].key, [b'k', 6]);
+    }
+
+    #[test]
+    fn test_open_existing_appends() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = DataFileWriter::new(dir.path(), DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        for i in 0..5u8 {
+            writer.write_entry(&[i], b"first").unwrap();
+        }
+        let path = writer.path.clone();
+        drop(writer);
+
+        let mut writer = DataFileWriter::open_existing(&path, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        assert_eq!(writer.sequence, 1);
+        for i in 5..10u8 {
+            writer.write_entry(&[i], b"second").unwrap();
+        }
+        drop(writer);
+
+        let entries = DataFileReader::scan_all(&path).unwrap();
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[9].key, vec![9]);
+        assert_eq!(latest_data_file(dir.path()), Some(path));
+    }
+
+    #[test]
+    fn test_force_rotate_returns_sealed_file() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = DataFileWriter::new(dir.path(), DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(b"a", b"1").unwrap();
+        writer.write_entry(b"b", b"2").unwrap();
+        writer.write_tombstone(b"a").unwrap();
+        assert_eq!(writer.current_file_entry_count(), 3);
+
+        let sealed = writer.force_rotate().unwrap();
+        assert_eq!(writer.current_file_entry_count(), 0);
+        assert_eq!(writer.current_size(), 0);
+        assert_ne!(writer.path, sealed);
+
+        writer.write_entry(b"c", b"3").unwrap();
+        assert_eq!(writer.current_file_entry_count(), 1);
+        let entries = DataFileReader::scan_all(&sealed).unwrap();
+        let keys: Vec<_> = entries.iter().map(|e| e.key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"a"[..], b"b", b"a"]);
+    }
+
+    #[test]
+    fn test_verify_last_n_entries_detects_corruption() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = DataFileWriter::new(dir.path(), DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        for i in 0..5u8 {
+            writer.write_entry(&[b'k', i], b"value").unwrap();
+        }
+        assert!(writer.verify_last_n_entries(5).unwrap());
+
+        // Flip a value byte of the second-to-last entry
+        let entry_len = DATA_HEADER_SIZE as u64 + 2 + 5;
+        let mut bytes = std::fs::read(&writer.path).unwrap();
+        bytes[(3 * entry_len + entry_len - 1) as usize] ^= 0xFF;
+        std::fs::write(&writer.path, bytes).unwrap();
+
+        assert!(writer.verify_last_n_entries(1).unwrap());
+        assert!(!writer.verify_last_n_entries(2).unwrap());
+        assert!(!writer.verify_last_n_entries(100).unwrap());
+
+        writer.force_rotate().unwrap();
+        assert!(writer.verify_last_n_entries(5).unwrap());
+        assert!(!crate::config::Config::default().verify_writes_after_flush);
+    }
+
+    #[test]
+    fn test_small_max_size_rotates() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = DataFileWriter::new(dir.path(), 1024).unwrap();
+        writer.write_entry(b"a", &[1u8; 600]).unwrap();
+        writer.write_entry(b"b", &[2u8; 600]).unwrap();
+        drop(writer);
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_str().and_then(parse_data_sequence).is_some())
+            .collect();
+        assert_eq!(files.len(), 2);
+        for f in &files {
+            assert_eq!(DataFileReader::scan_all(&f.path()).unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_verify_file_integrity_counts_errors() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(b"k1", b"v1").unwrap();
+        let second = writer.write_entry(b"k2", b"v2").unwrap();
+        let third = writer.write_entry(b"k3", b"v3").unwrap();
+        writer.write_entry(b"k4", b"v4").unwrap();
+
+        let file = find_data_file(&dir);
+        let mut data = std::fs::read(&file).unwrap();
+        data[second as usize + DATA_HEADER_SIZE] ^= 0xFF; // payload of k2
+        data[third as usize] = 0x00; // magic of k3
+        std::fs::write(&file, data).unwrap();
+
+        let report = DataFileReader::verify_file_integrity(&file).unwrap();
+        assert_eq!(report.entries_verified, 2);
+        assert_eq!(report.checksum_errors, 1);
+        assert_eq!(report.magic_errors, 1);
+    }
+
+    #[test]
+    fn test_sector_aligned_writes() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+
+        // Unaligned entry first: alignment applies from the entry after it
+        writer.write_entry(b"plain", b"v").unwrap();
+        writer.write_entry_aligned(b"a1", &[1u8; 100]).unwrap();
+        writer.write_entry_aligned(b"a2", &[2u8; 5000]).unwrap();
+        writer.set_sector_aligned(true);
+        writer.write_tombstone(b"a1").unwrap();
+        writer.write_entry(b"a3", &[3u8; 4096 - DATA_HEADER_SIZE - 2]).unwrap(); // exactly one sector
+        writer.write_entry(b"a4", b"last").unwrap();
+        assert_eq!(writer.current_size() % SECTOR_SIZE, 0);
+
+        let path = find_data_file(&dir);
+        let entries = DataFileReader::scan_all(&path).unwrap();
+        let keys: Vec<&[u8]> = entries.iter().map(|e| e.key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"plain"[..], b"a1", b"a2", b"a1", b"a3", b"a4"]);
+        assert!(entries[2..].iter().all(|e| e.offset % SECTOR_SIZE == 0));
+        assert_eq!(entries[2].value, vec![2u8; 5000]);
+        assert!(entries[3].is_tombstone);
+        assert_eq!(entries[5].offset - entries[4].offset, SECTOR_SIZE);
+
+        // Every reader steps over the padding
+        assert_eq!(DataFileReader::verify_file_integrity(&path).unwrap().entries_verified, 6);
+        let ranges = DataFileReader::split_into_ranges(&path, 3).unwrap();
+        let ranged: usize = ranges.iter()
+            .map(|&(s, e)| DataFileReader::read_entry_range(&path, s, e).unwrap().len())
+            .sum();
+        assert_eq!(ranged, 6);
+        let a2 = DataFileReader::read_entry(&path, entries[2].offset).unwrap().unwrap();
+        assert_eq!(a2.key, b"a2");
+    }
+
     #[test]
     fn test_oversized_rejected() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("data");
-        let mut writer = DataFileWriter::new(&dir).unwrap();
+        let mut writer = DataFileWriter::new(&dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
 
         let big_key = vec![0xAA; MAX_KEY_SIZE + 1];
         assert!(matches!(
@@ -7,17 +7,329 @@
 //! **Write path**: WAL-first, then RAM, then mark dirty for trickle
 //! **Background**: Trickle thread flushes dirty entries to data files on cadence
 
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+use crossbeam_channel::Sender;
 use hashbrown::HashMap;
-use parking_lot::{RwLock, Mutex};
+use parking_lot::{Mutex, MutexGuard};
 
-use crate::config::Config;
-use crate::error::{ClawError, ClawResult};
-use crate::format::Operation;
-use crate::trickle::{DirtyTracker, TrickleHandle, start_trickle};
-use crate::wal::{WalWriter, WalReader};
+use crate::access_log::AccessLogWriter;
+use crate::compaction::{list_data_files, merge_files_to};
+use crate::config::{Config, LOCK_FILE_NAME};
+use crate::datafile::{latest_data_file, supersedes, DataFileReader, DataFileWriter, DATA_HEADER_SIZE};
+use crate::error::{ClawError, ClawResult, ClawResultExt};
+use crate::format::{check_entry_size, Operation, WalEntry};
+#[cfg(feature = "metrics")]
+use crate::metrics::EngineMetrics;
+use crate::platform_durability::{try_lock_exclusive, try_lock_shared};
+use crate::ram::{RamMap, RamReadGuard, RamStore};
+use crate::stats::{DbStats, EntryStats, MemoryBreakdown, DB_STATS_FILE};
+use crate::subscription::{ReplicaStream, SubscriptionHandle, WriteEvent, WriteSubscribers};
+use crate::trickle::{flush_dirty, DirtyTracker, TrickleHandle, start_trickle};
+use crate::wal::{WalPosition, WalWriter, WalReader};
+
+/// How [`ClawStoreEngine::open_with_mode`] opens a store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenMode {
+    /// Recover the WAL and append to it; exclusive lock on the store
+    #[default]
+    ReadWrite,
+    /// Recover the WAL without creating a writer; shared lock on the store
+    ReadOnly,
+}
+
+/// Key to the WAL record that last wrote it, for keys in RAM that have one.
+type WalPositions = HashMap<Vec<u8>, WalPosition>;
+
+/// Cleanup callback run when the engine is dropped, see
+/// [`ClawStoreEngine::register_shutdown_hook`].
+type ShutdownHook = Box<dyn Fn() + Send + Sync>;
+
+/// Key + value bytes per entry assumed by `estimate_entry_count_from_disk`
+/// when no `db.stats` file is available
+const DEFAULT_ESTIMATE_PAYLOAD_BYTES: f64 = 64.0;
+
+/// `(key, WAL sequence)` lookups remembered by `ClawStoreEngine::get_at_wal_sequence`
+const HISTORY_CACHE_ENTRIES: usize = 1024;
+
+/// Access order for cache mode, oldest first.
+///
+/// Each touch stamps the key with a fresh tick; `by_tick` keeps the ticks
+/// sorted so the least recently used key is its first entry.
+#[derive(Default)]
+struct LruOrder {
+    next_tick: u64,
+    ticks: HashMap<Vec<u8>, u64>,
+    by_tick: BTreeMap<u64, Vec<u8>>,
+}
+
+impl LruOrder {
+    /// Mark `key` as the most recently used.
+    fn touch(&mut self, key: &[u8]) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        if let Some(old) = self.ticks.insert(key.to_vec(), tick) {
+            self.by_tick.remove(&old);
+        }
+        self.by_tick.insert(tick, key.to_vec());
+    }
+
+    /// Stop tracking `key`.
+    fn forget(&mut self, key: &[u8]) {
+        if let Some(tick) = self.ticks.remove(key) {
+            self.by_tick.remove(&tick);
+        }
+    }
+
+    /// Remove and return the least recently used key.
+    fn pop_oldest(&mut self) -> Option<Vec<u8>> {
+        let (_, key) = self.by_tick.pop_first()?;
+        self.ticks.remove(&key);
+        Some(key)
+    }
+
+    /// Order for keys loaded from disk: they have no access history, so
+    /// they are all treated as oldest, in key order.
+    fn recovered(data: &RamMap) -> Self {
+        let mut lru = Self::default();
+        let mut keys: Vec<&Vec<u8>> = data.keys().collect();
+        keys.sort();
+        for key in keys {
+            lru.touch(key);
+        }
+        lru
+    }
+}
+
+/// Bounded LRU of historical values, keyed by key bytes followed by the
+/// big-endian WAL sequence.
+#[derive(Default)]
+struct HistoryCache {
+    values: HashMap<Vec<u8>, Option<Vec<u8>>>,
+    order: LruOrder,
+}
+
+impl HistoryCache {
+    fn cache_key(key: &[u8], sequence: u64) -> Vec<u8> {
+        let mut cache_key = Vec::with_capacity(key.len() + 8);
+        cache_key.extend_from_slice(key);
+        cache_key.extend_from_slice(&sequence.to_be_bytes());
+        cache_key
+    }
+
+    fn get(&mut self, cache_key: &[u8]) -> Option<Option<Vec<u8>>> {
+        let value = self.values.get(cache_key)?.clone();
+        self.order.touch(cache_key);
+        Some(value)
+    }
+
+    /// Approximate heap bytes: each cache key is held by `values` and twice
+    /// by `order`.
+    fn approx_bytes(&self) -> u64 {
+        let per_entry = std::mem::size_of::<Option<Vec<u8>>>() + 2 * std::mem::size_of::<u64>();
+        self.values.iter()
+            .map(|(key, value)| (3 * key.len() + value.as_ref().map_or(0, Vec::len) + per_entry) as u64)
+            .sum()
+    }
+
+    fn insert(&mut self, cache_key: Vec<u8>, value: Option<Vec<u8>>) {
+        self.order.touch(&cache_key);
+        self.values.insert(cache_key, value);
+        while self.values.len() > HISTORY_CACHE_ENTRIES {
+            match self.order.pop_oldest() {
+                Some(oldest) => { self.values.remove(&oldest); }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Sentinel key written and restored by `ClawStoreEngine::consistency_check_details`
+pub const CONSISTENCY_PROBE_KEY: &[u8] = b"__consistency_probe__";
+
+/// A single write destined for a table-prefixed key.
+///
+/// Used by [`ClawStoreEngine::put_cross_table`] to commit writes spanning
+/// several tables (e.g. a Reth state bundle) with one WAL sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossTableWrite {
+    /// Table prefix byte
+    pub prefix: u8,
+    /// Key bytes without the prefix
+    pub key: Vec<u8>,
+    /// Value bytes
+    pub value: Vec<u8>,
+}
+
+impl CrossTableWrite {
+    /// The full `[prefix][key]` key as stored in the engine.
+    pub fn prefixed_key(&self) -> Vec<u8> {
+        let mut prefixed = Vec::with_capacity(1 + self.key.len());
+        prefixed.push(self.prefix);
+        prefixed.extend_from_slice(&self.key);
+        prefixed
+    }
+}
+
+/// Sorted iterator over all engine keys, returned by
+/// [`ClawStoreEngine::iter_all_keys`].
+///
+/// Holds the engine read lock until dropped, so writers block while it is
+/// alive. Only keys are copied — values are never cloned.
+pub struct KeyIter<'a> {
+    _guard: RamReadGuard<'a>,
+    keys: std::vec::IntoIter<Vec<u8>>,
+}
+
+impl Iterator for KeyIter<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl ExactSizeIterator for KeyIter<'_> {}
+
+/// Borrowed, zero-copy view of the RAM working set, returned by
+/// [`ClawStoreEngine::read_view`].
+///
+/// Holds the engine read lock until dropped. Iteration order is the hash
+/// table's (unsorted).
+pub struct ReadView<'a> {
+    data: RamReadGuard<'a>,
+}
+
+impl ReadView<'_> {
+    /// Iterate keys without copying, in hash order.
+    pub fn iter_keys_unsorted(&self) -> impl Iterator<Item = &[u8]> {
+        self.data.keys().map(Vec::as_slice)
+    }
+
+    /// Iterate key-value pairs without copying, in hash order.
+    pub fn iter_all_entries_unsorted(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.data.iter().map(|(k, v)| (k.as_slice(), v.as_slice()))
+    }
+
+    /// Number of entries in the view.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns true if the view has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+/// A value with where it was last written, returned by
+/// [`ClawStoreEngine::get_with_metadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryMetadata {
+    /// Current value in RAM
+    pub value: Vec<u8>,
+    /// Sequence of the WAL file holding the last write (None if the key has
+    /// no local WAL record, e.g. loaded from data files or replicated in)
+    pub last_wal_sequence: Option<u64>,
+    /// Offset of that write's record within the file; writes of several
+    /// records at once (`put_durable_ordered`) report where the write starts
+    pub last_wal_offset: Option<u64>,
+    /// Changed since the trickle engine last flushed it
+    pub dirty: bool,
+}
+
+/// Result of [`ClawStoreEngine::verify_consistency`].
+///
+/// Inconsistencies are reported here rather than as errors.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Put/Delete entries recovered from all WAL files (SEAL records excluded)
+    pub wal_entries_recovered: usize,
+    /// Key-value pairs currently in RAM
+    pub ram_entries: usize,
+    /// Data file entries whose CRC32C matched
+    pub data_file_entries_verified: usize,
+    /// Data file entries whose CRC32C did not match
+    pub checksum_errors: usize,
+    /// Data file positions with bad magic or impossible header sizes
+    pub magic_errors: usize,
+    /// Leftover `.compact` temp files from an interrupted compaction
+    pub orphan_compact_files: Vec<PathBuf>,
+}
+
+impl ConsistencyReport {
+    /// True if no corruption or leftover compaction files were found.
+    pub fn is_clean(&self) -> bool {
+        self.checksum_errors == 0 && self.magic_errors == 0 && self.orphan_compact_files.is_empty()
+    }
+}
+
+/// Result of [`ClawStoreEngine::consistency_check_details`], one flag per check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsistencyCheckResult {
+    /// `dirty_count() <= len()`
+    pub dirty_within_len: bool,
+    /// The WAL writer's current file exists; for read-only engines, which
+    /// have no writer, the WAL directory does
+    pub wal_file_exists: bool,
+    /// `{path}/data` exists
+    pub data_dir_exists: bool,
+    /// A WAL write plus RAM insert/get/restore on the probe key behaved as
+    /// expected (not run, and true, for read-only engines)
+    pub probe_round_trip: bool,
+}
+
+impl ConsistencyCheckResult {
+    /// True if every check passed.
+    pub fn is_ok(&self) -> bool {
+        self.dirty_within_len && self.wal_file_exists && self.data_dir_exists && self.probe_round_trip
+    }
+}
+
+/// Result of [`ClawStoreEngine::vacuum`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VacuumReport {
+    /// Data file + WAL bytes before the vacuum
+    pub bytes_before: u64,
+    /// Data file + WAL bytes after the vacuum
+    pub bytes_after: u64,
+    /// `bytes_before - bytes_after` (0 if the store grew meanwhile)
+    pub bytes_saved: u64,
+    /// Wall-clock time the vacuum took
+    pub time_elapsed: std::time::Duration,
+    /// Data files merged into the single compacted file
+    pub data_files_compacted: usize,
+    /// Sealed WAL files deleted
+    pub wal_files_removed: usize,
+}
+
+/// Key-level differences between two engines, from [`ClawStoreEngine::diff`].
+///
+/// Each list is sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EngineDiff {
+    /// Keys only in the other engine, with their value there
+    pub added: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Keys in both engines with different values: `(key, old, new)`
+    pub modified: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+    /// Keys only in this engine
+    pub removed: Vec<Vec<u8>>,
+}
+
+impl EngineDiff {
+    /// True if both engines hold exactly the same entries.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
 
 /// Core storage engine: RAM hash table + WAL + trickle flush.
 ///
@@ -26,10 +338,11 @@ use crate::wal::{WalWriter, WalReader};
 /// Writers serialize through the WAL Mutex, then briefly hold the HashMap write lock.
 /// The trickle engine runs in the background flushing dirty entries to data files.
 pub struct ClawStoreEngine {
-    /// RAM working set — concurrent reads via RwLock
-    data: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
-    /// Write-ahead log — single writer via Mutex
-    wal: Mutex<WalWriter>,
+    /// RAM working set — concurrent reads via RwLock, or per-shard locks
+    /// with `Config::use_concurrent_hashmap`
+    data: Arc<RamStore>,
+    /// Write-ahead log — single writer via Mutex (None in read-only mode)
+    wal: Option<Mutex<WalWriter>>,
     /// Dirty key tracker — shared with trickle thread
     dirty: Arc<DirtyTracker>,
     /// Background trickle engine handle (None if not started)
@@ -38,6 +351,32 @@ pub struct ClawStoreEngine {
     path: PathBuf,
     /// Engine configuration
     config: Config,
+    /// Next write sequence number for ordered puts
+    next_sequence: AtomicU64,
+    /// Bumped under the RAM write lock by every mutation of `data`
+    write_generation: AtomicU64,
+    /// Times `data` was read-locked through `read_data`
+    #[cfg(any(test, feature = "testing"))]
+    read_locks: AtomicU64,
+    /// Advisory lock on `clawstore.lock`, held for the engine's lifetime
+    _lock_file: File,
+    /// Audit log of key accesses (None unless `Config::access_log` is set)
+    access_log: Option<Mutex<AccessLogWriter>>,
+    /// Prometheus metrics, set once by `register_metrics`
+    #[cfg(feature = "metrics")]
+    metrics: std::sync::OnceLock<EngineMetrics>,
+    /// Named cleanup callbacks, run in registration order on drop
+    shutdown_hooks: Mutex<Vec<(String, ShutdownHook)>>,
+    /// Channels notified after every successful write
+    subscribers: Arc<WriteSubscribers>,
+    /// Key access order for `put_and_evict_lru`; also updated by get, put
+    /// and delete when `Config::cache_mode` is set
+    lru: Mutex<LruOrder>,
+    /// Recent `get_at_wal_sequence` results for closed WAL files
+    history_cache: Mutex<HistoryCache>,
+    /// Last WAL record per key, only touched with the `data` lock held so
+    /// the two always agree
+    wal_positions: Mutex<WalPositions>,
 }
 
 impl ClawStoreEngine {
@@ -45,57 +384,107 @@ impl ClawStoreEngine {
     ///
     /// Creates WAL and data directories, replays WAL for crash recovery,
     /// and optionally starts the background trickle engine.
+    ///
+    /// Takes an exclusive advisory lock on `clawstore.lock`; returns
+    /// `AlreadyOpen` if another instance holds it.
     pub fn open<P: AsRef<Path>>(path: P, config: Config) -> ClawResult<Self> {
+        Self::open_with_mode(path, config, OpenMode::ReadWrite)
+    }
+
+    /// Open a store in the given mode.
+    ///
+    /// `ReadOnly` expects an existing store: it creates no directories, takes
+    /// a shared lock (so several readers may coexist, but not a writer) and
+    /// recovers the WAL without opening a `WalWriter`. Writes then fail with
+    /// `ClawError::ReadOnly`.
+    pub fn open_with_mode<P: AsRef<Path>>(path: P, config: Config, mode: OpenMode) -> ClawResult<Self> {
         let path = path.as_ref().to_path_buf();
         let wal_dir = path.join("wal");
         let data_dir = path.join("data");
+        let read_only = mode == OpenMode::ReadOnly;
 
         // Create directories
-        std::fs::create_dir_all(&wal_dir).map_err(|e| ClawError::Io {
-            path: Some(wal_dir.clone()),
-            kind: e.kind(),
-            message: format!("Failed to create WAL directory: {}", e),
-        })?;
-        std::fs::create_dir_all(&data_dir).map_err(|e| ClawError::Io {
-            path: Some(data_dir.clone()),
-            kind: e.kind(),
-            message: format!("Failed to create data directory: {}", e),
-        })?;
-
-        // Replay WAL into RAM (crash recovery)
-        let mut data = HashMap::new();
-        let reader = WalReader::new(&wal_dir);
-        let entries = reader.recover_entries()?;
-
-        for entry in &entries {
-            match entry.operation {
-                Operation::Put => {
-                    data.insert(entry.key.clone(), entry.value.clone());
-                }
-                Operation::Delete => {
-                    data.remove(&entry.key);
-                }
-            }
+        if !read_only {
+            std::fs::create_dir_all(&wal_dir).map_err(|e| ClawError::Io {
+                path: Some(wal_dir.clone()),
+                kind: e.kind(),
+                message: format!("Failed to create WAL directory: {}", e),
+                inner: Some(Arc::new(e)),
+            })?;
+            std::fs::create_dir_all(&data_dir).map_err(|e| ClawError::Io {
+                path: Some(data_dir.clone()),
+                kind: e.kind(),
+                message: format!("Failed to create data directory: {}", e),
+                inner: Some(Arc::new(e)),
+            })?;
         }
 
-        let recovered_count = data.len();
-        if recovered_count > 0 {
-            eprintln!(
-                "[ClawStore] Recovered {} entries from WAL at {}",
-                recovered_count,
-                wal_dir.display()
-            );
+        // Lock the store before touching the WAL — two writers would corrupt it.
+        // Readers share the lock; the lock file must already exist for them.
+        let lock_path = path.join(LOCK_FILE_NAME);
+        let lock_file = OpenOptions::new()
+            .create(!read_only)
+            .truncate(false)
+            .read(read_only)
+            .write(!read_only)
+            .open(&lock_path)
+            .with_context("Failed to open lock file").with_path(&lock_path)?;
+        let locked = if read_only {
+            try_lock_shared(&lock_file)
+        } else {
+            try_lock_exclusive(&lock_file)
+        };
+        if !locked.with_context("Failed to lock store").with_path(&lock_path)? {
+            return Err(ClawError::AlreadyOpen { path });
         }
 
-        let wal = WalWriter::new(&wal_dir)?;
+        let (data, wal_positions, max_sequence) = load_state(&path, &config)?;
+
+        let lru = if config.cache_mode.is_some() {
+            LruOrder::recovered(&data)
+        } else {
+            LruOrder::default()
+        };
+
+        let wal = if read_only {
+            None
+        } else {
+            Some(Mutex::new(WalWriter::new(&wal_dir)?.with_encryption_key(config.encryption_key)))
+        };
+
+        let access_log = match &config.access_log {
+            Some(log_path) => Some(Mutex::new(AccessLogWriter::open(log_path)?)),
+            None => None,
+        };
+
+        // Flushed entries must be stamped above every sequence already on disk
+        let dirty = DirtyTracker::new();
+        if !read_only {
+            for file_path in list_data_files(&data_dir)? {
+                dirty.seed_flush_sequence(DataFileReader::max_sequence_number(&file_path)?);
+            }
+        }
 
         Ok(Self {
-            data: Arc::new(RwLock::new(data)),
-            wal: Mutex::new(wal),
-            dirty: Arc::new(DirtyTracker::new()),
+            data: Arc::new(RamStore::new(data, config.use_concurrent_hashmap)),
+            wal,
+            dirty: Arc::new(dirty),
             trickle: Mutex::new(None),
             path,
             config,
+            next_sequence: AtomicU64::new(max_sequence + 1),
+            write_generation: AtomicU64::new(0),
+            #[cfg(any(test, feature = "testing"))]
+            read_locks: AtomicU64::new(0),
+            _lock_file: lock_file,
+            access_log,
+            #[cfg(feature = "metrics")]
+            metrics: std::sync::OnceLock::new(),
+            shutdown_hooks: Mutex::new(Vec::new()),
+            subscribers: Arc::new(WriteSubscribers::default()),
+            lru: Mutex::new(lru),
+            history_cache: Mutex::new(HistoryCache::default()),
+            wal_positions: Mutex::new(wal_positions),
         })
     }
 
@@ -104,6 +493,9 @@ impl ClawStoreEngine {
     /// The trickle thread periodically flushes dirty entries from RAM to data files.
     /// Not starting trickle is valid — the WAL provides crash safety regardless.
     pub fn start_trickle(&self) -> ClawResult<()> {
+        if self.wal.is_none() {
+            return Err(ClawError::ReadOnly { path: self.path.clone() });
+        }
         let data_dir = self.path.join("data");
         let handle = start_trickle(
             data_dir,
@@ -116,6 +508,212 @@ impl ClawStoreEngine {
         Ok(())
     }
 
+    /// Reload RAM from disk without closing the engine.
+    ///
+    /// For use after the WAL directory was changed externally (e.g. by a
+    /// restore tool). Stops the trickle engine, seals the current WAL file
+    /// and, holding the WAL mutex and the RAM write lock, rebuilds RAM (and
+    /// the cache-mode access order) exactly as `open` would before starting
+    /// a fresh WAL writer. The trickle engine is restarted if it was
+    /// running, whether or not the reload succeeded. Callers holding an
+    /// `Arc<ClawStoreEngine>` keep using the same instance throughout.
+    ///
+    /// On error nothing has been swapped in: RAM is unchanged and writes
+    /// continue in a new WAL file after the sealed one.
+    pub fn reopen(&self) -> ClawResult<()> {
+        let trickle_was_running = match self.trickle.lock().take() {
+            Some(handle) => {
+                handle.shutdown();
+                true
+            }
+            None => false,
+        };
+
+        let reloaded = self.reload_state();
+
+        if trickle_was_running {
+            self.start_trickle()?;
+        }
+        reloaded
+    }
+
+    /// The locked part of `reopen`. The new state and WAL writer are fully
+    /// built before either replaces the current one.
+    fn reload_state(&self) -> ClawResult<()> {
+        let mut lru = self.lru.lock();
+        let mut wal = self.wal.as_ref().map(|wal| wal.lock());
+        let mut data = self.data.write();
+
+        if let Some(writer) = wal.as_deref_mut() {
+            writer.seal("reopen")?;
+        }
+        let (reloaded, positions, max_sequence) = load_state(&self.path, &self.config)?;
+        let new_writer = if wal.is_some() {
+            Some(WalWriter::new(self.path.join("wal"))?.with_encryption_key(self.config.encryption_key))
+        } else {
+            None
+        };
+
+        self.write_generation.fetch_add(1, Ordering::Release);
+        if self.config.cache_mode.is_some() {
+            *lru = LruOrder::recovered(&reloaded);
+        }
+        data.replace_all(reloaded);
+        *self.wal_positions.lock() = positions;
+        // Cached history may predate files the restore replaced
+        *self.history_cache.lock() = HistoryCache::default();
+        self.next_sequence.fetch_max(max_sequence + 1, Ordering::SeqCst);
+        if let (Some(writer), Some(new_writer)) = (wal.as_deref_mut(), new_writer) {
+            *writer = new_writer;
+        }
+        Ok(())
+    }
+
+    /// Close the latest data file and return its path, e.g. for a backup.
+    ///
+    /// Stops the trickle engine (which flushes outstanding dirty keys first),
+    /// rotates the latest data file with `DataFileWriter::force_rotate` and
+    /// restarts trickle if it was running; trickle then appends to the new
+    /// file. Fails if no data file exists yet.
+    pub fn rotate_data_file(&self) -> ClawResult<PathBuf> {
+        if self.is_read_only() {
+            return Err(ClawError::ReadOnly { path: self.path.clone() });
+        }
+        let trickle_was_running = match self.trickle.lock().take() {
+            Some(handle) => {
+                handle.shutdown();
+                true
+            }
+            None => false,
+        };
+
+        let data_dir = self.path.join("data");
+        let rotated = match latest_data_file(&data_dir) {
+            Some(current) => DataFileWriter::open_existing(&current, self.config.max_data_file_size_bytes)
+                .and_then(|mut writer| writer.force_rotate()),
+            None => Err(ClawError::Io {
+                path: Some(data_dir),
+                kind: std::io::ErrorKind::NotFound,
+                message: "No data file to rotate".to_string(),
+                inner: None,
+            }),
+        };
+
+        if trickle_was_running {
+            self.start_trickle()?;
+        }
+        rotated
+    }
+
+    /// Reclaim unused disk space in data files and the WAL.
+    ///
+    /// 1. Stops the trickle engine.
+    /// 2. Holding the WAL mutex, flushes every dirty key to the latest data
+    ///    file and rotates the WAL, so every sealed WAL file is now covered
+    ///    by the data files.
+    /// 3. Merges all data files into one with `merge_files_to`. Merging the
+    ///    whole directory lets tombstones be dropped safely; compacting files
+    ///    one by one could drop a tombstone still shadowing an older file.
+    /// 4. Deletes the sealed WAL files.
+    /// 5. Restarts trickle if it was running.
+    ///
+    /// Step 4 only runs with `Config::warm_on_open`: otherwise reopening
+    /// loads nothing but the WAL and the deleted entries would be lost.
+    /// Each step leaves a valid store, so a failure in step 4 keeps the
+    /// merged data file; leftover WAL files only replay what it holds.
+    pub fn vacuum(&self) -> ClawResult<VacuumReport> {
+        if self.is_read_only() {
+            return Err(ClawError::ReadOnly { path: self.path.clone() });
+        }
+        let started = std::time::Instant::now();
+        let bytes_before = self.disk_usage()?;
+
+        let trickle_was_running = match self.trickle.lock().take() {
+            Some(handle) => {
+                handle.shutdown();
+                true
+            }
+            None => false,
+        };
+        let result = self.vacuum_stopped();
+        if trickle_was_running {
+            self.start_trickle()?;
+        }
+        let (data_files_compacted, wal_files_removed) = result?;
+
+        let bytes_after = self.disk_usage()?;
+        Ok(VacuumReport {
+            bytes_before,
+            bytes_after,
+            bytes_saved: bytes_before.saturating_sub(bytes_after),
+            time_elapsed: started.elapsed(),
+            data_files_compacted,
+            wal_files_removed,
+        })
+    }
+
+    /// Steps 2-4 of `vacuum`, with trickle stopped. Returns the number of
+    /// data files merged and WAL files removed.
+    fn vacuum_stopped(&self) -> ClawResult<(usize, usize)> {
+        let data_dir = self.path.join("data");
+        let sealed_wal_files = {
+            let mut wal = self.wal()?;
+            let max_size = self.config.max_data_file_size_bytes;
+            let mut writer = match latest_data_file(&data_dir) {
+                Some(current) => DataFileWriter::open_existing(&current, max_size)?,
+                None => DataFileWriter::new(&data_dir, max_size)?,
+            };
+            writer.set_sector_aligned(self.config.sector_aligned_writes);
+            writer.set_sequence_source(self.dirty.flush_sequence());
+            flush_dirty(&*self.data, &self.dirty, &mut writer, None, self.config.verify_writes_after_flush);
+            let unflushed = self.dirty_count();
+            if unflushed > 0 {
+                return Err(ClawError::Io {
+                    path: Some(data_dir),
+                    kind: std::io::ErrorKind::Other,
+                    message: format!("Failed to flush {} dirty keys before vacuum", unflushed),
+                    inner: None,
+                });
+            }
+            if self.config.warm_on_open {
+                wal.rotate_now()?;
+                wal.archived_paths()?
+            } else {
+                Vec::new()
+            }
+        };
+
+        let data_files = list_data_files(&data_dir)?;
+        if !data_files.is_empty() {
+            let sources: Vec<&Path> = data_files.iter().map(PathBuf::as_path).collect();
+            let (merged, _) = merge_files_to(&sources, &data_dir)?;
+            eprintln!(
+                "[ClawStore] Vacuum merged {} data files into {}",
+                data_files.len(),
+                merged.display()
+            );
+        }
+
+        for wal_file in &sealed_wal_files {
+            std::fs::remove_file(wal_file)
+                .with_context("Failed to remove sealed WAL file")
+                .with_path(wal_file)?;
+        }
+        Ok((data_files.len(), sealed_wal_files.len()))
+    }
+
+    /// Bytes held by data files and WAL files.
+    fn disk_usage(&self) -> ClawResult<u64> {
+        let mut total = WalReader::new(self.path.join("wal")).total_size_bytes()?;
+        for file_path in list_data_files(&self.path.join("data"))? {
+            total += std::fs::metadata(&file_path)
+                .with_context("Failed to stat data file")
+                .with_path(&file_path)?
+                .len();
+        }
+        Ok(total)
+    }
+
     /// Stop the background trickle engine gracefully.
     pub fn stop_trickle(&self) {
         let mut trickle = self.trickle.lock();
@@ -126,11 +724,103 @@ impl ClawStoreEngine {
 
     /// Get value for key from RAM.
     ///
-    /// Acquires a read lock — multiple concurrent readers allowed.
+    /// Acquires a read lock — multiple concurrent readers allowed. With
+    /// `Config::use_concurrent_hashmap` only the key's shard is locked, so
+    /// writers to other keys do not block it.
     /// Never touches the WAL or disk. This is the hot path.
     pub fn get(&self, key: &[u8]) -> ClawResult<Option<Vec<u8>>> {
-        let data = self.data.read();
-        Ok(data.get(key).cloned())
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let value = self.data.get(key);
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.get() {
+            metrics.get_total.inc();
+            metrics.get_latency_seconds.observe(started.elapsed().as_secs_f64());
+        }
+        if self.config.cache_mode.is_some() && value.is_some() {
+            self.lru.lock().touch(key);
+        }
+        self.log_access("get", key);
+        Ok(value)
+    }
+
+    /// Get values for several keys under a single read lock.
+    ///
+    /// All lookups observe the same RAM state — no write can interleave
+    /// between them. Results are returned in the same order as `keys`.
+    pub fn get_many<K: AsRef<[u8]>>(&self, keys: &[K]) -> ClawResult<Vec<Option<Vec<u8>>>> {
+        let data = self.read_data();
+        Ok(keys.iter().map(|k| data.get(k.as_ref()).cloned()).collect())
+    }
+
+    /// `get`, plus the WAL record that last wrote the key and whether the
+    /// trickle engine has yet to flush it. Meant for debugging state.
+    ///
+    /// Positions are kept for every key written since open (and for keys
+    /// replayed from the WAL on open), in step with RAM.
+    pub fn get_with_metadata(&self, key: &[u8]) -> ClawResult<Option<EntryMetadata>> {
+        let data = self.read_data();
+        let Some(value) = data.get(key) else {
+            return Ok(None);
+        };
+        let position = self.wal_positions.lock().get(key).copied();
+        Ok(Some(EntryMetadata {
+            value: value.clone(),
+            last_wal_sequence: position.map(|p| p.sequence),
+            last_wal_offset: position.map(|p| p.offset),
+            dirty: self.dirty.is_dirty(key),
+        }))
+    }
+
+    /// Value of `key` as of the end of WAL file `seq` (see `wal_sequence`),
+    /// reconstructed by replaying the key's WAL records.
+    ///
+    /// Replays every retained WAL file up to `seq` (later files are not
+    /// read), so keep it off hot paths. Results for
+    /// WAL files that can no longer change (all but the active one) are kept
+    /// in a small LRU cache. Only WAL history is consulted: a key whose last
+    /// write before `seq` lives only in data files reads as `None`, and a
+    /// `seq` older than every retained WAL file (e.g. after `vacuum`) fails
+    /// with a `NotFound` I/O error.
+    pub fn get_at_wal_sequence(&self, key: &[u8], seq: u64) -> ClawResult<Option<Vec<u8>>> {
+        let cache_key = HistoryCache::cache_key(key, seq);
+        if let Some(value) = self.history_cache.lock().get(&cache_key) {
+            return Ok(value);
+        }
+        // Read before replaying so a rotation mid-replay cannot make a partial answer cacheable
+        let closed = match &self.wal {
+            Some(wal) => seq < wal.lock().current_sequence(),
+            None => true,
+        };
+
+        let wal_dir = self.path.join("wal");
+        let reader = WalReader::new(&wal_dir)
+            .with_encryption_key(self.config.encryption_key)
+            .with_recovery_mode(self.config.wal_recovery_mode);
+        let oldest = reader.list_files()?.first().map(|file| file.sequence);
+        if oldest.map_or(true, |oldest| seq < oldest) {
+            return Err(ClawError::Io {
+                path: Some(wal_dir),
+                kind: std::io::ErrorKind::NotFound,
+                message: format!("WAL file {} is no longer retained", seq),
+                inner: None,
+            });
+        }
+
+        let mut value = None;
+        for entry in reader.read_through(seq)? {
+            let entry = entry?;
+            if entry.key == key {
+                value = match entry.operation {
+                    Operation::Put => Some(entry.value),
+                    _ => None,
+                };
+            }
+        }
+        if closed {
+            self.history_cache.lock().insert(cache_key, value.clone());
+        }
+        Ok(value)
     }
 
     /// Put key-value pair with full durability.
@@ -141,85 +831,723 @@ impl ClawStoreEngine {
     /// 3. Mark dirty for trickle
     ///
     /// If WAL write fails, RAM is NEVER modified.
+    ///
+    /// With `Config::cache_mode` set, this is
+    /// [`put_and_evict_lru`](Self::put_and_evict_lru) at the configured size.
     pub fn put(&self, key: &[u8], value: &[u8]) -> ClawResult<()> {
-        {
-            let mut wal = self.wal.lock();
+        if let Some(cache_size) = self.config.cache_mode {
+            return self.put_and_evict_lru(key, value, cache_size).map(|_| ());
+        }
+        let position = {
+            let mut wal = self.wal()?;
             wal.append_durable(key, value, Operation::Put)?;
+            wal.last_record_position()
+        };
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            data.insert(key.to_vec(), value.to_vec());
+            self.wal_positions.lock().insert(key.to_vec(), position);
         }
+        self.dirty.mark_dirty(key);
+        #[cfg(feature = "metrics")]
+        self.record_put_metrics();
+        self.log_access("put", key);
+        self.subscribers.publish(key, Operation::Put);
+        Ok(())
+    }
+
+    /// `put`, then call `on_commit` with the entry's WAL offset.
+    ///
+    /// The callback runs synchronously on the calling thread once the entry
+    /// is durable and RAM is updated. The offset is within the WAL file that
+    /// was active for the write (`wal_sequence()`, unless the WAL has rotated
+    /// since). Unlike `subscribe_to_writes`, it fires for this write only.
+    pub fn put_with_callback<F>(&self, key: &[u8], value: &[u8], on_commit: F) -> ClawResult<()>
+    where
+        F: FnOnce(u64) + Send + 'static,
+    {
+        let (offset, position) = {
+            let mut wal = self.wal()?;
+            let offset = wal.append_durable(key, value, Operation::Put)?;
+            (offset, wal.last_record_position())
+        };
         {
             let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
             data.insert(key.to_vec(), value.to_vec());
+            self.wal_positions.lock().insert(key.to_vec(), position);
         }
         self.dirty.mark_dirty(key);
+        #[cfg(feature = "metrics")]
+        self.record_put_metrics();
+        self.log_access("put", key);
+        self.subscribers.publish(key, Operation::Put);
+        on_commit(offset);
         Ok(())
     }
 
     /// Put WITHOUT durable sync (fast path). Still marks dirty.
     pub fn put_fast(&self, key: &[u8], value: &[u8]) -> ClawResult<()> {
-        {
-            let mut wal = self.wal.lock();
+        let position = {
+            let mut wal = self.wal()?;
             wal.append_fast(key, value, Operation::Put)?;
-        }
+            wal.last_record_position()
+        };
         {
             let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
             data.insert(key.to_vec(), value.to_vec());
+            self.wal_positions.lock().insert(key.to_vec(), position);
         }
         self.dirty.mark_dirty(key);
+        self.subscribers.publish(key, Operation::Put);
         Ok(())
     }
 
-    /// Sync the WAL to persistent storage.
+    /// `put_fast` only if `key` is absent. Returns `false` (writing nothing)
+    /// if it already exists.
     ///
-    /// Call this after a batch of `put_fast` writes to make them all
-    /// durable at once. One fsync for the entire batch instead of per-write.
-    /// This is the path Reth uses during block sync: buffer all writes,
-    /// then commit with a single fsync.
-    pub fn sync_wal(&self) -> ClawResult<()> {
-        let wal = self.wal.lock();
-        wal.sync()
+    /// Like `compare_and_delete`, holds the WAL mutex and the RAM write lock
+    /// together across the check and the write, so of several concurrent
+    /// callers for one key exactly one inserts.
+    pub fn put_fast_if_absent(&self, key: &[u8], value: &[u8]) -> ClawResult<bool> {
+        {
+            let mut wal = self.wal()?;
+            let mut data = self.data.write();
+            if data.contains_key(key) {
+                return Ok(false);
+            }
+            wal.append_fast(key, value, Operation::Put)?;
+            self.write_generation.fetch_add(1, Ordering::Release);
+            data.insert(key.to_vec(), value.to_vec());
+            self.wal_positions.lock().insert(key.to_vec(), wal.last_record_position());
+        }
+        self.dirty.mark_dirty(key);
+        self.subscribers.publish(key, Operation::Put);
+        Ok(true)
     }
 
-    /// Delete with full durability. Marks dirty so trickle writes tombstone.
-    pub fn delete(&self, key: &[u8]) -> ClawResult<()> {
-        {
-            let mut wal = self.wal.lock();
-            wal.append_durable(key, &[], Operation::Delete)?;
+    /// Insert every entry only if none of the keys exists yet.
+    ///
+    /// The existence check, the WAL batch (one durable_sync, recovered all
+    /// or nothing) and the RAM inserts happen with the WAL mutex and the RAM
+    /// write lock held together, so a set of keys is never partially
+    /// inserted. Returns `false` (and writes nothing) if any key is present.
+    pub fn put_many_if_all_absent(&self, entries: &[(&[u8], &[u8])]) -> ClawResult<bool> {
+        if entries.is_empty() {
+            return Ok(true);
         }
         {
+            let mut wal = self.wal()?;
             let mut data = self.data.write();
-            data.remove(key);
+            if entries.iter().any(|(key, _)| data.contains_key(key)) {
+                return Ok(false);
+            }
+            let batch: Vec<(&[u8], &[u8], Operation)> = entries.iter()
+                .map(|(k, v)| (*k, *v, Operation::Put))
+                .collect();
+            wal.append_batch_durable(&batch)?;
+            let position = wal.last_record_position();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            for (key, value) in entries {
+                data.insert(key.to_vec(), value.to_vec());
+                positions.insert(key.to_vec(), position);
+            }
         }
-        self.dirty.mark_dirty(key);
-        Ok(())
+        for (key, _) in entries {
+            self.dirty.mark_dirty(key);
+            self.log_access("put", key);
+            self.subscribers.publish(key, Operation::Put);
+        }
+        Ok(true)
     }
 
-    /// Check if key exists in RAM.
-    pub fn contains_key(&self, key: &[u8]) -> bool {
-        let data = self.data.read();
-        data.contains_key(key)
+    /// Put many entries with one WAL lock acquisition and one sync at the end.
+    ///
+    /// The WAL mutex is held while every entry is appended (no per-entry
+    /// sync), then released before RAM is updated under a single write lock,
+    /// and finally `sync_wal` makes the whole batch durable. Meant for block
+    /// import, where `put_fast` per key contends on the WAL mutex.
+    ///
+    /// If an append fails, RAM is not modified, but entries appended before
+    /// the failure may still be replayed on recovery (as with `put_fast`).
+    pub fn put_many_fast_sync_once(
+        &self,
+        entries: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+    ) -> ClawResult<()> {
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = entries.into_iter().collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let appended_at: Vec<WalPosition> = {
+            let mut wal = self.wal()?;
+            let mut appended_at = Vec::with_capacity(entries.len());
+            for (key, value) in &entries {
+                wal.append_fast(key, value, Operation::Put)?;
+                appended_at.push(wal.last_record_position());
+            }
+            appended_at
+        };
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            for ((key, value), position) in entries.iter().zip(appended_at) {
+                data.insert(key.clone(), value.clone());
+                positions.insert(key.clone(), position);
+            }
+        }
+        for (key, _) in &entries {
+            self.dirty.mark_dirty(key);
+            self.subscribers.publish(key, Operation::Put);
+        }
+        self.sync_wal()
     }
 
-    /// Number of key-value pairs in RAM.
-    pub fn len(&self) -> usize {
-        let data = self.data.read();
-        data.len()
+    /// Put many key-value pairs with a single durable sync.
+    ///
+    /// All entries are appended to the WAL as one batch followed by one
+    /// durable_sync, then applied to RAM under a single write-lock
+    /// acquisition. If the WAL write fails, RAM is NEVER modified.
+    pub fn put_batch<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, entries: &[(K, V)]) -> ClawResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let position = {
+            let batch: Vec<(&[u8], &[u8], Operation)> = entries.iter()
+                .map(|(k, v)| (k.as_ref(), v.as_ref(), Operation::Put))
+                .collect();
+            let mut wal = self.wal()?;
+            wal.append_batch_durable(&batch)?;
+            wal.last_record_position()
+        };
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            for (key, value) in entries {
+                data.insert(key.as_ref().to_vec(), value.as_ref().to_vec());
+                positions.insert(key.as_ref().to_vec(), position);
+            }
+        }
+        for (key, _) in entries {
+            self.dirty.mark_dirty(key.as_ref());
+            self.subscribers.publish(key.as_ref(), Operation::Put);
+        }
+        Ok(())
     }
 
-    /// Returns true if the store has no entries.
-    pub fn is_empty(&self) -> bool {
-        let data = self.data.read();
-        data.is_empty()
+    /// Apply a mix of puts (`Some(value)`) and deletes (`None`) with a
+    /// single durable sync.
+    ///
+    /// Same guarantees as [`put_batch`](Self::put_batch): one WAL batch
+    /// record that recovery replays all or nothing, then RAM under a single
+    /// write-lock acquisition. Operations apply in slice order, so a key
+    /// listed twice ends up with its last operation.
+    pub fn apply_batch<K: AsRef<[u8]>, V: AsRef<[u8]>>(&self, ops: &[(K, Option<V>)]) -> ClawResult<()> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let position = {
+            let batch: Vec<(&[u8], &[u8], Operation)> = ops.iter()
+                .map(|(k, v)| match v {
+                    Some(v) => (k.as_ref(), v.as_ref(), Operation::Put),
+                    None => (k.as_ref(), &[][..], Operation::Delete),
+                })
+                .collect();
+            let mut wal = self.wal()?;
+            wal.append_batch_durable(&batch)?;
+            wal.last_record_position()
+        };
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            for (key, value) in ops {
+                match value {
+                    Some(value) => {
+                        data.insert(key.as_ref().to_vec(), value.as_ref().to_vec());
+                        positions.insert(key.as_ref().to_vec(), position);
+                    }
+                    None => {
+                        data.remove(key.as_ref());
+                        positions.remove(key.as_ref());
+                    }
+                }
+            }
+        }
+        for (key, value) in ops {
+            self.dirty.mark_dirty(key.as_ref());
+            let operation = if value.is_some() { Operation::Put } else { Operation::Delete };
+            self.subscribers.publish(key.as_ref(), operation);
+        }
+        Ok(())
     }
 
-    /// Data directory path.
-    pub fn path(&self) -> &Path {
-        &self.path
+    /// Put writes spanning multiple tables with a single durable sync.
+    ///
+    /// Same guarantees as [`put_batch`](Self::put_batch).
+    pub fn put_cross_table(&self, writes: &[CrossTableWrite]) -> ClawResult<()> {
+        let entries: Vec<(Vec<u8>, &[u8])> = writes.iter()
+            .map(|w| (w.prefixed_key(), w.value.as_slice()))
+            .collect();
+        self.put_batch(&entries)
     }
 
-    /// Keys waiting to be flushed by trickle.
-    pub fn dirty_count(&self) -> usize {
-        self.dirty.dirty_count()
-    }
+    /// Put entries with full durability, preserving their order on disk.
+    ///
+    /// Each entry is stamped with a monotonic sequence number recorded in its
+    /// WAL entry, and the trickle engine flushes dirty keys in sequence order,
+    /// so data files reflect the order writes were applied (deterministic
+    /// replay). The whole slice shares one durable_sync.
+    pub fn put_durable_ordered(&self, entries: &[(&[u8], &[u8])]) -> ClawResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let (sequenced, position) = {
+            // Sequences are taken under the WAL lock so WAL order == sequence order
+            let mut wal = self.wal()?;
+            let first = self.next_sequence.fetch_add(entries.len() as u64, Ordering::Relaxed);
+            let sequenced: Vec<(&[u8], &[u8], u64)> = entries.iter()
+                .zip(first..)
+                .map(|((k, v), seq)| (*k, *v, seq))
+                .collect();
+            wal.append_sequenced_batch_durable(&sequenced)?;
+            (sequenced, wal.last_record_position())
+        };
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            for (key, value, _) in &sequenced {
+                data.insert(key.to_vec(), value.to_vec());
+                positions.insert(key.to_vec(), position);
+            }
+        }
+        for (key, _, seq) in &sequenced {
+            self.dirty.mark_dirty_sequenced(key, *seq);
+            self.subscribers.publish(key, Operation::Put);
+        }
+        Ok(())
+    }
+
+    /// Sync the WAL to persistent storage.
+    ///
+    /// Call this after a batch of `put_fast` writes to make them all
+    /// durable at once. One fsync for the entire batch instead of per-write.
+    /// This is the path Reth uses during block sync: buffer all writes,
+    /// then commit with a single fsync.
+    pub fn sync_wal(&self) -> ClawResult<()> {
+        let wal = self.wal()?;
+        wal.sync()
+    }
+
+    /// Seal the active WAL file and start a new one, e.g. before a backup.
+    ///
+    /// Returns the path of the closed file. See `WalWriter::rotate_now`.
+    pub fn rotate_wal(&self) -> ClawResult<PathBuf> {
+        self.wal()?.rotate_now()
+    }
+
+    /// Sequence number of the active WAL file (0 in read-only mode).
+    pub fn wal_sequence(&self) -> u64 {
+        self.wal.as_ref().map_or(0, |wal| wal.lock().current_sequence())
+    }
+
+    /// Entries written to the active WAL file since it was opened or
+    /// rotated to (0 in read-only mode).
+    pub fn wal_entries_in_current_file(&self) -> u64 {
+        self.wal.as_ref().map_or(0, |wal| wal.lock().entries_since_last_rotation())
+    }
+
+    /// Delete with full durability. Marks dirty so trickle writes tombstone.
+    pub fn delete(&self, key: &[u8]) -> ClawResult<()> {
+        {
+            let mut wal = self.wal()?;
+            wal.append_durable(key, &[], Operation::Delete)?;
+        }
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            data.remove(key);
+            self.wal_positions.lock().remove(key);
+        }
+        self.dirty.mark_dirty(key);
+        if self.config.cache_mode.is_some() {
+            self.lru.lock().forget(key);
+        }
+        self.log_access("delete", key);
+        self.subscribers.publish(key, Operation::Delete);
+        Ok(())
+    }
+
+    /// Put, first evicting the least recently used key if RAM already holds
+    /// `cache_size` keys. Returns the evicted key.
+    ///
+    /// The eviction is a delete: its tombstone and the put go to the WAL as
+    /// one batch with a single durable sync, so recovery never sees one
+    /// without the other. Overwriting an existing key never evicts.
+    ///
+    /// Recency covers keys written here, plus reads, `put`s and deletes when
+    /// `Config::cache_mode` is set; keys written by other paths are never
+    /// chosen for eviction.
+    pub fn put_and_evict_lru(&self, key: &[u8], value: &[u8], cache_size: usize) -> ClawResult<Option<Vec<u8>>> {
+        // Held throughout so concurrent cache-mode puts cannot pick the same victim
+        let mut lru = self.lru.lock();
+        let evicted = {
+            let data = self.read_data();
+            if data.contains_key(key) || data.len() < cache_size {
+                None
+            } else {
+                // Skip keys already removed by paths that do not update the order
+                std::iter::from_fn(|| lru.pop_oldest())
+                    .find(|candidate| candidate.as_slice() != key && data.contains_key(candidate))
+            }
+        };
+
+        let position = {
+            let mut batch: Vec<(&[u8], &[u8], Operation)> = Vec::with_capacity(2);
+            if let Some(victim) = &evicted {
+                batch.push((victim, &[], Operation::Delete));
+            }
+            batch.push((key, value, Operation::Put));
+            let appended = self.wal().and_then(|mut wal| {
+                wal.append_batch_durable(&batch)?;
+                Ok(wal.last_record_position())
+            });
+            match appended {
+                Ok(position) => position,
+                Err(e) => {
+                    // RAM untouched — the victim stays, now as most recently used
+                    if let Some(victim) = &evicted {
+                        lru.touch(victim);
+                    }
+                    return Err(e);
+                }
+            }
+        };
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            if let Some(victim) = &evicted {
+                data.remove(victim);
+                positions.remove(victim);
+            }
+            data.insert(key.to_vec(), value.to_vec());
+            positions.insert(key.to_vec(), position);
+        }
+        lru.touch(key);
+        drop(lru);
+
+        if let Some(victim) = &evicted {
+            self.dirty.mark_dirty(victim);
+            self.log_access("delete", victim);
+            self.subscribers.publish(victim, Operation::Delete);
+        }
+        self.dirty.mark_dirty(key);
+        #[cfg(feature = "metrics")]
+        self.record_put_metrics();
+        self.log_access("put", key);
+        self.subscribers.publish(key, Operation::Put);
+        Ok(evicted)
+    }
+
+    /// Delete WITHOUT durable sync (fast path). Still marks dirty.
+    ///
+    /// Call `sync_wal` after a run of fast deletes to make them durable.
+    pub fn delete_fast(&self, key: &[u8]) -> ClawResult<()> {
+        {
+            let mut wal = self.wal()?;
+            wal.append_fast(key, &[], Operation::Delete)?;
+        }
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            data.remove(key);
+            self.wal_positions.lock().remove(key);
+        }
+        self.dirty.mark_dirty(key);
+        self.subscribers.publish(key, Operation::Delete);
+        Ok(())
+    }
+
+    /// `delete_fast` for many keys, holding the WAL mutex and the RAM write
+    /// lock for the whole set so no other write interleaves with it.
+    ///
+    /// If an append fails, RAM is not modified, but deletes appended before
+    /// the failure may still be replayed on recovery (as with `delete_fast`).
+    pub fn delete_batch_fast<K: AsRef<[u8]>>(&self, keys: &[K]) -> ClawResult<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        {
+            let mut wal = self.wal()?;
+            let mut data = self.data.write();
+            for key in keys {
+                wal.append_fast(key.as_ref(), &[], Operation::Delete)?;
+            }
+            self.write_generation.fetch_add(1, Ordering::Release);
+            let mut positions = self.wal_positions.lock();
+            for key in keys {
+                data.remove(key.as_ref());
+                positions.remove(key.as_ref());
+            }
+        }
+        for key in keys {
+            self.dirty.mark_dirty(key.as_ref());
+            self.subscribers.publish(key.as_ref(), Operation::Delete);
+        }
+        Ok(())
+    }
+
+    /// Delete `key` only if its current value equals `expected_value`.
+    ///
+    /// The WAL mutex and the RAM write lock are held together across the
+    /// check, the durable delete entry and the removal, so no other write
+    /// can slip in between. Readers wait out the WAL sync. Returns `false`
+    /// (and writes nothing) if the key is absent or holds another value.
+    pub fn compare_and_delete(&self, key: &[u8], expected_value: &[u8]) -> ClawResult<bool> {
+        {
+            let mut wal = self.wal()?;
+            let mut data = self.data.write();
+            if data.get(key).as_deref() != Some(expected_value) {
+                return Ok(false);
+            }
+            wal.append_durable(key, &[], Operation::Delete)?;
+            self.write_generation.fetch_add(1, Ordering::Release);
+            data.remove(key);
+            self.wal_positions.lock().remove(key);
+        }
+        self.dirty.mark_dirty(key);
+        self.log_access("delete", key);
+        self.subscribers.publish(key, Operation::Delete);
+        Ok(true)
+    }
+
+    /// Apply a WAL entry received from elsewhere (e.g. a replication
+    /// primary) to RAM, bypassing the local WAL.
+    ///
+    /// The entry is checked against the key/value size limits, applied under
+    /// the write lock and marked dirty for trickle. Nothing is appended to the
+    /// local WAL, so the write is only crash-safe once trickle has flushed it;
+    /// the sender's WAL is the durable copy. SEAL and batch header records are
+    /// ignored. Fails with `ReadOnly` on a read-only engine.
+    pub fn apply_wal_entry(&self, entry: &WalEntry) -> ClawResult<()> {
+        self.apply_wal_entries_batch(std::slice::from_ref(entry)).map(|_| ())
+    }
+
+    /// Apply several replicated WAL entries in order under one write lock.
+    ///
+    /// Every entry is validated before any is applied, so an invalid entry
+    /// leaves RAM untouched. Returns the number of Put/Delete entries applied.
+    pub fn apply_wal_entries_batch(&self, entries: &[WalEntry]) -> ClawResult<usize> {
+        if self.is_read_only() {
+            return Err(ClawError::ReadOnly { path: self.path.clone() });
+        }
+        let applied: Vec<&WalEntry> = entries.iter()
+            .filter(|e| matches!(e.operation, Operation::Put | Operation::Delete))
+            .collect();
+        for entry in &applied {
+            check_entry_size(&entry.key, &entry.value)?;
+        }
+        if applied.is_empty() {
+            return Ok(0);
+        }
+        {
+            let mut data = self.data.write();
+            self.write_generation.fetch_add(1, Ordering::Release);
+            // No local WAL record backs these writes
+            let mut positions = self.wal_positions.lock();
+            for entry in &applied {
+                positions.remove(&entry.key);
+                match entry.operation {
+                    Operation::Put => {
+                        data.insert(entry.key.clone(), entry.value.clone());
+                    }
+                    _ => {
+                        data.remove(&entry.key);
+                    }
+                }
+            }
+        }
+        for entry in &applied {
+            self.dirty.mark_dirty(&entry.key);
+            self.subscribers.publish(&entry.key, entry.operation);
+        }
+        Ok(applied.len())
+    }
+
+    /// Register this engine's metrics with a Prometheus registry.
+    ///
+    /// Registers `clawstore_ram_entries`, `clawstore_dirty_keys`,
+    /// `clawstore_trickle_cycles_total`, `clawstore_wal_size_bytes`,
+    /// `clawstore_put_total`, `clawstore_get_total` and
+    /// `clawstore_get_latency_seconds`. Values update on `put` and `get`
+    /// from then on. Can be called once per engine.
+    #[cfg(feature = "metrics")]
+    pub fn register_metrics(&self, registry: &prometheus::Registry) -> ClawResult<()> {
+        if self.metrics.get().is_some() {
+            return Err(ClawError::Metrics { reason: "metrics already registered".to_string() });
+        }
+        let metrics = EngineMetrics::register(registry)
+            .map_err(|e| ClawError::Metrics { reason: e.to_string() })?;
+        let _ = self.metrics.set(metrics);
+        Ok(())
+    }
+
+    /// Register a cleanup callback to run when the engine is dropped.
+    ///
+    /// Hooks run in registration order, after the trickle engine has stopped
+    /// and before the WAL is sealed and the store lock released — e.g. to
+    /// flush external metrics or close handles that wrap this engine. A
+    /// panicking hook is logged and the remaining hooks still run.
+    pub fn register_shutdown_hook(
+        &self,
+        name: &str,
+        hook: impl Fn() + Send + Sync + 'static,
+    ) -> ClawResult<()> {
+        self.shutdown_hooks.lock().push((name.to_string(), Box::new(hook)));
+        Ok(())
+    }
+
+    /// Refresh counters and gauges after a successful `put`.
+    #[cfg(feature = "metrics")]
+    fn record_put_metrics(&self) {
+        if let Some(metrics) = self.metrics.get() {
+            metrics.put_total.inc();
+            metrics.ram_entries.set(self.len() as i64);
+            metrics.dirty_keys.set(self.dirty_count() as i64);
+            metrics.set_trickle_cycles(self.trickle_cycles());
+            if let Some(wal) = &self.wal {
+                metrics.wal_size_bytes.set(wal.lock().current_size() as i64);
+            }
+        }
+    }
+
+    /// Read-lock the RAM map.
+    fn read_data(&self) -> RamReadGuard<'_> {
+        #[cfg(any(test, feature = "testing"))]
+        self.read_locks.fetch_add(1, Ordering::Relaxed);
+        self.data.read()
+    }
+
+    /// Times the RAM map has been read-locked by this engine's read
+    /// methods, for tests checking that batched reads lock once.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn read_lock_count(&self) -> u64 {
+        self.read_locks.load(Ordering::Relaxed)
+    }
+
+    /// Lock the WAL writer, or fail with `ReadOnly` if there is none.
+    fn wal(&self) -> ClawResult<MutexGuard<'_, WalWriter>> {
+        match &self.wal {
+            Some(wal) => Ok(wal.lock()),
+            None => Err(ClawError::ReadOnly { path: self.path.clone() }),
+        }
+    }
+
+    /// Receive a `WriteEvent` on `tx` after every successful write: `put`,
+    /// `put_fast`, `delete` and their variants, and one event per key of
+    /// `put_batch`, `apply_batch`, `put_cross_table` and the other batch
+    /// writes.
+    ///
+    /// Events are sent after RAM is updated, in write order per writer.
+    /// Keep the returned handle alive for as long as events are wanted.
+    /// With an unbounded channel sending never blocks the writer and events
+    /// queue up until the receiver drains them; a full bounded channel
+    /// blocks the writer until there is room, so its receiver must not
+    /// write to this engine.
+    pub fn subscribe_to_writes(&self, tx: Sender<WriteEvent>) -> SubscriptionHandle {
+        self.subscribers.subscribe(tx)
+    }
+
+    /// Stream this store's contents and subsequent writes to a replica.
+    ///
+    /// The stream starts with a snapshot of RAM, then yields an event for
+    /// every write published to `subscribe_to_writes` subscribers. A
+    /// replica applies each event's `to_wal_entries` with
+    /// `apply_wal_entries_batch`. See `ReplicaStream` for ordering details
+    /// and for memory use when the stream is read slowly.
+    pub fn create_replica_stream(&self) -> ReplicaStream {
+        ReplicaStream::new(Arc::clone(&self.data), &self.subscribers)
+    }
+
+    /// Append an access record once an operation has completed.
+    ///
+    /// The operation has already taken effect, so a failed log write is
+    /// reported rather than returned to the caller.
+    fn log_access(&self, operation: &str, key: &[u8]) {
+        if let Some(log) = &self.access_log {
+            if let Err(e) = log.lock().record(operation, key) {
+                eprintln!("[ClawStore] Access log write failed: {}", e);
+            }
+        }
+    }
+
+    /// Counter bumped by every write to RAM.
+    ///
+    /// A copy of RAM state taken after reading generation `g` is still
+    /// current while this returns `g`; use it to cache derived views such as
+    /// sorted table snapshots.
+    pub fn write_generation(&self) -> u64 {
+        self.write_generation.load(Ordering::Acquire)
+    }
+
+    /// Check if key exists in RAM.
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        let data = self.read_data();
+        data.contains_key(key)
+    }
+
+    /// Number of key-value pairs in RAM.
+    pub fn len(&self) -> usize {
+        let data = self.read_data();
+        data.len()
+    }
+
+    /// Returns true if the store has no entries.
+    pub fn is_empty(&self) -> bool {
+        let data = self.read_data();
+        data.is_empty()
+    }
+
+    /// Data directory path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// True if the store was opened with `OpenMode::ReadOnly`.
+    pub fn is_read_only(&self) -> bool {
+        self.wal.is_none()
+    }
+
+    /// What the next trickle cycle would flush, without flushing it: every
+    /// dirty key with its current RAM value, or `None` if it was deleted.
+    /// Sorted by key. The dirty set is left as is.
+    ///
+    /// Holds the RAM read lock while reading the dirty set, so no write can
+    /// land between the two (writers mark keys dirty after releasing the
+    /// write lock, which is why the read lock is taken first).
+    pub fn get_all_dirty(&self) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+        let data = self.read_data();
+        let mut dirty: Vec<(Vec<u8>, Option<Vec<u8>>)> = self.dirty.dirty_keys()
+            .into_iter()
+            .map(|key| {
+                let value = data.get(&key).cloned();
+                (key, value)
+            })
+            .collect();
+        dirty.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        dirty
+    }
+
+    /// Keys waiting to be flushed by trickle.
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.dirty_count()
+    }
 
     /// Total entries flushed to data files since engine start.
     pub fn total_flushed(&self) -> u64 {
@@ -239,7 +1567,7 @@ impl ClawStoreEngine {
     ///
     /// Acquires a read lock — concurrent with other readers.
     pub fn prefix_scan(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
-        let data = self.data.read();
+        let data = self.read_data();
         let mut results: Vec<(Vec<u8>, Vec<u8>)> = data.iter()
             .filter(|(k, _)| k.starts_with(prefix))
             .map(|(k, v)| (k[prefix.len()..].to_vec(), v.clone()))
@@ -252,9 +1580,444 @@ impl ClawStoreEngine {
     ///
     /// More efficient than `prefix_scan().len()` — no cloning or sorting.
     pub fn prefix_count(&self, prefix: &[u8]) -> usize {
-        let data = self.data.read();
+        let data = self.read_data();
         data.keys().filter(|k| k.starts_with(prefix)).count()
     }
+
+    /// True if any key starts with `prefix`.
+    ///
+    /// Stops at the first match, unlike `prefix_count` which always scans
+    /// the whole table.
+    pub fn contains_prefix(&self, prefix: &[u8]) -> bool {
+        let data = self.read_data();
+        any_key_with_prefix(data.keys(), prefix)
+    }
+
+    /// Lexicographically smallest key starting with `prefix` (full scan, no sort).
+    pub fn first_key_with_prefix(&self, prefix: &[u8]) -> Option<Vec<u8>> {
+        let data = self.read_data();
+        data.keys().filter(|k| k.starts_with(prefix)).min().cloned()
+    }
+
+    /// Iterate all keys in sorted order without cloning values.
+    ///
+    /// The read lock is held for the iterator's lifetime — drop it promptly.
+    pub fn iter_all_keys(&self) -> KeyIter<'_> {
+        let data = self.read_data();
+        let mut keys: Vec<Vec<u8>> = data.keys().cloned().collect();
+        keys.sort();
+        KeyIter { _guard: data, keys: keys.into_iter() }
+    }
+
+    /// Borrow the RAM working set under a read lock for zero-copy scans.
+    ///
+    /// Borrowed iterators cannot outlive the lock guard, so unsorted
+    /// iteration goes through the returned view.
+    pub fn read_view(&self) -> ReadView<'_> {
+        ReadView { data: self.read_data() }
+    }
+
+    /// Key/value size statistics for all entries under a table prefix byte.
+    ///
+    /// Key lengths exclude the prefix byte. Single pass under one read lock.
+    pub fn table_stats(&self, prefix_byte: u8) -> EntryStats {
+        let data = self.read_data();
+        EntryStats::from_sizes(
+            data.iter()
+                .filter(|(k, _)| k.first() == Some(&prefix_byte))
+                .map(|(k, v)| (k.len() - 1, v.len())),
+        )
+    }
+
+    /// Estimate how much memory the engine holds, split by purpose.
+    ///
+    /// Deleted entries do not shrink the hash table, so after mass deletes
+    /// `hashmap_overhead_bytes` can exceed the data itself.
+    pub fn memory_usage_breakdown(&self) -> MemoryBreakdown {
+        let (hashmap_data_bytes, hashmap_overhead_bytes, wal_positions_bytes) = {
+            let data = self.read_data();
+            let payload: u64 = data.iter().map(|(k, v)| (k.len() + v.len()) as u64).sum();
+            let slot_bytes = (std::mem::size_of::<usize>() * 2 + 8) as u64;
+            let per_position = std::mem::size_of::<(Vec<u8>, WalPosition)>();
+            let positions: u64 = self.wal_positions.lock()
+                .keys()
+                .map(|key| (key.len() + per_position) as u64)
+                .sum();
+            (payload, (data.capacity() - data.len()) as u64 * slot_bytes, positions)
+        };
+        let history_cache_bytes = self.history_cache.lock().approx_bytes();
+        let dirty_tracker_bytes = self.dirty.approx_bytes();
+        // WalWriter has no write buffer; see `MemoryBreakdown::wal_buffer_bytes`
+        let wal_buffer_bytes = 0;
+        MemoryBreakdown {
+            hashmap_data_bytes,
+            hashmap_overhead_bytes,
+            dirty_tracker_bytes,
+            wal_positions_bytes,
+            history_cache_bytes,
+            wal_buffer_bytes,
+            total_estimate_bytes: hashmap_data_bytes
+                + hashmap_overhead_bytes
+                + dirty_tracker_bytes
+                + wal_positions_bytes
+                + history_cache_bytes
+                + wal_buffer_bytes,
+        }
+    }
+
+    /// Write the average key and value sizes of the entries in RAM to
+    /// `{path}/db.stats`, for `estimate_entry_count_from_disk` after a
+    /// later open.
+    pub fn save_db_stats(&self) -> ClawResult<()> {
+        if self.is_read_only() {
+            return Err(ClawError::ReadOnly { path: self.path.clone() });
+        }
+        let stats = {
+            let data = self.read_data();
+            EntryStats::from_sizes(data.iter().map(|(k, v)| (k.len(), v.len())))
+        };
+        let stats_path = self.path.join(DB_STATS_FILE);
+        std::fs::write(&stats_path, DbStats::from_entry_stats(&stats).to_toml())
+            .with_context("Failed to write db.stats")
+            .with_path(&stats_path)
+    }
+
+    /// Estimate how many entries `warm_from_data_files` would load, e.g. to
+    /// pre-size a map before calling it.
+    ///
+    /// Divides the total size of the data files by the average on-disk
+    /// entry size from `db.stats`, or by a 64-byte key + value guess when
+    /// the file is missing or unreadable. Stale values and tombstones still
+    /// in the files are counted too, so the estimate tends to run high.
+    pub fn estimate_entry_count_from_disk(&self) -> ClawResult<u64> {
+        let payload = std::fs::read_to_string(self.path.join(DB_STATS_FILE))
+            .ok()
+            .and_then(|text| DbStats::parse(&text))
+            .map_or(DEFAULT_ESTIMATE_PAYLOAD_BYTES, |stats| stats.avg_key_len + stats.avg_val_len);
+        let entry_size = DATA_HEADER_SIZE as f64 + payload;
+
+        let mut total_bytes = 0u64;
+        for file_path in list_data_files(&self.path.join("data"))? {
+            total_bytes += std::fs::metadata(&file_path)
+                .with_context("Failed to stat data file")
+                .with_path(&file_path)?
+                .len();
+        }
+        Ok((total_bytes as f64 / entry_size).round() as u64)
+    }
+
+    /// Load entries from all data files into RAM, oldest file first.
+    ///
+    /// Within the data files the last write wins and tombstones remove the
+    /// key. Keys already in RAM are left alone, since RAM is always at least
+    /// as new as the data files. Returns the number of entries inserted.
+    pub fn warm_from_data_files(&self) -> ClawResult<usize> {
+        let mut on_disk = HashMap::new();
+        load_data_files(&self.path.join("data"), &mut on_disk)?;
+
+        let mut data = self.data.write();
+        self.write_generation.fetch_add(1, Ordering::Release);
+        let mut loaded = 0;
+        for (key, value) in on_disk {
+            if !data.contains_key(&key) {
+                data.insert(key, value);
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Copy every entry into `dest`, e.g. to migrate to a new store.
+    ///
+    /// Holds this engine's read lock for the whole copy, writing each entry
+    /// with `dest.put_fast` and syncing `dest`'s WAL once at the end. This
+    /// engine is not modified. Returns the number of entries copied.
+    pub fn copy_to(&self, dest: &ClawStoreEngine) -> ClawResult<usize> {
+        self.copy_matching(dest, |_| true)
+    }
+
+    /// `copy_to` restricted to the table with prefix byte `prefix`.
+    pub fn copy_table_to(&self, prefix: u8, dest: &ClawStoreEngine) -> ClawResult<usize> {
+        self.copy_matching(dest, |key| key.first() == Some(&prefix))
+    }
+
+    fn copy_matching(&self, dest: &ClawStoreEngine, include: impl Fn(&[u8]) -> bool) -> ClawResult<usize> {
+        // dest.put_fast would wait on our own read lock forever
+        if std::ptr::eq(self, dest) {
+            return Err(ClawError::Io {
+                path: Some(self.path.clone()),
+                kind: std::io::ErrorKind::InvalidInput,
+                message: "Cannot copy an engine into itself".to_string(),
+                inner: None,
+            });
+        }
+
+        let data = self.read_data();
+        let mut copied = 0;
+        for (key, value) in data.iter().filter(|(k, _)| include(k)) {
+            dest.put_fast(key, value)?;
+            copied += 1;
+        }
+        drop(data);
+
+        dest.sync_wal()?;
+        Ok(copied)
+    }
+
+    /// Merge another shard into this engine, `other`'s values winning on
+    /// conflicting keys. Returns the number of entries merged.
+    ///
+    /// `other`'s trickle is stopped before its RAM is read, and `other` is
+    /// dropped (sealing its WAL) before anything is written here. All of
+    /// its entries are then written through this engine's WAL as a single
+    /// `put_batch`, so recovery replays the merge all or nothing, and reach
+    /// this engine's data files through its own trickle, under its own
+    /// sequence numbers. `other`'s directory is left on disk as it was.
+    ///
+    /// On error RAM is unchanged and `other` can be reopened from its
+    /// directory to retry. A shard whose entries exceed one WAL batch
+    /// (4 GiB) fails with `OversizedEntry` before anything is written.
+    pub fn merge_engines(&self, other: ClawStoreEngine) -> ClawResult<usize> {
+        other.stop_trickle();
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = other.read_data()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        drop(other);
+
+        self.put_batch(&entries)?;
+        Ok(entries.len())
+    }
+
+    /// Changes that turn this engine's entries into `other`'s.
+    ///
+    /// Both read locks are held together, so neither engine can be written
+    /// mid-diff. They are taken in a fixed (address) order, so concurrent
+    /// `a.diff(b)` and `b.diff(a)` calls cannot deadlock.
+    pub fn diff(&self, other: &ClawStoreEngine) -> ClawResult<EngineDiff> {
+        if std::ptr::eq(self, other) {
+            return Ok(EngineDiff::default());
+        }
+        let (old, new) = if (self as *const Self) < (other as *const Self) {
+            let old = self.read_data();
+            (old, other.read_data())
+        } else {
+            let new = other.read_data();
+            (self.read_data(), new)
+        };
+
+        let mut diff = EngineDiff::default();
+        for (key, old_value) in old.iter() {
+            match new.get(key) {
+                None => diff.removed.push(key.clone()),
+                Some(new_value) if new_value != old_value => {
+                    diff.modified.push((key.clone(), old_value.clone(), new_value.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new_value) in new.iter() {
+            if !old.contains_key(key) {
+                diff.added.push((key.clone(), new_value.clone()));
+            }
+        }
+        drop((old, new));
+
+        diff.added.sort_unstable();
+        diff.modified.sort_unstable();
+        diff.removed.sort_unstable();
+        Ok(diff)
+    }
+
+    /// Quick sanity check before serving traffic, e.g. after crash recovery.
+    ///
+    /// `true` if every check in [`consistency_check_details`](Self::consistency_check_details)
+    /// passed. Never returns an error.
+    pub fn is_consistent(&self) -> bool {
+        self.consistency_check_details().is_ok()
+    }
+
+    /// Run the cheap self-checks and report each one.
+    ///
+    /// The dirty-key check is a heuristic: keys deleted since the last
+    /// trickle flush are dirty without being in RAM, so it can also fail on
+    /// a healthy store with many unflushed deletes. Read-only engines skip
+    /// the probe, which needs a WAL writer. Unlike `verify_consistency`, no
+    /// file is read.
+    pub fn consistency_check_details(&self) -> ConsistencyCheckResult {
+        let dirty_within_len = self.dirty_count() <= self.len();
+        let data_dir_exists = self.path.join("data").is_dir();
+        let (wal_file_exists, probe_round_trip) = match &self.wal {
+            Some(wal) => {
+                let wal_file_exists = wal.lock().current_path().exists();
+                (wal_file_exists, self.probe_round_trip(wal).unwrap_or(false))
+            }
+            None => (self.path.join("wal").is_dir(), true),
+        };
+
+        ConsistencyCheckResult { dirty_within_len, wal_file_exists, data_dir_exists, probe_round_trip }
+    }
+
+    /// Write `CONSISTENCY_PROBE_KEY` and then its previous state (its old
+    /// value, or a delete) as one WAL batch, and mirror both in RAM.
+    ///
+    /// Bypasses `put` so cache mode cannot evict a real key, and holds the
+    /// WAL mutex and RAM write lock throughout: readers never see the probe
+    /// value (except a `get` racing it with `Config::use_concurrent_hashmap`)
+    /// and the key ends as it started, so nothing is marked dirty or
+    /// published.
+    fn probe_round_trip(&self, wal: &Mutex<WalWriter>) -> ClawResult<bool> {
+        const PROBE_VALUE: &[u8] = b"probe";
+        let mut wal = wal.lock();
+        let mut data = self.data.write();
+        let previous = data.get(CONSISTENCY_PROBE_KEY);
+        let restore = match &previous {
+            Some(value) => (CONSISTENCY_PROBE_KEY, value.as_slice(), Operation::Put),
+            None => (CONSISTENCY_PROBE_KEY, &[][..], Operation::Delete),
+        };
+        wal.append_batch_durable(&[(CONSISTENCY_PROBE_KEY, PROBE_VALUE, Operation::Put), restore])?;
+
+        data.insert(CONSISTENCY_PROBE_KEY.to_vec(), PROBE_VALUE.to_vec());
+        let stored = data.get(CONSISTENCY_PROBE_KEY);
+        match &previous {
+            Some(value) => {
+                data.insert(CONSISTENCY_PROBE_KEY.to_vec(), value.clone());
+                self.wal_positions.lock().insert(CONSISTENCY_PROBE_KEY.to_vec(), wal.last_record_position());
+            }
+            None => {
+                data.remove(CONSISTENCY_PROBE_KEY);
+            }
+        }
+        Ok(stored.as_deref() == Some(PROBE_VALUE) && data.get(CONSISTENCY_PROBE_KEY) == previous)
+    }
+
+    /// Scan WAL and data files and report what was found.
+    ///
+    /// Re-reads every WAL file, verifies every data file checksum and looks
+    /// for leftover `.compact` files. Returns `Err` only for I/O failures.
+    pub fn verify_consistency(&self) -> ClawResult<ConsistencyReport> {
+        let mut report = ConsistencyReport {
+            ram_entries: self.len(),
+            ..Default::default()
+        };
+
+        let reader = WalReader::new(self.path.join("wal")).with_encryption_key(self.config.encryption_key);
+        report.wal_entries_recovered = reader.recover_entries()?
+            .iter()
+            .filter(|e| !e.is_seal())
+            .count();
+
+        let data_dir = self.path.join("data");
+        for file_path in list_data_files(&data_dir)? {
+            let integrity = DataFileReader::verify_file_integrity(&file_path)?;
+            report.data_file_entries_verified += integrity.entries_verified;
+            report.checksum_errors += integrity.checksum_errors;
+            report.magic_errors += integrity.magic_errors;
+        }
+
+        let dir_entries = std::fs::read_dir(&data_dir)
+            .with_context("Failed to read data directory")
+            .with_path(&data_dir)?;
+        for entry in dir_entries.flatten() {
+            if entry.file_name().to_str().is_some_and(|n| n.ends_with(".compact")) {
+                report.orphan_compact_files.push(entry.path());
+            }
+        }
+        report.orphan_compact_files.sort();
+
+        Ok(report)
+    }
+}
+
+/// Short-circuiting prefix check, split out so tests can count key visits.
+fn any_key_with_prefix<'a>(mut keys: impl Iterator<Item = &'a Vec<u8>>, prefix: &[u8]) -> bool {
+    keys.any(|k| k.starts_with(prefix))
+}
+
+/// Build the RAM map from data files (if `warm_on_open`) and WAL replay.
+///
+/// Returns the map, the WAL position of each replayed key's last record and
+/// the highest write sequence seen in the WAL.
+fn load_state(path: &Path, config: &Config) -> ClawResult<(RamMap, WalPositions, u64)> {
+    let wal_dir = path.join("wal");
+    let data_dir = path.join("data");
+
+    // Data files hold older state than the WAL, so they load first and
+    // WAL replay below overwrites them (last write wins)
+    let mut data = HashMap::new();
+    if config.warm_on_open {
+        let warmed = load_data_files(&data_dir, &mut data)?;
+        eprintln!("[ClawStore] Warmed {} entries from data files at {}", warmed, data_dir.display());
+    }
+
+    // Replay WAL into RAM (crash recovery)
+    let mut max_sequence = 0u64;
+    let mut positions = HashMap::new();
+    let reader = WalReader::new(&wal_dir)
+        .with_encryption_key(config.encryption_key)
+        .with_recovery_mode(config.wal_recovery_mode);
+    let entries = reader.recover_with_positions()?;
+
+    for (position, entry) in &entries {
+        if let Some(seq) = entry.sequence {
+            max_sequence = max_sequence.max(seq);
+        }
+        match entry.operation {
+            Operation::Put => {
+                data.insert(entry.key.clone(), entry.value.clone());
+                positions.insert(entry.key.clone(), *position);
+            }
+            Operation::Delete => {
+                data.remove(&entry.key);
+                positions.remove(&entry.key);
+            }
+            Operation::Seal | Operation::Batch => {}
+        }
+    }
+
+    let recovered_count = entries.iter().filter(|(_, e)| !e.is_seal()).count();
+    if recovered_count > 0 {
+        eprintln!(
+            "[ClawStore] Recovered {} entries from WAL at {}",
+            recovered_count,
+            wal_dir.display()
+        );
+    }
+
+    Ok((data, positions, max_sequence))
+}
+
+/// Apply every data file in `data_dir` to `data` in sequence order.
+///
+/// Returns the number of live keys the data files contribute.
+fn load_data_files(data_dir: &Path, data: &mut HashMap<Vec<u8>, Vec<u8>>) -> ClawResult<usize> {
+    // Per key: (entry sequence number, value or None for a tombstone)
+    let mut on_disk: HashMap<Vec<u8>, (u64, Option<Vec<u8>>)> = HashMap::new();
+    for file_path in list_data_files(data_dir)? {
+        DataFileReader::stream_scan(&file_path, |entry| {
+            if let Some((earlier, _)) = on_disk.get(&entry.key) {
+                if !supersedes(entry.sequence_number, *earlier) {
+                    return;
+                }
+            }
+            let value = if entry.is_tombstone { None } else { Some(entry.value) };
+            on_disk.insert(entry.key, (entry.sequence_number, value));
+        })?;
+    }
+
+    let mut loaded = 0;
+    for (key, (_, value)) in on_disk {
+        match value {
+            Some(value) => {
+                data.insert(key, value);
+                loaded += 1;
+            }
+            None => {
+                data.remove(&key);
+            }
+        }
+    }
+    Ok(loaded)
 }
 
 impl Drop for ClawStoreEngine {
@@ -263,27 +2026,448 @@ impl Drop for ClawStoreEngine {
         if let Some(handle) = trickle.take() {
             handle.shutdown();
         }
+        for (name, hook) in self.shutdown_hooks.lock().drain(..) {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(&hook));
+            if result.is_err() {
+                eprintln!("[ClawStore] Shutdown hook '{}' panicked", name);
+            }
+        }
+        if let Some(wal) = &self.wal {
+            if let Err(e) = wal.lock().seal("graceful shutdown") {
+                eprintln!("[ClawStore] Failed to seal WAL on shutdown: {}", e);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::subscription::ReplicaEvent;
+    use crate::trickle::TrickleMode;
     use tempfile::TempDir;
     use std::time::Duration;
 
-    fn test_engine() -> (ClawStoreEngine, TempDir) {
-        let dir = TempDir::new().unwrap();
-        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
-        (engine, dir)
+    fn test_engine() -> (ClawStoreEngine, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let engine = ClawStoreEngine::open(dir.path(), Config::for_testing()).unwrap();
+        (engine, dir)
+    }
+
+    #[test]
+    fn test_for_testing_config_flushes_quickly() {
+        assert!(Config::for_testing().validate().is_ok());
+
+        let (engine, _dir) = test_engine();
+        engine.put(b"k", b"v").unwrap();
+        engine.start_trickle().unwrap();
+        let start = std::time::Instant::now();
+        while engine.total_flushed() == 0 && start.elapsed() < Duration::from_millis(200) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(engine.total_flushed(), 1);
+        engine.stop_trickle();
+    }
+
+    #[test]
+    fn test_flush_sequence_continues_after_reopen() {
+        let dir = TempDir::new().unwrap();
+        for value in [b"v1", b"v2"] {
+            let engine = ClawStoreEngine::open(dir.path(), Config::for_testing()).unwrap();
+            engine.put(b"k", value).unwrap();
+            engine.start_trickle().unwrap();
+            engine.stop_trickle();
+        }
+
+        let data_file = latest_data_file(&dir.path().join("data")).unwrap();
+        let sequences: Vec<u64> = DataFileReader::scan_all(&data_file).unwrap()
+            .iter().map(|e| e.sequence_number).collect();
+        assert_eq!(sequences, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_open_empty() {
+        let (engine, _dir) = test_engine();
+        assert_eq!(engine.len(), 0);
+        assert!(engine.is_empty());
+        assert_eq!(engine.dirty_count(), 0);
+    }
+
+    #[test]
+    fn test_shutdown_hooks_run_on_drop_in_order() {
+        use std::sync::atomic::AtomicBool;
+
+        let (engine, _dir) = test_engine();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let ran = Arc::new(AtomicBool::new(false));
+
+        let first = Arc::clone(&order);
+        engine.register_shutdown_hook("first", move || first.lock().push("first")).unwrap();
+        engine.register_shutdown_hook("panics", || panic!("hook failure")).unwrap();
+        let last = Arc::clone(&order);
+        let flag = Arc::clone(&ran);
+        engine.register_shutdown_hook("last", move || {
+            last.lock().push("last");
+            flag.store(true, Ordering::SeqCst);
+        }).unwrap();
+
+        assert!(!ran.load(Ordering::SeqCst));
+        drop(engine);
+        assert!(ran.load(Ordering::SeqCst));
+        assert_eq!(*order.lock(), vec!["first", "last"]);
+    }
+
+    #[test]
+    fn test_wal_sequence_advances_on_reopen() {
+        let (engine, dir) = test_engine();
+        let first_seq = engine.wal_sequence();
+        engine.put(b"a", b"1").unwrap();
+        engine.put(b"b", b"2").unwrap();
+        engine.delete(b"a").unwrap();
+        assert_eq!(engine.wal_entries_in_current_file(), 3);
+        drop(engine);
+
+        // The previous file is sealed, so the new writer starts the next one
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.wal_sequence(), first_seq + 1);
+        assert_eq!(engine.wal_entries_in_current_file(), 0);
+        engine.put(b"c", b"3").unwrap();
+        assert_eq!(engine.wal_entries_in_current_file(), 1);
+    }
+
+    #[test]
+    fn test_rotate_wal_keeps_data_recoverable() {
+        let (engine, dir) = test_engine();
+        engine.put(b"before", b"1").unwrap();
+        let seq = engine.wal_sequence();
+        let closed = engine.rotate_wal().unwrap();
+        assert!(closed.exists());
+        assert_eq!(engine.wal_sequence(), seq + 1);
+        engine.put(b"after", b"2").unwrap();
+        drop(engine);
+
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.get(b"before").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(engine.get(b"after").unwrap(), Some(b"2".to_vec()));
+    }
+
+    #[test]
+    fn test_write_generation_changes_on_every_write() {
+        let (engine, _dir) = test_engine();
+        let start = engine.write_generation();
+        engine.put(b"a", b"1").unwrap();
+        engine.put_fast(b"b", b"2").unwrap();
+        engine.delete(b"a").unwrap();
+        assert_eq!(engine.write_generation(), start + 3);
+
+        engine.get(b"b").unwrap();
+        assert_eq!(engine.write_generation(), start + 3);
+    }
+
+    #[test]
+    fn test_reopen_picks_up_external_wal_file() {
+        let (engine, dir) = test_engine();
+        engine.put(b"existing", b"before").unwrap();
+        engine.start_trickle().unwrap();
+
+        // A restore tool drops a newer WAL file into the directory
+        let staging = TempDir::new().unwrap();
+        let mut external = WalWriter::new(staging.path()).unwrap();
+        external.append_durable(b"restored", b"from-tool", Operation::Put).unwrap();
+        external.append_durable(b"existing", b"overwritten", Operation::Put).unwrap();
+        let external_path = external.current_path().to_path_buf();
+        drop(external);
+        let target_seq = engine.wal_sequence() + 5;
+        std::fs::copy(&external_path, dir.path().join("wal").join(format!("wal-{:016x}.claw", target_seq))).unwrap();
+
+        assert_eq!(engine.get(b"restored").unwrap(), None);
+        engine.reopen().unwrap();
+
+        assert_eq!(engine.get(b"restored").unwrap(), Some(b"from-tool".to_vec()));
+        assert_eq!(engine.get(b"existing").unwrap(), Some(b"overwritten".to_vec()));
+        assert!(engine.trickle.lock().as_ref().is_some_and(|t| t.is_running()));
+
+        // New writes go to a file after the external one and survive a restart
+        assert_eq!(engine.wal_sequence(), target_seq + 1);
+        engine.put(b"after", b"reopen").unwrap();
+        drop(engine);
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.get(b"after").unwrap(), Some(b"reopen".to_vec()));
+        assert_eq!(engine.get(b"restored").unwrap(), Some(b"from-tool".to_vec()));
+    }
+
+    #[test]
+    fn test_reopen_failure_leaves_engine_usable() {
+        let (engine, dir) = test_engine();
+        engine.put(b"existing", b"before").unwrap();
+        engine.start_trickle().unwrap();
+        let sequence_before = engine.wal_sequence();
+
+        // An encrypted WAL file the engine has no key for
+        let staging = TempDir::new().unwrap();
+        let mut external = WalWriter::new(staging.path()).unwrap().with_encryption_key(Some([7; 32]));
+        external.append_durable(b"restored", b"secret", Operation::Put).unwrap();
+        let external_path = external.current_path().to_path_buf();
+        drop(external);
+        let bad_path = dir.path().join("wal").join(format!("wal-{:016x}.claw", sequence_before + 5));
+        std::fs::copy(&external_path, &bad_path).unwrap();
+
+        assert!(matches!(engine.reopen(), Err(ClawError::EncryptionKeyRequired { .. })));
+        assert_eq!(engine.get(b"existing").unwrap(), Some(b"before".to_vec()));
+        assert!(engine.trickle.lock().as_ref().is_some_and(|t| t.is_running()));
+
+        // Writes land in a file after the sealed one and are recovered
+        engine.put(b"after", b"failed-reopen").unwrap();
+        assert!(engine.wal_sequence() > sequence_before);
+        drop(engine);
+        std::fs::remove_file(&bad_path).unwrap();
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.get(b"after").unwrap(), Some(b"failed-reopen".to_vec()));
+        assert_eq!(engine.get(b"existing").unwrap(), Some(b"before".to_vec()));
+    }
+
+    #[test]
+    fn test_reopen_rebuilds_lru_order() {
+        let dir = TempDir::new().unwrap();
+        let config = Config { cache_mode: Some(4), ..Config::default() };
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        // Key 2 is the least recently used before the reopen
+        for i in [2u8, 1, 0] {
+            engine.put_and_evict_lru(&[i], &[i], 4).unwrap();
+        }
+
+        let staging = TempDir::new().unwrap();
+        let mut external = WalWriter::new(staging.path()).unwrap();
+        external.append_durable(&[9], &[9], Operation::Put).unwrap();
+        let external_path = external.current_path().to_path_buf();
+        drop(external);
+        let target_seq = engine.wal_sequence() + 5;
+        std::fs::copy(&external_path, dir.path().join("wal").join(format!("wal-{:016x}.claw", target_seq))).unwrap();
+        engine.reopen().unwrap();
+
+        // Like `open`: reloaded keys are oldest, in key order
+        assert_eq!(engine.put_and_evict_lru(&[5], &[5], 4).unwrap(), Some(vec![0]));
+        assert_eq!(engine.put_and_evict_lru(&[6], &[6], 4).unwrap(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_open_twice_fails() {
+        let (engine, dir) = test_engine();
+        let second = ClawStoreEngine::open(dir.path(), Config::default());
+        assert!(matches!(second, Err(ClawError::AlreadyOpen { .. })));
+
+        // Dropping the first engine releases the lock
+        drop(engine);
+        assert!(ClawStoreEngine::open(dir.path(), Config::default()).is_ok());
+    }
+
+    #[test]
+    fn test_verify_consistency_counts_checksum_error() {
+        let (engine, dir) = test_engine();
+        engine.put(b"a", b"value-a").unwrap();
+        engine.put(b"b", b"value-b").unwrap();
+        engine.start_trickle().unwrap();
+        engine.stop_trickle(); // final flush
+
+        let report = engine.verify_consistency().unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.wal_entries_recovered, 2);
+        assert_eq!(report.ram_entries, 2);
+        assert_eq!(report.data_file_entries_verified, 2);
+
+        // Flip the last byte of the data file (inside the second entry's value)
+        let data_file = crate::datafile::latest_data_file(&dir.path().join("data")).unwrap();
+        let mut bytes = std::fs::read(&data_file).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(&data_file, bytes).unwrap();
+        std::fs::write(dir.path().join("data").join("data-0000000000000001.claw.compact"), b"").unwrap();
+
+        let report = engine.verify_consistency().unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.checksum_errors, 1);
+        assert_eq!(report.data_file_entries_verified, 1);
+        assert_eq!(report.orphan_compact_files.len(), 1);
+    }
+
+    #[test]
+    fn test_warm_on_open_after_wal_truncated() {
+        let dir = TempDir::new().unwrap();
+        {
+            let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+            for i in 0..50u32 {
+                engine.put(&i.to_be_bytes(), format!("v{}", i).as_bytes()).unwrap();
+            }
+            engine.delete(&7u32.to_be_bytes()).unwrap();
+            engine.start_trickle().unwrap();
+            engine.stop_trickle(); // final flush
+        }
+        crate::compaction::compact_directory(&dir.path().join("data"), 0.0).unwrap();
+        std::fs::remove_dir_all(dir.path().join("wal")).unwrap();
+
+        let cold = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert!(cold.is_empty());
+        drop(cold);
+
+        let config = Config { warm_on_open: true, ..Config::default() };
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        assert_eq!(engine.len(), 49);
+        assert_eq!(engine.get(&3u32.to_be_bytes()).unwrap(), Some(b"v3".to_vec()));
+        assert_eq!(engine.get(&7u32.to_be_bytes()).unwrap(), None);
+        // Everything is already in RAM
+        assert_eq!(engine.warm_from_data_files().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_estimate_entry_count_from_disk() {
+        let dir = TempDir::new().unwrap();
+        {
+            let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+            for i in 0..2000u32 {
+                engine.put_fast(&i.to_be_bytes(), format!("value-{:0width$}", i, width = (i % 40) as usize).as_bytes()).unwrap();
+            }
+            engine.sync_wal().unwrap();
+            engine.start_trickle().unwrap();
+            engine.stop_trickle(); // final flush
+            engine.save_db_stats().unwrap();
+        }
+        let stats_text = std::fs::read_to_string(dir.path().join(DB_STATS_FILE)).unwrap();
+        assert_eq!(DbStats::parse(&stats_text).unwrap().entry_count, 2000);
+        std::fs::remove_dir_all(dir.path().join("wal")).unwrap();
+
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        let estimate = engine.estimate_entry_count_from_disk().unwrap();
+        let loaded = engine.warm_from_data_files().unwrap() as u64;
+        assert_eq!(loaded, 2000);
+        assert!(estimate.abs_diff(loaded) <= loaded / 2, "estimate {estimate}, loaded {loaded}");
+    }
+
+    #[test]
+    fn test_contains_prefix_short_circuits() {
+        let keys: Vec<Vec<u8>> = (0..1000u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let visited = std::cell::Cell::new(0);
+        let counting = keys.iter().inspect(|_| visited.set(visited.get() + 1));
+        assert!(any_key_with_prefix(counting, &[0, 0, 0, 5]));
+        assert_eq!(visited.get(), 6);
+
+        let (engine, _dir) = test_engine();
+        assert!(!engine.contains_prefix(b"a"));
+        engine.put(b"ab", b"1").unwrap();
+        engine.put(b"aa", b"2").unwrap();
+        engine.put(b"b", b"3").unwrap();
+        assert!(engine.contains_prefix(b"a"));
+        assert!(!engine.contains_prefix(b"c"));
+        assert_eq!(engine.first_key_with_prefix(b"a"), Some(b"aa".to_vec()));
+        assert_eq!(engine.first_key_with_prefix(b"c"), None);
+    }
+
+    #[test]
+    fn test_read_only_mode_rejects_writes() {
+        let dir = TempDir::new().unwrap();
+        {
+            let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+            engine.put(b"k1", b"v1").unwrap();
+        }
+
+        let reader = ClawStoreEngine::open_with_mode(dir.path(), Config::default(), OpenMode::ReadOnly).unwrap();
+        assert!(reader.is_read_only());
+        assert_eq!(reader.get(b"k1").unwrap(), Some(b"v1".to_vec()));
+        assert!(matches!(reader.put(b"k2", b"v2"), Err(ClawError::ReadOnly { .. })));
+        assert!(matches!(reader.delete(b"k1"), Err(ClawError::ReadOnly { .. })));
+        assert!(matches!(reader.start_trickle(), Err(ClawError::ReadOnly { .. })));
+        assert_eq!(reader.len(), 1);
+
+        let second = ClawStoreEngine::open_with_mode(dir.path(), Config::default(), OpenMode::ReadOnly).unwrap();
+        assert_eq!(second.get(b"k1").unwrap(), Some(b"v1".to_vec()));
+        assert!(matches!(
+            ClawStoreEngine::open(dir.path(), Config::default()),
+            Err(ClawError::AlreadyOpen { .. })
+        ));
+    }
+
+    #[test]
+    fn test_put_many_fast_sync_once_syncs_once() {
+        let count = 10_000u32;
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..count)
+            .map(|i| (i.to_be_bytes().to_vec(), vec![0xAB; 32]))
+            .collect();
+
+        let (engine, dir) = test_engine();
+        let syncs_before = engine.wal().unwrap().sync_count();
+        engine.put_many_fast_sync_once(entries.clone()).unwrap();
+        assert_eq!(engine.wal().unwrap().sync_count() - syncs_before, 1);
+        assert_eq!(engine.len(), count as usize);
+        assert_eq!(engine.dirty_count(), count as usize);
+
+        drop(engine);
+        let engine = ClawStoreEngine::open(dir.path(), Config::for_testing()).unwrap();
+        assert_eq!(engine.len(), count as usize);
+        assert_eq!(engine.get(&(count - 1).to_be_bytes()).unwrap(), Some(vec![0xAB; 32]));
+    }
+
+    #[test]
+    fn test_get_under_write_contention() {
+        const KEYS: u32 = 256;
+        for use_concurrent_hashmap in [false, true] {
+            let dir = TempDir::new().unwrap();
+            let config = Config { use_concurrent_hashmap, ..Config::for_testing() };
+            let engine = Arc::new(ClawStoreEngine::open(dir.path(), config).unwrap());
+            let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..KEYS)
+                .map(|i| (i.to_be_bytes().to_vec(), vec![0xCD; 32]))
+                .collect();
+            engine.put_many_fast_sync_once(entries).unwrap();
+
+            let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let writer = {
+                let (engine, stop) = (Arc::clone(&engine), Arc::clone(&stop));
+                std::thread::spawn(move || {
+                    let mut writes = 0u32;
+                    while !stop.load(Ordering::Relaxed) {
+                        engine.put_fast(&(writes % KEYS).to_be_bytes(), &[0xEF; 32]).unwrap();
+                        writes += 1;
+                    }
+                })
+            };
+            let readers: Vec<_> = (0..8u32).map(|r| {
+                let engine = Arc::clone(&engine);
+                std::thread::spawn(move || {
+                    for i in 0..5_000u32 {
+                        let value = engine.get(&((i + r * 61) % KEYS).to_be_bytes()).unwrap().unwrap();
+                        assert!(value == [0xCD; 32] || value == [0xEF; 32]);
+                    }
+                })
+            }).collect();
+            for reader in readers {
+                reader.join().unwrap();
+            }
+            stop.store(true, Ordering::Relaxed);
+            writer.join().unwrap();
+            assert_eq!(engine.len(), KEYS as usize);
+        }
     }
 
     #[test]
-    fn test_open_empty() {
-        let (engine, _dir) = test_engine();
-        assert_eq!(engine.len(), 0);
-        assert!(engine.is_empty());
-        assert_eq!(engine.dirty_count(), 0);
+    fn test_access_log_records_operations_in_order() {
+        let dir = TempDir::new().unwrap();
+        let log_path = dir.path().join("access.log");
+        let config = Config { access_log: Some(log_path.clone()), ..Config::default() };
+        let engine = ClawStoreEngine::open(dir.path().join("store"), config).unwrap();
+
+        engine.put(b"k1", b"v1").unwrap();
+        engine.get(b"k1").unwrap();
+        engine.delete(b"k1").unwrap();
+        engine.get(b"missing").unwrap();
+        drop(engine);
+
+        let contents = std::fs::read_to_string(&log_path).unwrap();
+        let lines: Vec<Vec<&str>> = contents.lines().map(|l| l.split(',').collect()).collect();
+        let ops: Vec<(&str, &str)> = lines.iter().map(|l| (l[1], l[2])).collect();
+        assert_eq!(
+            ops,
+            vec![("put", "6b31"), ("get", "6b31"), ("delete", "6b31"), ("get", "6d697373696e67")]
+        );
+
+        let timestamps: Vec<u128> = lines.iter().map(|l| l[0].parse().unwrap()).collect();
+        assert!(timestamps.windows(2).all(|w| w[0] <= w[1]));
     }
 
     #[test]
@@ -314,6 +2498,167 @@ mod tests {
         assert_eq!(engine.dirty_count(), 1); // same key
     }
 
+    #[test]
+    fn test_get_all_dirty_leaves_dirty_set() {
+        let (engine, _dir) = test_engine();
+        engine.put(b"a", b"1").unwrap();
+        engine.put(b"b", b"2").unwrap();
+        engine.put(b"c", b"3").unwrap();
+        engine.delete(b"d").unwrap();
+
+        let dirty = engine.get_all_dirty();
+        assert_eq!(dirty, vec![
+            (b"a".to_vec(), Some(b"1".to_vec())),
+            (b"b".to_vec(), Some(b"2".to_vec())),
+            (b"c".to_vec(), Some(b"3".to_vec())),
+            (b"d".to_vec(), None),
+        ]);
+        assert_eq!(engine.dirty_count(), 4);
+    }
+
+    #[test]
+    fn test_put_and_evict_lru_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let config = Config { cache_mode: Some(5), ..Config::default() };
+        let engine = ClawStoreEngine::open(dir.path(), config.clone()).unwrap();
+        for i in 0..5u8 {
+            assert_eq!(engine.put_and_evict_lru(&[i], &[i], 5).unwrap(), None);
+        }
+        assert_eq!(engine.put_and_evict_lru(&[5], &[5], 5).unwrap(), Some(vec![0]));
+        assert_eq!(engine.get(&[0]).unwrap(), None);
+        assert_eq!(engine.len(), 5);
+
+        // Reading key 1 makes key 2 the oldest
+        assert_eq!(engine.get(&[1]).unwrap(), Some(vec![1]));
+        assert_eq!(engine.put_and_evict_lru(&[6], &[6], 5).unwrap(), Some(vec![2]));
+        // Overwriting refreshes without evicting; plain put goes through cache mode
+        assert_eq!(engine.put_and_evict_lru(&[3], b"new", 5).unwrap(), None);
+        engine.put(&[7], &[7]).unwrap();
+        assert_eq!(engine.get(&[4]).unwrap(), None);
+
+        // Evictions are durable deletes
+        drop(engine);
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        let keys: Vec<Vec<u8>> = engine.prefix_scan(&[]).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![vec![1], vec![3], vec![5], vec![6], vec![7]]);
+    }
+
+    #[test]
+    fn test_get_at_wal_sequence_replays_history() {
+        let (engine, _dir) = test_engine();
+        let mut sequences = Vec::new();
+        for value in [&b"v1"[..], b"v2", b"v3"] {
+            engine.put(b"k", value).unwrap();
+            engine.put(b"other", value).unwrap();
+            sequences.push(engine.wal_sequence());
+            engine.rotate_wal().unwrap();
+        }
+        engine.delete(b"k").unwrap();
+        let deleted_at = engine.wal_sequence();
+
+        for (value, seq) in [&b"v1"[..], b"v2", b"v3"].iter().zip(&sequences) {
+            assert_eq!(engine.get_at_wal_sequence(b"k", *seq).unwrap().as_deref(), Some(*value));
+            // Served from the cache the second time
+            assert_eq!(engine.get_at_wal_sequence(b"k", *seq).unwrap().as_deref(), Some(*value));
+        }
+        assert_eq!(engine.get_at_wal_sequence(b"k", deleted_at).unwrap(), None);
+        assert_eq!(engine.get_at_wal_sequence(b"missing", deleted_at).unwrap(), None);
+
+        // The active file is not cached: a later write shows up
+        engine.put(b"k", b"v4").unwrap();
+        assert_eq!(engine.get_at_wal_sequence(b"k", deleted_at).unwrap().as_deref(), Some(&b"v4"[..]));
+
+        // Vacuum drops the old WAL files; only cached answers survive
+        engine.vacuum().unwrap();
+        assert_eq!(engine.get_at_wal_sequence(b"k", sequences[0]).unwrap().as_deref(), Some(&b"v1"[..]));
+        assert!(engine.get_at_wal_sequence(b"other", sequences[0]).is_err());
+    }
+
+    #[test]
+    fn test_get_with_metadata_tracks_last_write() {
+        let dir = TempDir::new().unwrap();
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.get_with_metadata(b"k").unwrap(), None);
+
+        engine.put(b"other", b"x").unwrap();
+        engine.put(b"k", b"v1").unwrap();
+        let first = engine.get_with_metadata(b"k").unwrap().unwrap();
+        assert_eq!(first.value, b"v1");
+        assert_eq!(first.last_wal_sequence, Some(engine.wal_sequence()));
+        assert!(first.last_wal_offset.unwrap() > 0);
+        assert!(first.dirty);
+
+        engine.rotate_wal().unwrap();
+        engine.put(b"k", b"v2").unwrap();
+        engine.put_batch(&[(b"batched", b"b")]).unwrap();
+        let second = engine.get_with_metadata(b"k").unwrap().unwrap();
+        assert_eq!(second.value, b"v2");
+        assert!(second.last_wal_sequence > first.last_wal_sequence);
+        assert_eq!(second.last_wal_offset, Some(0));
+
+        // Replicated writes have no local WAL record
+        let replicated = WalReader::new(dir.path().join("wal")).recover_entries().unwrap().remove(0);
+        assert_eq!(replicated.key, b"other");
+        engine.apply_wal_entry(&replicated).unwrap();
+        assert_eq!(engine.get_with_metadata(b"other").unwrap().unwrap().last_wal_sequence, None);
+
+        // Reopening recovers the same positions from the WAL
+        let batched = engine.get_with_metadata(b"batched").unwrap().unwrap();
+        drop(engine);
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        let recovered = engine.get_with_metadata(b"k").unwrap().unwrap();
+        assert_eq!((recovered.last_wal_sequence, recovered.last_wal_offset), (second.last_wal_sequence, second.last_wal_offset));
+        assert!(!recovered.dirty);
+        let recovered = engine.get_with_metadata(b"batched").unwrap().unwrap();
+        assert_eq!(recovered.last_wal_offset, batched.last_wal_offset);
+
+        engine.delete(b"k").unwrap();
+        assert_eq!(engine.get_with_metadata(b"k").unwrap(), None);
+    }
+
+    #[test]
+    fn test_open_with_wrong_encryption_key_fails() {
+        let dir = TempDir::new().unwrap();
+        let config = Config { encryption_key: Some([1u8; 32]), ..Config::default() };
+        let engine = ClawStoreEngine::open(dir.path(), config.clone()).unwrap();
+        engine.put(b"a", b"1").unwrap();
+        engine.put(b"b", b"2").unwrap();
+        drop(engine);
+
+        let wrong = Config { encryption_key: Some([2u8; 32]), ..Config::default() };
+        let result = ClawStoreEngine::open(dir.path(), wrong);
+        assert!(matches!(result, Err(ClawError::DecryptionFailed { .. })));
+
+        // Nothing was lost: the right key still recovers everything
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        assert_eq!(engine.get(b"b").unwrap(), Some(b"2".to_vec()));
+        assert_eq!(engine.len(), 2);
+    }
+
+    #[test]
+    fn test_copy_to_and_copy_table_to() {
+        let (source, _src_dir) = test_engine();
+        for i in 0..20u8 {
+            source.put_fast(&[1, i], &[i; 4]).unwrap();
+            source.put_fast(&[2, i], &[i; 8]).unwrap();
+        }
+        let before: Vec<(Vec<u8>, Vec<u8>)> = source.prefix_scan(&[]);
+
+        let (dest, _dest_dir) = test_engine();
+        assert_eq!(source.copy_to(&dest).unwrap(), 40);
+        assert_eq!(dest.prefix_scan(&[]), before);
+        // Source untouched
+        assert_eq!(source.prefix_scan(&[]), before);
+
+        let (table_dest, _table_dir) = test_engine();
+        assert_eq!(source.copy_table_to(2, &table_dest).unwrap(), 20);
+        assert_eq!(table_dest.prefix_scan(&[]), source.prefix_scan(&[2]).into_iter()
+            .map(|(k, v)| ([&[2u8][..], &k].concat(), v))
+            .collect::<Vec<_>>());
+
+        assert!(source.copy_to(&source).is_err());
+    }
+
     #[test]
     fn test_put_overwrite() {
         let (engine, _dir) = test_engine();
@@ -332,6 +2677,413 @@ mod tests {
         assert_eq!(engine.len(), 0);
     }
 
+    #[test]
+    fn test_put_with_callback_reports_wal_offset() {
+        let (engine, dir) = test_engine();
+        engine.put(b"first", b"1").unwrap();
+
+        let offset = Arc::new(AtomicU64::new(u64::MAX));
+        let seen = Arc::clone(&offset);
+        engine.put_with_callback(b"tracked", b"value", move |at| seen.store(at, Ordering::SeqCst)).unwrap();
+        let offset = offset.load(Ordering::SeqCst);
+        assert!(offset > 0);
+
+        // The offset locates exactly this entry in the active WAL file
+        let wal_file = dir.path().join("wal").join(format!("wal-{:016x}.claw", engine.wal_sequence()));
+        let bytes = std::fs::read(wal_file).unwrap();
+        let entry = crate::format::deserialize_entry(&bytes[offset as usize..]).unwrap();
+        assert_eq!((entry.key.as_slice(), entry.value.as_slice()), (&b"tracked"[..], &b"value"[..]));
+        assert_eq!(engine.get(b"tracked").unwrap(), Some(b"value".to_vec()));
+    }
+
+    #[test]
+    fn test_put_fast_if_absent() {
+        let (engine, _dir) = test_engine();
+        assert!(engine.put_fast_if_absent(b"k", b"first").unwrap());
+        assert!(!engine.put_fast_if_absent(b"k", b"second").unwrap());
+        assert_eq!(engine.get(b"k").unwrap(), Some(b"first".to_vec()));
+        assert_eq!(engine.dirty_count(), 1);
+    }
+
+    #[test]
+    fn test_put_many_if_all_absent_racing_threads() {
+        let (engine, _dir) = test_engine();
+        let a: Vec<(&[u8], &[u8])> = vec![(b"idx:1", b"a1"), (b"idx:2", b"a2"), (b"idx:3", b"a3")];
+        let b: Vec<(&[u8], &[u8])> = vec![(b"idx:1", b"b1"), (b"idx:2", b"b2"), (b"idx:3", b"b3")];
+
+        let (won_a, won_b) = std::thread::scope(|s| {
+            let ta = s.spawn(|| engine.put_many_if_all_absent(&a).unwrap());
+            let tb = s.spawn(|| engine.put_many_if_all_absent(&b).unwrap());
+            (ta.join().unwrap(), tb.join().unwrap())
+        });
+        assert!(won_a ^ won_b);
+
+        let winner = if won_a { &a } else { &b };
+        for (key, value) in winner {
+            assert_eq!(engine.get(key).unwrap().as_deref(), Some(*value));
+        }
+        assert_eq!(engine.len(), 3);
+
+        // One existing key blocks the whole set
+        assert!(!engine.put_many_if_all_absent(&[(b"idx:4", b"x"), (b"idx:1", b"x")]).unwrap());
+        assert!(!engine.contains_key(b"idx:4"));
+    }
+
+    #[test]
+    fn test_apply_wal_entries_batch_skips_local_wal() {
+        let (primary, primary_dir) = test_engine();
+        primary.put(b"a", b"1").unwrap();
+        primary.put(b"b", b"2").unwrap();
+        primary.put(b"c", b"3").unwrap();
+        primary.delete(b"b").unwrap();
+        let entries = WalReader::new(primary_dir.path().join("wal")).recover_entries().unwrap();
+
+        let (replica, replica_dir) = test_engine();
+        assert_eq!(replica.apply_wal_entries_batch(&entries).unwrap(), 4);
+        assert_eq!(replica.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(replica.get(b"b").unwrap(), None);
+        assert_eq!(replica.get(b"c").unwrap(), Some(b"3".to_vec()));
+        assert_eq!(replica.dirty_count(), 3);
+
+        replica.apply_wal_entry(&entries[1]).unwrap();
+        assert_eq!(replica.get(b"b").unwrap(), Some(b"2".to_vec()));
+
+        replica.sync_wal().unwrap();
+        let local = WalReader::new(replica_dir.path().join("wal")).recover_entries().unwrap();
+        assert!(local.is_empty());
+    }
+
+    #[test]
+    fn test_merge_engines_other_wins() {
+        let (shard_a, _dir_a) = test_engine();
+        let (shard_b, dir_b) = test_engine();
+        shard_a.put(b"shared", b"from a").unwrap();
+        shard_a.put(b"only a", b"a").unwrap();
+        shard_b.put(b"shared", b"from b").unwrap();
+        for i in 0..2000u16 {
+            shard_b.put_fast(&i.to_be_bytes(), b"b").unwrap();
+        }
+        shard_b.start_trickle().unwrap();
+
+        let syncs_before = shard_a.wal().unwrap().sync_count();
+        assert_eq!(shard_a.merge_engines(shard_b).unwrap(), 2001);
+        assert_eq!(shard_a.wal().unwrap().sync_count() - syncs_before, 1);
+        assert_eq!(shard_a.len(), 2002);
+        assert_eq!(shard_a.get(b"only a").unwrap(), Some(b"a".to_vec()));
+        assert_eq!(shard_a.get(b"shared").unwrap(), Some(b"from b".to_vec()));
+        assert_eq!(shard_a.get(&1999u16.to_be_bytes()).unwrap(), Some(b"b".to_vec()));
+
+        // The merged shard was closed cleanly and can be reopened
+        let reopened = ClawStoreEngine::open(dir_b.path(), Config::for_testing()).unwrap();
+        assert_eq!(reopened.len(), 2001);
+    }
+
+    #[test]
+    fn test_merge_engines_failure_leaves_both_intact() {
+        use crate::platform_durability::{clear_fault_injector, set_fault_injector, SimulatedDisk};
+
+        let (shard_a, _dir_a) = test_engine();
+        let (shard_b, dir_b) = test_engine();
+        shard_a.put(b"shared", b"from a").unwrap();
+        shard_b.put(b"shared", b"from b").unwrap();
+        shard_b.put(b"only b", b"b").unwrap();
+        let generation = shard_a.write_generation();
+
+        // Every sync on this thread fails, the merge batch's included
+        set_fault_injector(SimulatedDisk::fail_after(0));
+        let result = shard_a.merge_engines(shard_b);
+        clear_fault_injector();
+        assert!(result.is_err());
+        assert_eq!(shard_a.write_generation(), generation);
+        assert_eq!(shard_a.len(), 1);
+        assert_eq!(shard_a.get(b"shared").unwrap(), Some(b"from a".to_vec()));
+
+        // The merged shard is still on disk, so the merge can be retried
+        let reopened = ClawStoreEngine::open(dir_b.path(), Config::for_testing()).unwrap();
+        assert_eq!(shard_a.merge_engines(reopened).unwrap(), 2);
+        assert_eq!(shard_a.get(b"shared").unwrap(), Some(b"from b".to_vec()));
+        assert_eq!(shard_a.get(b"only b").unwrap(), Some(b"b".to_vec()));
+    }
+
+    #[test]
+    fn test_replica_stream_converges() {
+        let (source, _source_dir) = test_engine();
+        for i in 0..10u8 {
+            source.put(&[0, i], b"before").unwrap();
+        }
+        let mut stream = source.create_replica_stream();
+
+        for i in 0..100u8 {
+            match i % 4 {
+                3 => source.delete(&[0, i % 10]).unwrap(),
+                _ => source.put(&[1, i % 30], &[i; 3]).unwrap(),
+            }
+        }
+        // Batch writes publish one event per key
+        source.put_batch(&[([2u8, 0], b"a"), ([2, 1], b"b"), ([0, 1], b"c")]).unwrap();
+        source.apply_batch(&[([2u8, 0], None), ([2, 2], Some(b"d")), ([1, 0], None)]).unwrap();
+
+        let (replica, _replica_dir) = test_engine();
+        let events: Vec<ReplicaEvent> = stream.by_ref().take(107).map(|e| e.unwrap()).collect();
+        assert!(matches!(&events[0], ReplicaEvent::Snapshot { entries, .. } if entries.len() == 10));
+        assert!(events.iter().enumerate().all(|(i, e)| e.sequence() == i as u64));
+        for event in &events {
+            replica.apply_wal_entries_batch(&event.to_wal_entries().unwrap()).unwrap();
+        }
+        assert_eq!(replica.prefix_scan(&[]), source.prefix_scan(&[]));
+
+        drop(source);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_rotate_data_file_seals_flushed_entries() {
+        let (engine, _dir) = test_engine();
+        assert!(engine.rotate_data_file().is_err());
+
+        engine.put(b"k1", b"v1").unwrap();
+        engine.put(b"k2", b"v2").unwrap();
+        engine.start_trickle().unwrap();
+        let sealed = engine.rotate_data_file().unwrap();
+
+        let mut keys: Vec<Vec<u8>> = DataFileReader::scan_all(&sealed).unwrap()
+            .into_iter().map(|e| e.key).collect();
+        keys.sort();
+        assert_eq!(keys, vec![b"k1".to_vec(), b"k2".to_vec()]);
+        assert_ne!(latest_data_file(&engine.path().join("data")), Some(sealed));
+        engine.stop_trickle();
+    }
+
+    #[test]
+    fn test_consistency_check_flags() {
+        let (engine, dir) = test_engine();
+        engine.put(b"k", b"v").unwrap();
+        assert!(engine.is_consistent());
+        assert!(engine.is_consistent());
+        assert_eq!(engine.len(), 1);
+        assert!(!engine.contains_key(CONSISTENCY_PROBE_KEY));
+        assert_eq!(engine.dirty_count(), 1);
+
+        // A value stored under the probe key survives the probe and a reopen
+        engine.put(CONSISTENCY_PROBE_KEY, b"user").unwrap();
+        assert!(engine.is_consistent());
+        assert_eq!(engine.get(CONSISTENCY_PROBE_KEY).unwrap(), Some(b"user".to_vec()));
+        engine.delete(CONSISTENCY_PROBE_KEY).unwrap();
+
+        // Unflushed deletes leave more dirty keys than entries
+        engine.put(b"gone", b"v").unwrap();
+        engine.delete(b"gone").unwrap();
+        engine.delete(b"k").unwrap();
+        let details = engine.consistency_check_details();
+        assert!(!details.dirty_within_len);
+        assert!(details.wal_file_exists && details.data_dir_exists && details.probe_round_trip);
+
+        let wal_file = engine.wal().unwrap().current_path().to_path_buf();
+        std::fs::remove_file(&wal_file).unwrap();
+        std::fs::remove_dir_all(dir.path().join("data")).unwrap();
+        let details = engine.consistency_check_details();
+        assert!(!details.wal_file_exists);
+        assert!(!details.data_dir_exists);
+        assert!(!engine.is_consistent());
+        drop(engine);
+
+        let dir = TempDir::new().unwrap();
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        engine.put(b"k", b"v").unwrap();
+        assert!(engine.is_consistent());
+        drop(engine);
+        let reader = ClawStoreEngine::open_with_mode(dir.path(), Config::default(), OpenMode::ReadOnly).unwrap();
+        assert!(reader.is_consistent());
+        assert_eq!(reader.len(), 1);
+        assert_eq!(reader.get(CONSISTENCY_PROBE_KEY).unwrap(), None);
+    }
+
+    #[test]
+    fn test_consistency_probe_does_not_evict_in_cache_mode() {
+        let dir = TempDir::new().unwrap();
+        let config = Config { cache_mode: Some(2), ..Config::for_testing() };
+        let engine = ClawStoreEngine::open(dir.path(), config.clone()).unwrap();
+        engine.put(b"a", b"1").unwrap();
+        engine.put(b"b", b"2").unwrap();
+        assert!(engine.is_consistent());
+        drop(engine);
+
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        assert_eq!(engine.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(engine.get(b"b").unwrap(), Some(b"2".to_vec()));
+    }
+
+    #[test]
+    fn test_vacuum_reclaims_deleted_space() {
+        let dir = TempDir::new().unwrap();
+        let config = Config { warm_on_open: true, ..Config::default() };
+        let engine = ClawStoreEngine::open(dir.path(), config.clone()).unwrap();
+        for i in 0..100u32 {
+            engine.put(format!("key-{:03}", i).as_bytes(), &[i as u8; 64]).unwrap();
+        }
+        engine.vacuum().unwrap();
+        for i in (0..100u32).step_by(2) {
+            engine.delete(format!("key-{:03}", i).as_bytes()).unwrap();
+        }
+
+        let report = engine.vacuum().unwrap();
+        assert!(report.bytes_saved > 0);
+        assert_eq!(report.bytes_before - report.bytes_after, report.bytes_saved);
+        assert_eq!(report.data_files_compacted, 1);
+        assert_eq!(report.wal_files_removed, 1);
+        assert_eq!(engine.dirty_count(), 0);
+        for i in (1..100u32).step_by(2) {
+            assert_eq!(engine.get(format!("key-{:03}", i).as_bytes()).unwrap(), Some(vec![i as u8; 64]));
+        }
+        drop(engine);
+
+        // Deleted keys stay deleted with their WAL entries gone
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        assert_eq!(engine.len(), 50);
+        assert!(!engine.contains_key(b"key-000"));
+        assert!(engine.contains_key(b"key-099"));
+    }
+
+    #[test]
+    fn test_memory_breakdown_after_mass_delete() {
+        let (engine, _dir) = test_engine();
+        for i in 0..1000u32 {
+            engine.put_fast(&i.to_be_bytes(), &[7u8; 8]).unwrap();
+        }
+        let full = engine.memory_usage_breakdown();
+        assert_eq!(full.hashmap_data_bytes, 1000 * 12);
+        assert!(full.dirty_tracker_bytes >= 1000 * 4);
+        assert!(full.wal_positions_bytes >= 1000 * 4);
+        assert_eq!(full.history_cache_bytes, 0);
+
+        for i in 0..900u32 {
+            engine.delete_fast(&i.to_be_bytes()).unwrap();
+        }
+        let sparse = engine.memory_usage_breakdown();
+        assert_eq!(sparse.hashmap_data_bytes, 100 * 12);
+        assert!(sparse.hashmap_overhead_bytes > sparse.hashmap_data_bytes);
+        assert!(sparse.wal_positions_bytes < full.wal_positions_bytes);
+        assert_eq!(sparse.wal_buffer_bytes, 0);
+
+        engine.rotate_wal().unwrap();
+        engine.get_at_wal_sequence(&999u32.to_be_bytes(), 0).unwrap();
+        let cached = engine.memory_usage_breakdown();
+        assert!(cached.history_cache_bytes > 0);
+        assert_eq!(
+            cached.total_estimate_bytes,
+            cached.hashmap_data_bytes
+                + cached.hashmap_overhead_bytes
+                + cached.dirty_tracker_bytes
+                + cached.wal_positions_bytes
+                + cached.history_cache_bytes
+        );
+    }
+
+    #[test]
+    fn test_delete_fast_and_batch() {
+        let (engine, dir) = test_engine();
+        for key in [b"a", b"b", b"c", b"d"] {
+            engine.put(key, b"v").unwrap();
+        }
+
+        engine.delete_fast(b"a").unwrap();
+        assert!(!engine.contains_key(b"a"));
+        engine.delete_batch_fast(&[b"b", b"c"]).unwrap();
+        assert!(!engine.contains_key(b"b") && !engine.contains_key(b"c"));
+        assert_eq!(engine.len(), 1);
+        assert_eq!(engine.dirty_count(), 4);
+        engine.sync_wal().unwrap();
+
+        // Synced to the WAL file before the engine closes it
+        let reader = WalReader::new(dir.path().join("wal"));
+        let deleted: Vec<_> = reader.recover_entries().unwrap().into_iter()
+            .filter(|e| e.operation == Operation::Delete)
+            .map(|e| e.key)
+            .collect();
+        assert_eq!(deleted, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+
+        drop(engine);
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.len(), 1);
+        assert!(engine.contains_key(b"d"));
+    }
+
+    #[test]
+    fn test_diff_classifies_changes() {
+        let (before, _dir_a) = test_engine();
+        let (after, _dir_b) = test_engine();
+        for (key, value) in [(&b"same"[..], &b"1"[..]), (b"changed", b"old"), (b"dropped", b"x")] {
+            before.put_fast(key, value).unwrap();
+        }
+        for (key, value) in [(&b"same"[..], &b"1"[..]), (b"changed", b"new"), (b"fresh-2", b"b"), (b"fresh-1", b"a")] {
+            after.put_fast(key, value).unwrap();
+        }
+
+        let diff = before.diff(&after).unwrap();
+        assert_eq!(diff.added, vec![(b"fresh-1".to_vec(), b"a".to_vec()), (b"fresh-2".to_vec(), b"b".to_vec())]);
+        assert_eq!(diff.modified, vec![(b"changed".to_vec(), b"old".to_vec(), b"new".to_vec())]);
+        assert_eq!(diff.removed, vec![b"dropped".to_vec()]);
+
+        // The reverse diff swaps the roles
+        let back = after.diff(&before).unwrap();
+        assert_eq!(back.removed, vec![b"fresh-1".to_vec(), b"fresh-2".to_vec()]);
+        assert_eq!(back.modified, vec![(b"changed".to_vec(), b"new".to_vec(), b"old".to_vec())]);
+        assert_eq!(back.added, vec![(b"dropped".to_vec(), b"x".to_vec())]);
+
+        assert!(before.diff(&before).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_and_delete() {
+        let (engine, dir) = test_engine();
+        assert!(!engine.compare_and_delete(b"k", b"v").unwrap());
+        engine.put(b"k", b"v").unwrap();
+        assert!(!engine.compare_and_delete(b"k", b"other").unwrap());
+        assert_eq!(engine.get(b"k").unwrap(), Some(b"v".to_vec()));
+        assert!(engine.compare_and_delete(b"k", b"v").unwrap());
+        assert!(!engine.contains_key(b"k"));
+        assert_eq!(engine.dirty_count(), 1);
+
+        // The delete is durable
+        drop(engine);
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert!(!engine.contains_key(b"k"));
+    }
+
+    #[test]
+    fn test_compare_and_delete_races_with_update() {
+        let (engine, _dir) = test_engine();
+        let engine = Arc::new(engine);
+
+        for round in 0..20u8 {
+            let key = [b'r', round];
+            engine.put(&key, b"old").unwrap();
+
+            // Two deleters expecting "old" and one updater: at most one
+            // delete wins, and a delete never removes the updated value
+            let barrier = Arc::new(std::sync::Barrier::new(3));
+            let deleters: Vec<_> = (0..2).map(|_| {
+                let (e, b) = (Arc::clone(&engine), Arc::clone(&barrier));
+                std::thread::spawn(move || {
+                    b.wait();
+                    e.compare_and_delete(&key, b"old").unwrap()
+                })
+            }).collect();
+            let updater = {
+                let (e, b) = (Arc::clone(&engine), Arc::clone(&barrier));
+                std::thread::spawn(move || {
+                    b.wait();
+                    e.put(&key, b"new").unwrap();
+                })
+            };
+
+            let deleted = deleters.into_iter().map(|h| h.join().unwrap()).filter(|&d| d).count();
+            updater.join().unwrap();
+            assert!(deleted <= 1);
+            assert_eq!(engine.get(&key).unwrap(), Some(b"new".to_vec()));
+        }
+    }
+
     #[test]
     fn test_crash_recovery() {
         let dir = TempDir::new().unwrap();
@@ -351,11 +3103,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_crash_recovery_with_concurrent_hashmap() {
+        use crate::platform_durability::{clear_fault_injector, set_fault_injector, SimulatedDisk};
+
+        let dir = TempDir::new().unwrap();
+        let config = Config { use_concurrent_hashmap: true, ..Config::for_testing() };
+        {
+            let engine = ClawStoreEngine::open(dir.path(), config.clone()).unwrap();
+            engine.put(b"flushed", b"v1").unwrap();
+            engine.start_trickle().unwrap();
+            engine.stop_trickle();
+            assert_eq!(engine.dirty_count(), 0);
+
+            engine.put(b"flushed", b"v2").unwrap();
+            engine.put_batch(&[(b"b1".to_vec(), b"1".to_vec()), (b"b2".to_vec(), b"2".to_vec())]).unwrap();
+            engine.put(b"doomed", b"temp").unwrap();
+            engine.delete(b"doomed").unwrap();
+
+            // A failed WAL sync must leave RAM untouched
+            set_fault_injector(SimulatedDisk::fail_after(0));
+            assert!(engine.put(b"lost", b"x").is_err());
+            clear_fault_injector();
+            assert_eq!(engine.get(b"lost").unwrap(), None);
+        }
+        let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
+        assert_eq!(engine.get(b"flushed").unwrap(), Some(b"v2".to_vec()));
+        assert_eq!(engine.get_many(&[b"b1".as_slice(), b"b2"]).unwrap(), vec![Some(b"1".to_vec()), Some(b"2".to_vec())]);
+        assert_eq!(engine.get(b"doomed").unwrap(), None);
+        // The unsynced record may or may not have reached the disk
+        let expected = if engine.get(b"lost").unwrap().is_some() { 4 } else { 3 };
+        assert_eq!(engine.len(), expected);
+        assert_eq!(engine.read_view().iter_keys_unsorted().count(), expected);
+    }
+
     #[test]
     fn test_trickle_integration() {
         let dir = TempDir::new().unwrap();
         let mut config = Config::default();
-        config.trickle_cadence = Duration::from_millis(50);
+        config.trickle_mode = TrickleMode::Fixed(Duration::from_millis(50));
 
         let engine = ClawStoreEngine::open(dir.path(), config).unwrap();
         engine.put(b"t1", b"v1").unwrap();
@@ -417,6 +3203,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_many() {
+        let (engine, _dir) = test_engine();
+        engine.put(b"a", b"1").unwrap();
+        engine.put(b"c", b"3").unwrap();
+
+        let locks_before = engine.read_lock_count();
+        let values = engine.get_many(&[b"a".as_slice(), b"b", b"c"]).unwrap();
+        assert_eq!(values, vec![Some(b"1".to_vec()), None, Some(b"3".to_vec())]);
+        assert_eq!(engine.read_lock_count() - locks_before, 1);
+    }
+
+    #[test]
+    fn test_put_cross_table_single_sync() {
+        let (engine, _dir) = test_engine();
+        let syncs_before = engine.wal().unwrap().sync_count();
+
+        let writes: Vec<CrossTableWrite> = (0..10u8)
+            .map(|i| CrossTableWrite {
+                prefix: 0x0C + i % 3,
+                key: vec![i],
+                value: vec![i; 4],
+            })
+            .collect();
+        engine.put_cross_table(&writes).unwrap();
+
+        assert_eq!(engine.wal().unwrap().sync_count() - syncs_before, 1);
+        assert_eq!(engine.len(), 10);
+        assert_eq!(engine.dirty_count(), 10);
+        assert_eq!(engine.prefix_count(&[0x0C]), 4);
+        assert_eq!(engine.get(&[0x0D, 1]).unwrap(), Some(vec![1; 4]));
+    }
+
+    #[test]
+    fn test_put_durable_ordered_flushes_in_sequence() {
+        let (engine, dir) = test_engine();
+        engine.start_trickle().unwrap();
+
+        let keys: Vec<[u8; 4]> = (0..1000u32).rev().map(|i| i.to_be_bytes()).collect();
+        for chunk in keys.chunks(100) {
+            let batch: Vec<(&[u8], &[u8])> = chunk.iter().map(|k| (&k[..], &b"v"[..])).collect();
+            engine.put_durable_ordered(&batch).unwrap();
+        }
+        engine.stop_trickle(); // final flush
+
+        let data_file = crate::datafile::latest_data_file(&dir.path().join("data")).unwrap();
+        let flushed: Vec<Vec<u8>> = crate::datafile::DataFileReader::scan_all(&data_file).unwrap()
+            .into_iter().map(|e| e.key).collect();
+        let expected: Vec<Vec<u8>> = keys.iter().map(|k| k.to_vec()).collect();
+        assert_eq!(flushed, expected);
+
+        let recovered = WalReader::new(dir.path().join("wal")).recover_entries().unwrap();
+        let seqs: Vec<u64> = recovered.iter().map(|e| e.sequence.unwrap()).collect();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_put_batch() {
+        let (engine, _dir) = test_engine();
+        let syncs_before = engine.wal().unwrap().sync_count();
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..50u8).map(|i| (vec![i], vec![i; 3])).collect();
+        engine.put_batch(&entries).unwrap();
+
+        assert_eq!(engine.wal().unwrap().sync_count() - syncs_before, 1);
+        assert_eq!(engine.len(), 50);
+        assert_eq!(engine.get(&[7]).unwrap(), Some(vec![7; 3]));
+        assert_eq!(engine.dirty_count(), 50);
+    }
+
+    #[test]
+    fn test_apply_batch_mixed_ops() {
+        let (engine, dir) = test_engine();
+        engine.put(b"old", b"1").unwrap();
+        let syncs_before = engine.wal().unwrap().sync_count();
+        engine.apply_batch(&[
+            (&b"new"[..], Some(&b"2"[..])),
+            (b"old", None),
+            (b"new", Some(b"3")),
+        ]).unwrap();
+
+        assert_eq!(engine.wal().unwrap().sync_count() - syncs_before, 1);
+        assert_eq!(engine.get(b"new").unwrap(), Some(b"3".to_vec()));
+        assert!(!engine.contains_key(b"old"));
+        drop(engine);
+
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        assert_eq!(engine.get(b"new").unwrap(), Some(b"3".to_vec()));
+        assert_eq!(engine.len(), 1);
+    }
+
     #[test]
     fn test_put_fast() {
         let (engine, _dir) = test_engine();
@@ -445,6 +3321,42 @@ mod tests {
         assert_eq!(results[2], (vec![b'c'], b"val_c".to_vec()));
     }
 
+    #[test]
+    fn test_table_stats() {
+        let (engine, _dir) = test_engine();
+        engine.put(&[0x05, 1, 2], &[0u8; 10]).unwrap();
+        engine.put(&[0x05, 1, 2, 3, 4], &[0u8; 40]).unwrap();
+        engine.put(&[0x05, 9, 9, 9], &[0u8; 1]).unwrap();
+        engine.put(&[0x06, 1], &[0u8; 1000]).unwrap(); // other table
+
+        let stats = engine.table_stats(0x05);
+        assert_eq!(stats.entry_count, 3);
+        assert_eq!((stats.min_key_len, stats.max_key_len), (2, 4));
+        assert_eq!(stats.avg_key_len, 3.0);
+        assert_eq!((stats.min_val_len, stats.max_val_len), (1, 40));
+        assert_eq!(stats.avg_val_len, 17.0);
+        assert_eq!(engine.table_stats(0x07), EntryStats::default());
+    }
+
+    #[test]
+    fn test_iter_all_keys() {
+        let (engine, _dir) = test_engine();
+        for i in [5u8, 1, 9, 3] {
+            engine.put(&[0x01, i], &[i]).unwrap();
+        }
+        engine.put(b"zz", b"other").unwrap();
+
+        let keys: Vec<Vec<u8>> = engine.iter_all_keys().collect();
+        assert_eq!(keys.len(), engine.len());
+        let scanned: Vec<Vec<u8>> = engine.prefix_scan(&[]).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, scanned);
+
+        let view = engine.read_view();
+        assert_eq!(view.iter_keys_unsorted().count(), 5);
+        let total: usize = view.iter_all_entries_unsorted().map(|(_, v)| v.len()).sum();
+        assert_eq!(total, 4 + 5);
+    }
+
     #[test]
     fn test_prefix_count() {
         let (engine, _dir) = test_engine();
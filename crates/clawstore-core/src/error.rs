@@ -5,7 +5,9 @@
 
 use std::fmt;
 use std::error::Error;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// ClawStore error types with detailed context
 #[derive(Debug, Clone)]
@@ -18,6 +20,10 @@ pub enum ClawError {
         kind: std::io::ErrorKind,
         /// Human-readable description
         message: String,
+        /// The original error, exposed through `Error::source`
+        ///
+        /// Held in an `Arc` so `ClawError` stays `Clone`.
+        inner: Option<Arc<dyn Error + Send + Sync>>,
     },
 
     /// WAL file is corrupted and cannot be recovered
@@ -81,12 +87,124 @@ pub enum ClawError {
         /// Bytes actually found
         found_bytes: [u8; 4],
     },
+
+    /// Store directory cannot be created or written to
+    PathNotWritable {
+        /// Directory that failed the check
+        path: PathBuf,
+        /// Underlying failure description
+        reason: String,
+    },
+
+    /// Filesystem rejected a durable sync (e.g. no fdatasync support)
+    DurableSyncUnsupported {
+        /// Directory that failed the check
+        path: PathBuf,
+        /// Underlying failure description
+        reason: String,
+    },
+
+    /// Not enough free disk space for a WAL segment plus a data file
+    InsufficientDiskSpace {
+        /// Directory that failed the check
+        path: PathBuf,
+        /// Bytes available to this process
+        available_bytes: u64,
+        /// Bytes required
+        required_bytes: u64,
+    },
+
+    /// Store directory is locked by another process
+    DirectoryLocked {
+        /// Locked directory
+        path: PathBuf,
+    },
+
+    /// Store is already open by another engine instance or process
+    AlreadyOpen {
+        /// Store directory
+        path: PathBuf,
+    },
+
+    /// Write attempted on a store opened in read-only mode
+    ReadOnly {
+        /// Store directory
+        path: PathBuf,
+    },
+
+    /// Registering metrics with a Prometheus registry failed
+    #[cfg(feature = "metrics")]
+    Metrics {
+        /// Description from the prometheus crate
+        reason: String,
+    },
+
+    /// WAL entry is encrypted but no encryption key was configured
+    EncryptionKeyRequired {
+        /// File containing the encrypted entry
+        path: PathBuf,
+        /// Offset of the encrypted entry
+        offset: u64,
+    },
+
+    /// WAL entry failed AES-GCM authentication under the configured key
+    /// (its CRC checked out, so the key is wrong rather than the bytes)
+    DecryptionFailed {
+        /// File containing the encrypted entry
+        path: PathBuf,
+        /// Offset of the encrypted entry
+        offset: u64,
+    },
+
+    /// WAL entry was written in a format version this build cannot parse
+    UnsupportedFormatVersion {
+        /// `format_version` byte from the entry header
+        version: u8,
+    },
+
+    /// In-place data file rewrite with a key or value of a different size
+    RewriteSizeMismatch {
+        /// Data file holding the entry
+        path: PathBuf,
+        /// Offset of the entry
+        offset: u64,
+        /// Whether the key or the value differs in size
+        component: String,
+        /// Size stored in the entry header
+        expected: u64,
+        /// Size supplied for the rewrite
+        actual: u64,
+    },
+}
+
+impl ClawError {
+    /// Whether retrying the same operation may succeed.
+    ///
+    /// True only for I/O errors of kind `WouldBlock`, `TimedOut` or
+    /// `Interrupted`; corruption, size limits and state errors are permanent.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ClawError::Io {
+                kind: io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted,
+                ..
+            }
+        )
+    }
+
+    /// Whether the error reports damaged on-disk data.
+    pub fn is_corruption(&self) -> bool {
+        matches!(
+            self,
+            ClawError::WalCorrupted { .. } | ClawError::ChecksumMismatch { .. } | ClawError::NoMagicFound { .. }
+        )
+    }
 }
 
 impl fmt::Display for ClawError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ClawError::Io { path, kind, message } => {
+            ClawError::Io { path, kind, message, .. } => {
                 if let Some(path) = path {
                     write!(f, "I/O error in {}: {} ({})", path.display(), message, kind)
                 } else {
@@ -122,11 +240,67 @@ impl fmt::Display for ClawError {
                 write!(f, "Magic bytes not found in {} at offset {}: found {:02x}{:02x}{:02x}{:02x}",
                        path.display(), offset, found_bytes[0], found_bytes[1], found_bytes[2], found_bytes[3])
             }
+
+            ClawError::PathNotWritable { path, reason } => {
+                write!(f, "Path {} is not writable: {}", path.display(), reason)
+            }
+
+            ClawError::DurableSyncUnsupported { path, reason } => {
+                write!(f, "Durable sync not supported in {}: {}", path.display(), reason)
+            }
+
+            ClawError::InsufficientDiskSpace { path, available_bytes, required_bytes } => {
+                write!(f, "Insufficient disk space in {}: {} bytes available, {} required",
+                       path.display(), available_bytes, required_bytes)
+            }
+
+            ClawError::DirectoryLocked { path } => {
+                write!(f, "Directory {} is locked by another ClawStore process", path.display())
+            }
+
+            ClawError::AlreadyOpen { path } => {
+                write!(f, "ClawStore at {} is already open in another instance", path.display())
+            }
+
+            ClawError::ReadOnly { path } => {
+                write!(f, "ClawStore at {} is open in read-only mode", path.display())
+            }
+
+            #[cfg(feature = "metrics")]
+            ClawError::Metrics { reason } => {
+                write!(f, "Metrics registration failed: {}", reason)
+            }
+
+            ClawError::EncryptionKeyRequired { path, offset } => {
+                write!(f, "Encrypted WAL entry in {} at offset {} but no encryption key configured",
+                       path.display(), offset)
+            }
+
+            ClawError::DecryptionFailed { path, offset } => {
+                write!(f, "WAL entry in {} at offset {} failed AES-GCM authentication (wrong encryption key?)",
+                       path.display(), offset)
+            }
+
+            ClawError::UnsupportedFormatVersion { version } => {
+                write!(f, "Unsupported WAL entry format version {}", version)
+            }
+
+            ClawError::RewriteSizeMismatch { path, offset, component, expected, actual } => {
+                write!(f, "Cannot rewrite {} of entry in {} at offset {} in place: {} bytes stored, {} given",
+                       component, path.display(), offset, expected, actual)
+            }
         }
     }
 }
 
-impl Error for ClawError {}
+impl Error for ClawError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClawError::Io { inner, .. } => inner.as_deref().map(|e| e as &(dyn Error + 'static)),
+            _ => None,
+        }
+    }
+}
 
 /// Convert std::io::Error to ClawError::Io
 impl From<std::io::Error> for ClawError {
@@ -135,6 +309,7 @@ impl From<std::io::Error> for ClawError {
             path: None,
             kind: err.kind(),
             message: err.to_string(),
+            inner: Some(Arc::new(err)),
         }
     }
 }
@@ -142,6 +317,83 @@ impl From<std::io::Error> for ClawError {
 /// Result type alias for ClawStore operations
 pub type ClawResult<T> = Result<T, ClawError>;
 
+/// Wrap an I/O error with the file path and a description of the failed step.
+///
+/// The message reads `"{msg}: {err}"`, matching the rest of the crate.
+pub fn wrap_io(err: io::Error, path: &Path, msg: &str) -> ClawError {
+    ClawError::Io {
+        path: Some(path.to_path_buf()),
+        kind: err.kind(),
+        message: format!("{}: {}", msg, err),
+        inner: Some(Arc::new(err)),
+    }
+}
+
+/// Adds file-path and message context to fallible results.
+///
+/// Replaces verbose `map_err(|e| ClawError::Io { ... })` closures:
+///
+/// ```ignore
+/// file.write_all(&buf).with_context("WAL write failed").with_path(&path)?;
+/// ```
+pub trait ClawResultExt<T> {
+    /// Attach the file path where the error occurred (kept if already set).
+    fn with_path(self, path: &Path) -> ClawResult<T>;
+    /// Prefix the error description with `msg`.
+    fn with_context(self, msg: &str) -> ClawResult<T>;
+}
+
+impl<T> ClawResultExt<T> for ClawResult<T> {
+    fn with_path(self, path: &Path) -> ClawResult<T> {
+        self.map_err(|err| match err {
+            ClawError::Io { path: None, kind, message, inner } => ClawError::Io {
+                path: Some(path.to_path_buf()),
+                kind,
+                message,
+                inner,
+            },
+            other => other,
+        })
+    }
+
+    fn with_context(self, msg: &str) -> ClawResult<T> {
+        self.map_err(|err| match err {
+            ClawError::Io { path, kind, message, inner } => ClawError::Io {
+                path,
+                kind,
+                message: format!("{}: {}", msg, message),
+                inner,
+            },
+            ClawError::WalCorrupted { path, offset, reason } => ClawError::WalCorrupted {
+                path,
+                offset,
+                reason: format!("{}: {}", msg, reason),
+            },
+            other => other,
+        })
+    }
+}
+
+impl<T> ClawResultExt<T> for io::Result<T> {
+    fn with_path(self, path: &Path) -> ClawResult<T> {
+        self.map_err(|err| ClawError::Io {
+            path: Some(path.to_path_buf()),
+            kind: err.kind(),
+            message: err.to_string(),
+            inner: Some(Arc::new(err)),
+        })
+    }
+
+    fn with_context(self, msg: &str) -> ClawResult<T> {
+        self.map_err(|err| ClawError::Io {
+            path: None,
+            kind: err.kind(),
+            message: format!("{}: {}", msg, err),
+            inner: Some(Arc::new(err)),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,6 +413,41 @@ mod tests {
         assert!(display.contains("0x87654321"));
     }
 
+    #[test]
+    fn test_transient_and_corruption_classification() {
+        let io = |kind| ClawError::from(io::Error::new(kind, "io"));
+        let path = PathBuf::from("/tmp/x");
+        let cases = vec![
+            (io(io::ErrorKind::WouldBlock), true, false),
+            (io(io::ErrorKind::TimedOut), true, false),
+            (io(io::ErrorKind::Interrupted), true, false),
+            (io(io::ErrorKind::NotFound), false, false),
+            (io(io::ErrorKind::PermissionDenied), false, false),
+            (ClawError::WalCorrupted { path: path.clone(), offset: 0, reason: String::new() }, false, true),
+            (ClawError::ChecksumMismatch { path: path.clone(), expected: 1, actual: 2, offset: 0 }, false, true),
+            (ClawError::NoMagicFound { path: path.clone(), offset: 0, found_bytes: [0; 4] }, false, true),
+            (ClawError::TornWrite { path: path.clone(), expected_size: 1, available_bytes: 0, offset: 0 }, false, false),
+            (ClawError::SnapshotMemoryExceeded { requested_bytes: 2, limit_bytes: 1 }, false, false),
+            (ClawError::OversizedEntry { entry_size: 2, max_size: 1, component: "key".into() }, false, false),
+            (ClawError::PathNotWritable { path: path.clone(), reason: String::new() }, false, false),
+            (ClawError::DurableSyncUnsupported { path: path.clone(), reason: String::new() }, false, false),
+            (ClawError::InsufficientDiskSpace { path: path.clone(), available_bytes: 0, required_bytes: 1 }, false, false),
+            (ClawError::DirectoryLocked { path: path.clone() }, false, false),
+            (ClawError::AlreadyOpen { path: path.clone() }, false, false),
+            (ClawError::ReadOnly { path: path.clone() }, false, false),
+            (ClawError::EncryptionKeyRequired { path: path.clone(), offset: 0 }, false, false),
+            (ClawError::DecryptionFailed { path, offset: 0 }, false, false),
+            (ClawError::UnsupportedFormatVersion { version: 2 }, false, false),
+            (ClawError::RewriteSizeMismatch {
+                path: PathBuf::from("/tmp/x"), offset: 0, component: "value".into(), expected: 32, actual: 33,
+            }, false, false),
+        ];
+        for (err, transient, corruption) in cases {
+            assert_eq!(err.is_transient(), transient, "{}", err);
+            assert_eq!(err.is_corruption(), corruption, "{}", err);
+        }
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -171,4 +458,57 @@ mod tests {
             _ => panic!("Expected Io error"),
         }
     }
+
+    #[test]
+    fn test_result_ext_context_in_display() {
+        let io_result: io::Result<()> = Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+        let err = io_result
+            .with_context("Failed to open WAL file")
+            .with_path(Path::new("/tmp/wal-0.claw"))
+            .unwrap_err();
+
+        let display = format!("{}", err);
+        assert!(display.contains("/tmp/wal-0.claw"));
+        assert!(display.contains("Failed to open WAL file: denied"));
+        assert!(matches!(err, ClawError::Io { kind: io::ErrorKind::PermissionDenied, .. }));
+    }
+
+    #[test]
+    fn test_result_ext_keeps_existing_path() {
+        let err: ClawResult<()> = Err(wrap_io(
+            io::Error::new(io::ErrorKind::NotFound, "gone"),
+            Path::new("/data/original"),
+            "Failed to stat data file",
+        ));
+        let err = err.with_path(Path::new("/data/other")).with_context("Compaction").unwrap_err();
+
+        let display = format!("{}", err);
+        assert!(display.contains("/data/original"));
+        assert!(!display.contains("/data/other"));
+        assert!(display.contains("Compaction: Failed to stat data file: gone"));
+    }
+
+    #[test]
+    fn test_source_chain_reaches_io_error() {
+        let io_result: io::Result<()> = Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+        let err = io_result
+            .with_context("Failed to open WAL file")
+            .with_path(Path::new("/tmp/wal-0.claw"))
+            .unwrap_err();
+
+        let mut chain = Vec::new();
+        let mut current: Option<&(dyn Error + 'static)> = err.source();
+        while let Some(cause) = current {
+            chain.push(cause.to_string());
+            current = cause.source();
+        }
+        assert_eq!(chain, vec!["denied".to_string()]);
+
+        let io_err = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io_err.kind(), io::ErrorKind::PermissionDenied);
+
+        let cloned = err.clone();
+        assert!(cloned.source().is_some());
+        assert!(ClawError::AlreadyOpen { path: PathBuf::from("/tmp") }.source().is_none());
+    }
 }
@@ -2,8 +2,20 @@
 //!
 //! All WAL entries follow a consistent format:
 //! ChunkHeader (32 bytes) + key_len(u16) + value_len(u32) + operation(u8) + padding(u8) + key_bytes + value_bytes
+//!
+//! A `Batch` record wraps several entries' key_len/value_len/operation/padding
+//! /key/value groups under one header and one CRC, so the batch is recovered
+//! whole or not at all.
+//!
+//! When an encryption key is configured, everything after the header is
+//! sealed with AES-256-GCM (ciphertext + 16-byte tag) and the 12-byte nonce
+//! is stored in the header padding.
+
+use std::borrow::Cow;
 
 use crate::error::{ClawError, ClawResult};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
 
 /// Magic bytes identifying ClawStore WAL entries: "CLAW" in ASCII (little-endian)
 pub const MAGIC_BYTES: u32 = 0x574C4143; // 'C','L','A','W' stored as little-endian u32
@@ -20,6 +32,23 @@ pub const MAX_VALUE_SIZE: usize = 32 * 1024 * 1024;
 /// Header size in bytes
 pub const HEADER_SIZE: usize = 32;
 
+/// Entry format version written by this build (`ChunkHeader::format_version`)
+///
+/// Headers written before versioning carry 0 there and are read as version 1.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// AES-GCM nonce size in bytes (stored in `ChunkHeader::_padding[0..12]`)
+pub const NONCE_SIZE: usize = 12;
+
+/// Header flag (in `reserved[0]`): payload is AES-256-GCM encrypted
+pub const FLAG_ENCRYPTED: u8 = 0x01;
+
+/// Header flag (in `reserved[0]`): payload starts with a u64 LE sequence number
+pub const FLAG_SEQUENCED: u8 = 0x02;
+
+/// Offset in `ChunkHeader::_padding` of a `Batch` record's u32 LE entry count
+pub const BATCH_COUNT_OFFSET: usize = 12;
+
 /// WAL operation types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -28,6 +57,10 @@ pub enum Operation {
     Put = 1,
     /// Delete a key
     Delete = 2,
+    /// Marks a WAL file as cleanly closed; the value carries the reason
+    Seal = 3,
+    /// Record holding several Put/Delete entries under one CRC (header only)
+    Batch = 4,
 }
 
 /// Fixed-size header for each WAL entry
@@ -38,8 +71,10 @@ pub enum Operation {
 ///   [4..8]   length:     u32  - payload length in bytes
 ///   [8..12]  checksum:   u32  - CRC32C of payload bytes
 ///   [12]     entry_type: u8   - operation type
-///   [13..16] reserved:   [u8;3]
-///   [16..32] padding:    [u8;16]
+///   [13..15] reserved:   [u8;2]   - reserved[0] holds flags (FLAG_ENCRYPTED, FLAG_SEQUENCED)
+///   [15]     format_version: u8   - payload layout version (FORMAT_VERSION; 0 = pre-versioning)
+///   [16..32] padding:    [u8;16]  - AES-GCM nonce in [16..28] when encrypted,
+///                                   batch entry count (u32 LE) in [28..32]
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct ChunkHeader {
@@ -49,11 +84,13 @@ pub struct ChunkHeader {
     pub length: u32,
     /// CRC32C checksum of the payload bytes
     pub checksum: u32,
-    /// Operation type (Put=1 or Delete=2)
+    /// Operation type (Put=1, Delete=2, Seal=3 or Batch=4)
     pub entry_type: u8,
-    /// Reserved for future use, must be zero
-    pub reserved: [u8; 3],
-    /// Padding to reach 32 bytes
+    /// reserved[0] holds entry flags; reserved[1] must be zero
+    pub reserved: [u8; 2],
+    /// Payload layout version; readers skip versions they don't know
+    pub format_version: u8,
+    /// Padding to reach 32 bytes; carries the nonce for encrypted entries
     pub _padding: [u8; 16],
 }
 
@@ -64,6 +101,15 @@ pub struct WalEntry {
     pub key: Vec<u8>,
     pub value: Vec<u8>,
     pub operation: Operation,
+    /// Write sequence number, present for entries written via ordered puts
+    pub sequence: Option<u64>,
+}
+
+impl WalEntry {
+    /// True if this is a SEAL record written by `WalWriter::seal`.
+    pub fn is_seal(&self) -> bool {
+        self.operation == Operation::Seal
+    }
 }
 
 impl ChunkHeader {
@@ -74,7 +120,8 @@ impl ChunkHeader {
             length,
             checksum,
             entry_type: entry_type as u8,
-            reserved: [0; 3],
+            reserved: [0; 2],
+            format_version: FORMAT_VERSION,
             _padding: [0; 16],
         }
     }
@@ -86,8 +133,9 @@ impl ChunkHeader {
         buf[4..8].copy_from_slice(&self.length.to_le_bytes());
         buf[8..12].copy_from_slice(&self.checksum.to_le_bytes());
         buf[12] = self.entry_type;
-        buf[13..16].copy_from_slice(&self.reserved);
-        // _padding is already zeroed
+        buf[13..15].copy_from_slice(&self.reserved);
+        buf[15] = self.format_version;
+        buf[16..32].copy_from_slice(&self._padding);
         buf
     }
 
@@ -101,7 +149,8 @@ impl ChunkHeader {
             length: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
             checksum: u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
             entry_type: bytes[12],
-            reserved: [bytes[13], bytes[14], bytes[15]],
+            reserved: [bytes[13], bytes[14]],
+            format_version: bytes[15],
             _padding: {
                 let mut pad = [0u8; 16];
                 pad.copy_from_slice(&bytes[16..32]);
@@ -109,13 +158,207 @@ impl ChunkHeader {
             },
         }
     }
+
+    /// Whether the payload following this header is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.reserved[0] & FLAG_ENCRYPTED != 0
+    }
+
+    /// Whether the payload carries a sequence number
+    pub fn is_sequenced(&self) -> bool {
+        self.reserved[0] & FLAG_SEQUENCED != 0
+    }
+
+    /// AES-GCM nonce for encrypted entries
+    pub fn nonce(&self) -> [u8; NONCE_SIZE] {
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce.copy_from_slice(&self._padding[..NONCE_SIZE]);
+        nonce
+    }
+
+    /// Whether this header starts a `Batch` record
+    pub fn is_batch(&self) -> bool {
+        self.entry_type == Operation::Batch as u8
+    }
+
+    /// Number of entries in a `Batch` record (0 for other records)
+    pub fn batch_count(&self) -> u32 {
+        if !self.is_batch() {
+            return 0;
+        }
+        let b = &self._padding[BATCH_COUNT_OFFSET..BATCH_COUNT_OFFSET + 4];
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+    }
 }
 
 /// Serialize a key-value pair into a complete WAL entry
 ///
 /// Format: ChunkHeader(32) + key_len(u16 LE) + value_len(u32 LE) + operation(u8) + padding(u8) + key + value
 pub fn serialize_entry(key: &[u8], value: &[u8], op: Operation) -> ClawResult<Vec<u8>> {
+    serialize_entry_with_key(key, value, op, None)
+}
+
+/// Serialize a WAL entry, encrypting the payload when `encryption_key` is set.
+///
+/// The CRC32C in the header covers the bytes as stored (the ciphertext), so
+/// corruption is still detected and skipped during recovery without the key.
+pub fn serialize_entry_with_key(
+    key: &[u8],
+    value: &[u8],
+    op: Operation,
+    encryption_key: Option<&[u8; 32]>,
+) -> ClawResult<Vec<u8>> {
+    serialize_entry_sequenced(key, value, op, None, encryption_key)
+}
+
+/// Serialize a WAL entry carrying an optional write sequence number.
+///
+/// With a sequence, the payload is prefixed by `sequence(u64 LE)` and the
+/// header sets `FLAG_SEQUENCED`. Encryption (if any) covers the sequence too.
+pub fn serialize_entry_sequenced(
+    key: &[u8],
+    value: &[u8],
+    op: Operation,
+    sequence: Option<u64>,
+    encryption_key: Option<&[u8; 32]>,
+) -> ClawResult<Vec<u8>> {
     // Validate input sizes BEFORE any allocation (prevents memory exhaustion attacks)
+    check_entry_size(key, value)?;
+
+    // Payload: [sequence(8)] + key_len(2) + value_len(4) + operation(1) + padding(1) + key + value
+    let payload_size = 8 + 2 + 4 + 1 + 1 + key.len() + value.len();
+
+    // Build payload to compute checksum
+    let mut payload = Vec::with_capacity(payload_size);
+    if let Some(seq) = sequence {
+        payload.extend_from_slice(&seq.to_le_bytes());
+    }
+    push_entry_fields(&mut payload, key, value, op);
+
+    let mut header = ChunkHeader::new(0, 0, op);
+    if sequence.is_some() {
+        header.reserved[0] |= FLAG_SEQUENCED;
+    }
+    assemble_record(header, payload, encryption_key)
+}
+
+/// Serialize several entries into one `Batch` record with a single CRC.
+///
+/// Each entry keeps its own key_len/value_len/operation fields; only the
+/// 32-byte header is shared. Entries must be `Put` or `Delete`.
+pub fn serialize_batch(entries: &[(&[u8], &[u8], Operation)]) -> ClawResult<Vec<u8>> {
+    serialize_batch_with_key(entries, None)
+}
+
+/// Serialize a `Batch` record, encrypting the whole payload when
+/// `encryption_key` is set.
+pub fn serialize_batch_with_key(
+    entries: &[(&[u8], &[u8], Operation)],
+    encryption_key: Option<&[u8; 32]>,
+) -> ClawResult<Vec<u8>> {
+    let mut payload_size = 0u64;
+    for (key, value, op) in entries {
+        check_entry_size(key, value)?;
+        if !matches!(op, Operation::Put | Operation::Delete) {
+            return Err(ClawError::WalCorrupted {
+                path: std::path::PathBuf::from("<buffer>"),
+                offset: 0,
+                reason: format!("Batch entries must be Put or Delete, got {:?}", op),
+            });
+        }
+        payload_size += (8 + key.len() + value.len()) as u64;
+    }
+    if payload_size > u32::MAX as u64 - 16 {
+        return Err(ClawError::OversizedEntry {
+            entry_size: payload_size,
+            max_size: u32::MAX as u64 - 16,
+            component: "batch".to_string(),
+        });
+    }
+
+    let mut payload = Vec::with_capacity(payload_size as usize);
+    for (key, value, op) in entries {
+        push_entry_fields(&mut payload, key, value, *op);
+    }
+
+    let mut header = ChunkHeader::new(0, 0, Operation::Batch);
+    header._padding[BATCH_COUNT_OFFSET..BATCH_COUNT_OFFSET + 4]
+        .copy_from_slice(&(entries.len() as u32).to_le_bytes());
+    assemble_record(header, payload, encryption_key)
+}
+
+/// Unencrypted WAL entries serialized back to back into one buffer.
+///
+/// Each `append_entry` writes the same bytes as `serialize_entry` straight
+/// into the shared buffer, so a batch costs one allocation (none if
+/// `capacity_hint` was large enough) instead of one per entry. `clear`
+/// keeps the allocation for the next batch.
+#[derive(Debug, Default, Clone)]
+pub struct BulkEntryBuffer {
+    bytes: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl BulkEntryBuffer {
+    /// Empty buffer with room for `capacity_hint` bytes of entries.
+    pub fn new(capacity_hint: usize) -> Self {
+        Self { bytes: Vec::with_capacity(capacity_hint), offsets: Vec::new() }
+    }
+
+    /// Serialize one entry onto the end of the buffer; returns its offset.
+    ///
+    /// An oversized key or value leaves the buffer unchanged.
+    pub fn append_entry(&mut self, key: &[u8], value: &[u8], op: Operation) -> ClawResult<usize> {
+        check_entry_size(key, value)?;
+
+        let start = self.bytes.len();
+        self.bytes.extend_from_slice(&[0u8; HEADER_SIZE]);
+        push_entry_fields(&mut self.bytes, key, value, op);
+
+        // Header last: length and CRC cover the payload just written
+        let payload = &self.bytes[start + HEADER_SIZE..];
+        let header = ChunkHeader::new(payload.len() as u32, crc32c::crc32c(payload), op);
+        self.bytes[start..start + HEADER_SIZE].copy_from_slice(&header.to_bytes());
+
+        self.offsets.push(start);
+        Ok(start)
+    }
+
+    /// All serialized entries, ready to append to a WAL file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Starting offset of each entry in `as_bytes`, in append order.
+    pub fn entry_offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Number of entries in the buffer.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// True if no entry has been appended since creation or `clear`.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Remove all entries, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.offsets.clear();
+    }
+
+    /// Bytes of entry `index`.
+    pub(crate) fn entry_bytes(&self, index: usize) -> &[u8] {
+        let end = self.offsets.get(index + 1).copied().unwrap_or(self.bytes.len());
+        &self.bytes[self.offsets[index]..end]
+    }
+}
+
+/// Reject keys or values over the format limits.
+pub(crate) fn check_entry_size(key: &[u8], value: &[u8]) -> ClawResult<()> {
     if key.len() > MAX_KEY_SIZE {
         return Err(ClawError::OversizedEntry {
             entry_size: key.len() as u64,
@@ -131,28 +374,47 @@ pub fn serialize_entry(key: &[u8], value: &[u8], op: Operation) -> ClawResult<Ve
             component: "value".to_string(),
         });
     }
+    Ok(())
+}
 
-    // Payload: key_len(2) + value_len(4) + operation(1) + padding(1) + key + value
-    let payload_size = 2 + 4 + 1 + 1 + key.len() + value.len();
-    let total_size = HEADER_SIZE + payload_size;
-
-    // Build payload to compute checksum
-    let mut payload = Vec::with_capacity(payload_size);
+/// Append key_len(2) + value_len(4) + operation(1) + padding(1) + key + value.
+fn push_entry_fields(payload: &mut Vec<u8>, key: &[u8], value: &[u8], op: Operation) {
     payload.extend_from_slice(&(key.len() as u16).to_le_bytes());
     payload.extend_from_slice(&(value.len() as u32).to_le_bytes());
     payload.push(op as u8);
     payload.push(0); // padding byte
     payload.extend_from_slice(key);
     payload.extend_from_slice(value);
+}
 
-    // Compute CRC32C checksum over payload bytes
-    let checksum = crc32c::crc32c(&payload);
+/// Encrypt (optionally) and checksum `payload`, then prefix it with `header`.
+///
+/// `header` supplies the entry type, flags and batch count; length,
+/// checksum and nonce are filled in here.
+fn assemble_record(
+    mut header: ChunkHeader,
+    mut payload: Vec<u8>,
+    encryption_key: Option<&[u8; 32]>,
+) -> ClawResult<Vec<u8>> {
+    // Seal the payload if encryption is enabled; nonce goes into the header
+    if let Some(enc_key) = encryption_key {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(enc_key));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        payload = cipher.encrypt(&nonce, payload.as_slice()).map_err(|_| ClawError::WalCorrupted {
+            path: std::path::PathBuf::from("<buffer>"),
+            offset: 0,
+            reason: "AES-GCM encryption failed".to_string(),
+        })?;
+        header.reserved[0] |= FLAG_ENCRYPTED;
+        header._padding[..NONCE_SIZE].copy_from_slice(&nonce);
+    }
 
-    // Create header
-    let header = ChunkHeader::new(payload.len() as u32, checksum, op);
+    // Compute CRC32C checksum over payload bytes (ciphertext when encrypted)
+    header.length = payload.len() as u32;
+    header.checksum = crc32c::crc32c(&payload);
 
     // Assemble complete entry: header + payload
-    let mut buffer = Vec::with_capacity(total_size);
+    let mut buffer = Vec::with_capacity(HEADER_SIZE + payload.len());
     buffer.extend_from_slice(&header.to_bytes());
     buffer.extend_from_slice(&payload);
 
@@ -161,6 +423,75 @@ pub fn serialize_entry(key: &[u8], value: &[u8], op: Operation) -> ClawResult<Ve
 
 /// Deserialize a WAL entry from a byte slice
 pub fn deserialize_entry(data: &[u8]) -> ClawResult<WalEntry> {
+    deserialize_entry_with_key(data, None)
+}
+
+/// Deserialize a WAL entry, decrypting it with `encryption_key` if the header
+/// marks it as encrypted.
+///
+/// Returns `EncryptionKeyRequired` for an encrypted entry when no key is given.
+/// A wrong key fails GCM authentication and is reported as
+/// `DecryptionFailed`. A `Batch` record holds several entries and is
+/// rejected here; use [`deserialize_entries_with_key`] for it. An entry whose
+/// header carries an unknown `format_version` returns
+/// `UnsupportedFormatVersion`.
+pub fn deserialize_entry_with_key(data: &[u8], encryption_key: Option<&[u8; 32]>) -> ClawResult<WalEntry> {
+    let (header, payload) = open_record(data, encryption_key)?;
+    if header.is_batch() {
+        return Err(ClawError::WalCorrupted {
+            path: std::path::PathBuf::from("<buffer>"),
+            offset: 0,
+            reason: format!("Batch record holds {} entries; use deserialize_entries", header.batch_count()),
+        });
+    }
+    single_entry(header, &payload)
+}
+
+/// Deserialize a WAL record into the entries it holds.
+///
+/// A `Batch` record yields all of its entries in write order; any other
+/// record yields exactly one.
+pub fn deserialize_entries(data: &[u8]) -> ClawResult<Vec<WalEntry>> {
+    deserialize_entries_with_key(data, None)
+}
+
+/// [`deserialize_entries`] with decryption, as in [`deserialize_entry_with_key`].
+pub fn deserialize_entries_with_key(data: &[u8], encryption_key: Option<&[u8; 32]>) -> ClawResult<Vec<WalEntry>> {
+    let (header, payload) = open_record(data, encryption_key)?;
+    if !header.is_batch() {
+        return Ok(vec![single_entry(header, &payload)?]);
+    }
+
+    let count = header.batch_count() as usize;
+    let mut entries = Vec::with_capacity(count.min(payload.len() / 8));
+    let mut pos = 0;
+    for _ in 0..count {
+        let (key, value, operation, consumed) = parse_entry_fields(&payload[pos..], HEADER_SIZE + pos)?;
+        if !matches!(operation, Operation::Put | Operation::Delete) {
+            return Err(ClawError::WalCorrupted {
+                path: std::path::PathBuf::from("<buffer>"),
+                offset: (HEADER_SIZE + pos + 6) as u64,
+                reason: format!("Invalid operation in batch: {:?}", operation),
+            });
+        }
+        entries.push(WalEntry { header, key, value, operation, sequence: None });
+        pos += consumed;
+    }
+
+    if pos != payload.len() {
+        return Err(ClawError::WalCorrupted {
+            path: std::path::PathBuf::from("<buffer>"),
+            offset: (HEADER_SIZE + pos) as u64,
+            reason: format!("Batch payload has {} trailing bytes after {} entries", payload.len() - pos, count),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Validate a record's header, CRC and format version, then decrypt its
+/// payload if needed.
+fn open_record<'a>(data: &'a [u8], encryption_key: Option<&[u8; 32]>) -> ClawResult<(ChunkHeader, Cow<'a, [u8]>)> {
     if data.len() < HEADER_SIZE {
         return Err(ClawError::WalCorrupted {
             path: std::path::PathBuf::from("<buffer>"),
@@ -195,10 +526,10 @@ pub fn deserialize_entry(data: &[u8]) -> ClawResult<WalEntry> {
         });
     }
 
-    let payload = &data[payload_start..payload_end];
+    let stored = &data[payload_start..payload_end];
 
     // Verify CRC32C checksum
-    let computed_checksum = crc32c::crc32c(payload);
+    let computed_checksum = crc32c::crc32c(stored);
     if computed_checksum != header.checksum {
         return Err(ClawError::ChecksumMismatch {
             path: std::path::PathBuf::from("<buffer>"),
@@ -208,23 +539,86 @@ pub fn deserialize_entry(data: &[u8]) -> ClawResult<WalEntry> {
         });
     }
 
-    // Parse payload: key_len(2) + value_len(4) + operation(1) + padding(1) + key + value
-    if payload.len() < 8 {
+    // Dispatch on the payload layout. The CRC passed, so an unknown version
+    // is a newer writer's entry rather than corruption.
+    match header.format_version {
+        0 | FORMAT_VERSION => {}
+        version => return Err(ClawError::UnsupportedFormatVersion { version }),
+    }
+
+    // Decrypt if needed — only after the CRC confirms the ciphertext is intact
+    let payload = if header.is_encrypted() {
+        let enc_key = encryption_key.ok_or_else(|| ClawError::EncryptionKeyRequired {
+            path: std::path::PathBuf::from("<buffer>"),
+            offset: 0,
+        })?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(enc_key));
+        let nonce = header.nonce();
+        // The CRC matched, so a failed tag means the key is wrong, not the bytes
+        let decrypted = cipher.decrypt(Nonce::from_slice(&nonce), stored).map_err(|_| ClawError::DecryptionFailed {
+            path: std::path::PathBuf::from("<buffer>"),
+            offset: 0,
+        })?;
+        Cow::Owned(decrypted)
+    } else {
+        Cow::Borrowed(stored)
+    };
+
+    Ok((header, payload))
+}
+
+/// Build a single (non-batch) entry from its verified payload.
+fn single_entry(header: ChunkHeader, payload: &[u8]) -> ClawResult<WalEntry> {
+    let payload_start = HEADER_SIZE;
+
+    // Strip the sequence prefix of ordered entries
+    let (sequence, fields, fields_start) = if header.is_sequenced() {
+        if payload.len() < 8 {
+            return Err(ClawError::WalCorrupted {
+                path: std::path::PathBuf::from("<buffer>"),
+                offset: payload_start as u64,
+                reason: "Payload too short for sequence number".to_string(),
+            });
+        }
+        let (seq_bytes, rest) = payload.split_at(8);
+        (Some(u64::from_le_bytes(seq_bytes.try_into().unwrap())), rest, payload_start + 8)
+    } else {
+        (None, payload, payload_start)
+    };
+
+    let (key, value, operation, _) = parse_entry_fields(fields, fields_start)?;
+    Ok(WalEntry {
+        header,
+        key,
+        value,
+        operation,
+        sequence,
+    })
+}
+
+/// Parse key_len(2) + value_len(4) + operation(1) + padding(1) + key + value
+/// from the start of `fields`.
+///
+/// `offset` is the position of `fields` within the record, for error
+/// reporting. Returns the key, value, operation and bytes consumed.
+fn parse_entry_fields(fields: &[u8], offset: usize) -> ClawResult<(Vec<u8>, Vec<u8>, Operation, usize)> {
+    if fields.len() < 8 {
         return Err(ClawError::WalCorrupted {
             path: std::path::PathBuf::from("<buffer>"),
-            offset: payload_start as u64,
+            offset: offset as u64,
             reason: "Payload too short for header fields".to_string(),
         });
     }
 
-    let key_len = u16::from_le_bytes([payload[0], payload[1]]) as usize;
-    let value_len = u32::from_le_bytes([payload[2], payload[3], payload[4], payload[5]]) as usize;
-    let operation = match payload[6] {
+    let key_len = u16::from_le_bytes([fields[0], fields[1]]) as usize;
+    let value_len = u32::from_le_bytes([fields[2], fields[3], fields[4], fields[5]]) as usize;
+    let operation = match fields[6] {
         1 => Operation::Put,
         2 => Operation::Delete,
+        3 => Operation::Seal,
         other => return Err(ClawError::WalCorrupted {
             path: std::path::PathBuf::from("<buffer>"),
-            offset: (payload_start + 6) as u64,
+            offset: (offset + 6) as u64,
             reason: format!("Invalid operation type: {}", other),
         }),
     };
@@ -233,21 +627,21 @@ pub fn deserialize_entry(data: &[u8]) -> ClawResult<WalEntry> {
     let key_end = data_start + key_len;
     let value_end = key_end + value_len;
 
-    if payload.len() < value_end {
+    if fields.len() < value_end {
         return Err(ClawError::WalCorrupted {
             path: std::path::PathBuf::from("<buffer>"),
-            offset: payload_start as u64,
+            offset: offset as u64,
             reason: format!("Payload too short: need {} bytes for key({}) + value({})",
                           value_end, key_len, value_len),
         });
     }
 
-    Ok(WalEntry {
-        header,
-        key: payload[data_start..key_end].to_vec(),
-        value: payload[key_end..value_end].to_vec(),
+    Ok((
+        fields[data_start..key_end].to_vec(),
+        fields[key_end..value_end].to_vec(),
         operation,
-    })
+        value_end,
+    ))
 }
 
 #[cfg(test)]
@@ -300,6 +694,48 @@ mod tests {
         assert!(matches!(result, Err(ClawError::OversizedEntry { component, .. }) if component == "value"));
     }
 
+    #[test]
+    fn test_bulk_buffer_matches_serialize_entry() {
+        let entries: Vec<(Vec<u8>, Vec<u8>, Operation)> = (0..100u32)
+            .map(|i| {
+                let op = if i % 10 == 9 { Operation::Delete } else { Operation::Put };
+                (format!("key-{i}").into_bytes(), vec![i as u8; i as usize], op)
+            })
+            .collect();
+        let mut expected = Vec::new();
+        let mut expected_offsets = Vec::new();
+        for (key, value, op) in &entries {
+            expected_offsets.push(expected.len());
+            expected.extend_from_slice(&serialize_entry(key, value, *op).unwrap());
+        }
+
+        // Sized up front: the buffer never reallocates
+        let mut buffer = BulkEntryBuffer::new(expected.len());
+        let base = buffer.as_bytes().as_ptr();
+        for (i, (key, value, op)) in entries.iter().enumerate() {
+            assert_eq!(buffer.append_entry(key, value, *op).unwrap(), expected_offsets[i]);
+        }
+        assert_eq!(buffer.as_bytes(), expected.as_slice());
+        assert_eq!(buffer.entry_offsets(), expected_offsets.as_slice());
+        assert_eq!(buffer.as_bytes().as_ptr(), base);
+
+        let third = deserialize_entry(buffer.entry_bytes(3)).unwrap();
+        assert_eq!(third.key, b"key-3");
+        assert_eq!(third.value, vec![3u8; 3]);
+
+        // Oversized entries are rejected without touching the buffer
+        let too_long = vec![0u8; MAX_KEY_SIZE + 1];
+        assert!(matches!(
+            buffer.append_entry(&too_long, b"v", Operation::Put),
+            Err(ClawError::OversizedEntry { .. })
+        ));
+        assert_eq!(buffer.len(), 100);
+        assert_eq!(buffer.as_bytes().len(), expected.len());
+
+        buffer.clear();
+        assert!(buffer.is_empty() && buffer.as_bytes().is_empty());
+    }
+
     #[test]
     fn test_corrupted_magic_detected() {
         let mut data = serialize_entry(b"key", b"value", Operation::Put).unwrap();
@@ -315,10 +751,102 @@ mod tests {
         assert!(matches!(deserialize_entry(&data), Err(ClawError::ChecksumMismatch { .. })));
     }
 
+    #[test]
+    fn test_encrypted_roundtrip() {
+        let enc_key = [7u8; 32];
+        let data = serialize_entry_with_key(b"key", b"secret", Operation::Put, Some(&enc_key)).unwrap();
+        let header = ChunkHeader::from_bytes(&data[..HEADER_SIZE].try_into().unwrap());
+        assert!(header.is_encrypted());
+        assert!(!data.windows(6).any(|w| w == b"secret"));
+
+        let entry = deserialize_entry_with_key(&data, Some(&enc_key)).unwrap();
+        assert_eq!(entry.key, b"key");
+        assert_eq!(entry.value, b"secret");
+    }
+
+    #[test]
+    fn test_encrypted_entry_requires_key() {
+        let enc_key = [7u8; 32];
+        let data = serialize_entry_with_key(b"key", b"secret", Operation::Put, Some(&enc_key)).unwrap();
+        assert!(matches!(deserialize_entry(&data), Err(ClawError::EncryptionKeyRequired { .. })));
+        assert!(matches!(
+            deserialize_entry_with_key(&data, Some(&[8u8; 32])),
+            Err(ClawError::DecryptionFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sequenced_roundtrip() {
+        let data = serialize_entry_sequenced(b"key", b"value", Operation::Put, Some(42), None).unwrap();
+        let entry = deserialize_entry(&data).unwrap();
+        assert_eq!(entry.sequence, Some(42));
+        assert_eq!(entry.key, b"key");
+        assert_eq!(entry.value, b"value");
+
+        let plain = deserialize_entry(&serialize_entry(b"key", b"value", Operation::Put).unwrap()).unwrap();
+        assert_eq!(plain.sequence, None);
+    }
+
+    #[test]
+    fn test_format_version_dispatch() {
+        let mut data = serialize_entry(b"key", b"value", Operation::Put).unwrap();
+        assert_eq!(data[15], FORMAT_VERSION);
+
+        // Pre-versioning headers (0) still parse as version 1
+        data[15] = 0;
+        assert_eq!(deserialize_entry(&data).unwrap().value, b"value");
+
+        // The version byte is outside the CRC, so a newer entry still verifies
+        data[15] = 2;
+        assert!(matches!(
+            deserialize_entries(&data),
+            Err(ClawError::UnsupportedFormatVersion { version: 2 })
+        ));
+    }
+
     #[test]
     fn test_max_key_size_accepted() {
         let key = vec![0x41u8; MAX_KEY_SIZE]; // exactly at limit
         let result = serialize_entry(&key, b"v", Operation::Put);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_batch_roundtrip_and_size() {
+        let keys: Vec<Vec<u8>> = (0..100u32).map(|i| i.to_le_bytes().to_vec()).collect();
+        let entries: Vec<(&[u8], &[u8], Operation)> = keys.iter()
+            .map(|k| (k.as_slice(), b"value".as_slice(), Operation::Put))
+            .collect();
+
+        let batch = serialize_batch(&entries).unwrap();
+        let individual: usize = entries.iter()
+            .map(|(k, v, op)| serialize_entry(k, v, *op).unwrap().len())
+            .sum();
+        assert_eq!(batch.len(), individual - 99 * HEADER_SIZE);
+
+        let decoded = deserialize_entries(&batch).unwrap();
+        assert_eq!(decoded.len(), 100);
+        assert_eq!(decoded[42].key, 42u32.to_le_bytes());
+        assert_eq!(decoded[42].value, b"value");
+        assert!(matches!(deserialize_entry(&batch), Err(ClawError::WalCorrupted { .. })));
+
+        let single = serialize_entry(b"k", b"v", Operation::Delete).unwrap();
+        let decoded = deserialize_entries(&single).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].operation, Operation::Delete);
+    }
+
+    #[test]
+    fn test_encrypted_batch_roundtrip() {
+        let enc_key = [7u8; 32];
+        let entries: [(&[u8], &[u8], Operation); 2] =
+            [(b"a", b"secret", Operation::Put), (b"b", b"", Operation::Delete)];
+        let batch = serialize_batch_with_key(&entries, Some(&enc_key)).unwrap();
+        assert!(!batch.windows(6).any(|w| w == b"secret"));
+
+        let decoded = deserialize_entries_with_key(&batch, Some(&enc_key)).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].value, b"secret");
+        assert_eq!(decoded[1].operation, Operation::Delete);
+    }
 }
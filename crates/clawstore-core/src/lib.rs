@@ -15,21 +15,31 @@
 //! It can be used for any key-value workload on any computer.
 //! Blockchain-specific adapters live in separate crates (e.g. clawstore-reth).
 
+pub mod access_log;
 pub mod compaction;
 pub mod config;
 pub mod datafile;
 pub mod engine;
 pub mod error;
 pub mod format;
+#[cfg(feature = "metrics")]
+mod metrics;
 pub mod platform_durability;
+mod ram;
+pub mod retry;
+pub mod stats;
+pub mod subscription;
 pub mod trickle;
 pub mod wal;
 
 // Re-export key types for convenience
-pub use config::Config;
-pub use datafile::{DataEntry, DataFileReader, DataFileWriter};
-pub use engine::ClawStoreEngine;
-pub use error::{ClawError, ClawResult};
-pub use format::Operation;
-pub use trickle::{DirtyTracker, TrickleHandle, start_trickle};
-pub use wal::{WalWriter, WalReader};
+pub use access_log::AccessLogWriter;
+pub use config::{Config, ConfigBuilder, ConfigError};
+pub use datafile::{DataEntry, DataFileReader, DataFileWriter, IntegrityReport};
+pub use engine::{ClawStoreEngine, ConsistencyCheckResult, ConsistencyReport, CrossTableWrite, EntryMetadata, KeyIter, OpenMode, ReadView, EngineDiff, VacuumReport};
+pub use error::{ClawError, ClawResult, ClawResultExt};
+pub use format::{BulkEntryBuffer, Operation};
+pub use stats::{EntryStats, MemoryBreakdown};
+pub use subscription::{ReplicaEvent, ReplicaStream, SubscriptionHandle, WriteEvent};
+pub use trickle::{DirtyTracker, TrickleHandle, TrickleMode, TrickleSource, start_trickle};
+pub use wal::{WalWriter, WalReader, WalPosition, WalFileInfo, WalRecoveryMode, WalRepairTool, WalIssue, WalIssueKind, RepairReport};
@@ -3,9 +3,62 @@
 //! Each platform has different guarantees for when data is actually written to persistent storage.
 //! This module provides a unified interface that maps to the strongest durability guarantee
 //! available on each platform.
+//!
+//! With the `fault_injection` feature (always on in this crate's tests), a
+//! [`SimulatedDisk`] can be installed on the current thread to make
+//! `durable_sync` fail, for testing how callers survive a lost sync.
 
+#[cfg(any(test, feature = "fault_injection"))]
+use std::cell::RefCell;
 use std::fs::File;
 use std::io;
+use std::path::Path;
+#[cfg(any(test, feature = "fault_injection"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Fault injector for `durable_sync`, installed per thread with
+/// [`set_fault_injector`].
+#[cfg(any(test, feature = "fault_injection"))]
+#[derive(Debug, Default)]
+pub struct SimulatedDisk {
+    /// Let this many syncs through, then fail every later one (None = never fail)
+    pub fail_after_n_syncs: Option<usize>,
+    /// Syncs attempted while installed, failed ones included
+    pub current_syncs: AtomicUsize,
+}
+
+#[cfg(any(test, feature = "fault_injection"))]
+impl SimulatedDisk {
+    /// A disk whose syncs start failing after `n` successful ones.
+    pub fn fail_after(n: usize) -> Self {
+        Self { fail_after_n_syncs: Some(n), current_syncs: AtomicUsize::new(0) }
+    }
+
+    /// Count one sync attempt; true if it should fail.
+    fn sync_fails(&self) -> bool {
+        let attempt = self.current_syncs.fetch_add(1, Ordering::Relaxed);
+        self.fail_after_n_syncs.is_some_and(|limit| attempt >= limit)
+    }
+}
+
+#[cfg(any(test, feature = "fault_injection"))]
+thread_local! {
+    static FAULT_INJECTOR: RefCell<Option<SimulatedDisk>> = const { RefCell::new(None) };
+}
+
+/// Route this thread's `durable_sync` calls through `disk`, replacing any
+/// injector already set. Syncs on other threads (e.g. trickle) are not affected.
+#[cfg(any(test, feature = "fault_injection"))]
+pub fn set_fault_injector(disk: SimulatedDisk) {
+    FAULT_INJECTOR.with(|injector| *injector.borrow_mut() = Some(disk));
+}
+
+/// Remove this thread's fault injector, returning it so its sync count can
+/// be inspected.
+#[cfg(any(test, feature = "fault_injection"))]
+pub fn clear_fault_injector() -> Option<SimulatedDisk> {
+    FAULT_INJECTOR.with(|injector| injector.borrow_mut().take())
+}
 
 /// Ensures data is durably written to persistent storage before returning.
 ///
@@ -19,6 +72,14 @@ use std::io;
 /// This function makes system calls that may block for extended periods during heavy I/O.
 /// The caller must not hold locks that could cause deadlocks during the sync operation.
 pub fn durable_sync(file: &File) -> io::Result<()> {
+    #[cfg(any(test, feature = "fault_injection"))]
+    {
+        let fail = FAULT_INJECTOR.with(|injector| injector.borrow().as_ref().is_some_and(SimulatedDisk::sync_fails));
+        if fail {
+            return Err(io::Error::other("simulated sync failure"));
+        }
+    }
+
     #[cfg(target_os = "linux")]
     {
         // Linux: fdatasync() syncs file data but not metadata (atime, mtime)
@@ -78,6 +139,172 @@ pub fn durable_sync(file: &File) -> io::Result<()> {
     }
 }
 
+/// Bytes available to unprivileged users on the filesystem containing `path`.
+///
+/// Platforms without a supported query report `u64::MAX` (check skipped).
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    #[cfg(unix)]
+    {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // SAFETY: statvfs only writes into the zeroed struct we pass; c_path is a
+        // valid NUL-terminated string that outlives the call.
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        let result = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+        if result == 0 {
+            // Field widths differ between platforms (u32 on macOS, u64 on Linux)
+            #[allow(clippy::unnecessary_cast)]
+            Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use winapi::um::fileapi::GetDiskFreeSpaceExW;
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        let mut free_to_caller: u64 = 0;
+        // SAFETY: wide is NUL-terminated; the output pointer is valid for the call.
+        // Unused totals may be null per the API contract.
+        let result = unsafe {
+            GetDiskFreeSpaceExW(
+                wide.as_ptr(),
+                &mut free_to_caller as *mut u64 as *mut _,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if result != 0 {
+            Ok(free_to_caller)
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = path;
+        Ok(u64::MAX)
+    }
+}
+
+/// Total physical memory of the machine in bytes.
+///
+/// Linux reads `MemTotal` from `/proc/meminfo`; other platforms return
+/// `ErrorKind::Unsupported`.
+pub fn total_memory() -> io::Result<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo")?;
+        parse_meminfo_total(&meminfo)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "MemTotal not found in /proc/meminfo"))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "total memory query not supported on this platform"))
+    }
+}
+
+/// Bytes in the `MemTotal:  <n> kB` line of `/proc/meminfo` contents.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line["MemTotal:".len()..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    kib.checked_mul(1024)
+}
+
+/// Try to take an exclusive advisory lock on `file` without blocking.
+///
+/// Returns `Ok(false)` if another process already holds the lock. The lock
+/// is released when the file handle is closed.
+pub fn try_lock_exclusive(file: &File) -> io::Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: flock operates on a valid open file descriptor obtained from File.
+        let result = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+        if result == 0 {
+            return Ok(true);
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() == io::ErrorKind::WouldBlock {
+            Ok(false)
+        } else {
+            Err(err)
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsRawHandle;
+        use winapi::um::fileapi::LockFile;
+        // SAFETY: LockFile operates on a valid handle obtained from File.
+        // Locks the first byte, which is enough for mutual exclusion.
+        let result = unsafe { LockFile(file.as_raw_handle() as *mut _, 0, 0, 1, 0) };
+        Ok(result != 0)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = file;
+        Ok(true)
+    }
+}
+
+/// Try to take a shared advisory lock on `file` without blocking.
+///
+/// Any number of shared holders may coexist; returns `Ok(false)` if another
+/// process holds the exclusive lock. Released when the file handle is closed.
+pub fn try_lock_shared(file: &File) -> io::Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: flock operates on a valid open file descriptor obtained from File.
+        let result = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) };
+        if result == 0 {
+            return Ok(true);
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() == io::ErrorKind::WouldBlock {
+            Ok(false)
+        } else {
+            Err(err)
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsRawHandle;
+        use winapi::um::fileapi::LockFileEx;
+        use winapi::um::minwinbase::{LOCKFILE_FAIL_IMMEDIATELY, OVERLAPPED};
+        // SAFETY: LockFileEx operates on a valid handle obtained from File and a
+        // zeroed OVERLAPPED (offset 0). No LOCKFILE_EXCLUSIVE_LOCK flag = shared.
+        let result = unsafe {
+            let mut overlapped: OVERLAPPED = std::mem::zeroed();
+            LockFileEx(
+                file.as_raw_handle() as *mut _,
+                LOCKFILE_FAIL_IMMEDIATELY,
+                0,
+                1,
+                0,
+                &mut overlapped,
+            )
+        };
+        Ok(result != 0)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = file;
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,4 +319,57 @@ mod tests {
         let result = durable_sync(file.as_file());
         assert!(result.is_ok(), "durable_sync failed: {:?}", result.err());
     }
+
+    #[test]
+    fn test_simulated_disk_fails_after_limit() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        set_fault_injector(SimulatedDisk::fail_after(2));
+        assert!(durable_sync(file.as_file()).is_ok());
+        assert!(durable_sync(file.as_file()).is_ok());
+        let err = durable_sync(file.as_file()).unwrap_err();
+        assert_eq!(err.to_string(), "simulated sync failure");
+        assert!(durable_sync(file.as_file()).is_err());
+
+        let disk = clear_fault_injector().unwrap();
+        assert_eq!(disk.current_syncs.load(Ordering::Relaxed), 4);
+        assert!(durable_sync(file.as_file()).is_ok());
+    }
+
+    #[test]
+    fn test_available_space_reports_bytes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(available_space(dir.path()).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_parse_meminfo_total() {
+        let meminfo = "MemFree:         1024 kB\nMemTotal:       16384000 kB\nBuffers: 0 kB\n";
+        assert_eq!(parse_meminfo_total(meminfo), Some(16_384_000 * 1024));
+        assert_eq!(parse_meminfo_total("MemFree: 1 kB\n"), None);
+        assert_eq!(parse_meminfo_total("MemTotal: lots kB\n"), None);
+        #[cfg(target_os = "linux")]
+        assert!(total_memory().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_exclusive_lock_conflicts() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let first = File::open(file.path()).unwrap();
+        let second = File::open(file.path()).unwrap();
+        assert!(try_lock_exclusive(&first).unwrap());
+        assert!(!try_lock_exclusive(&second).unwrap());
+        drop(first);
+        assert!(try_lock_exclusive(&second).unwrap());
+    }
+
+    #[test]
+    fn test_shared_locks_coexist() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let first = File::open(file.path()).unwrap();
+        let second = File::open(file.path()).unwrap();
+        let writer = File::open(file.path()).unwrap();
+        assert!(try_lock_shared(&first).unwrap());
+        assert!(try_lock_shared(&second).unwrap());
+        assert!(!try_lock_exclusive(&writer).unwrap());
+    }
 }
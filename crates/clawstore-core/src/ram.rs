@@ -0,0 +1,258 @@
+//! RAM working set of the engine
+//!
+//! [`RamStore`] keeps the key-value map either behind one `RwLock` (the
+//! default) or, with `Config::use_concurrent_hashmap`, in a sharded
+//! `DashMap`. In the concurrent layout `get` only locks the key's shard,
+//! so point reads never wait for a writer holding another shard.
+//!
+//! Everything else goes through the guards returned by `read` and `write`,
+//! which behave the same in both layouts. The concurrent layout pairs the
+//! map with a gate lock: writers hold it exclusively for their whole
+//! update, and read guards hold it shared plus a read lock on every shard.
+//! Multi-key reads (`get_many`, scans, `read_view`) therefore still see
+//! whole writes, while a lone `get` may observe a batch half applied.
+
+use std::collections::hash_map::RandomState;
+
+use dashmap::{DashMap, SharedValue};
+use hashbrown::HashMap;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Plain RAM map, as rebuilt on open.
+pub(crate) type RamMap = HashMap<Vec<u8>, Vec<u8>>;
+
+/// One `DashMap` shard.
+type Shard = hashbrown::HashMap<Vec<u8>, SharedValue<Vec<u8>>, RandomState>;
+
+/// Read lock on one `DashMap` shard.
+type ShardReadGuard<'a> = dashmap::RwLockReadGuard<'a, Shard>;
+
+/// The engine's RAM map in one of its two layouts.
+pub(crate) enum RamStore {
+    /// One lock for the whole map
+    Locked(RwLock<RamMap>),
+    /// Sharded map; `gate` serializes writers against multi-key readers
+    Concurrent {
+        gate: RwLock<()>,
+        map: DashMap<Vec<u8>, Vec<u8>>,
+    },
+}
+
+impl RamStore {
+    /// Wrap `map` in the layout selected by `concurrent`.
+    pub(crate) fn new(map: RamMap, concurrent: bool) -> Self {
+        if concurrent {
+            let mut shards = DashMap::with_capacity(map.len());
+            shards.extend(map);
+            Self::Concurrent { gate: RwLock::new(()), map: shards }
+        } else {
+            Self::Locked(RwLock::new(map))
+        }
+    }
+
+    /// Copy of `key`'s value. Takes no map-wide lock in the concurrent layout.
+    pub(crate) fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            Self::Locked(map) => map.read().get(key).cloned(),
+            Self::Concurrent { map, .. } => map.get(key).map(|r| r.value().clone()),
+        }
+    }
+
+    /// Lock the whole map for reading.
+    pub(crate) fn read(&self) -> RamReadGuard<'_> {
+        match self {
+            Self::Locked(map) => RamReadGuard::Locked(map.read()),
+            Self::Concurrent { gate, map } => {
+                let gate = gate.read();
+                let shards = map.shards().iter().map(|shard| shard.read()).collect();
+                RamReadGuard::Concurrent { _gate: gate, map, shards }
+            }
+        }
+    }
+
+    /// Lock the whole map for writing.
+    pub(crate) fn write(&self) -> RamWriteGuard<'_> {
+        match self {
+            Self::Locked(map) => RamWriteGuard::Locked(map.write()),
+            Self::Concurrent { gate, map } => RamWriteGuard::Concurrent { _gate: gate.write(), map },
+        }
+    }
+}
+
+/// Shared access to every entry, from [`RamStore::read`].
+pub(crate) enum RamReadGuard<'a> {
+    Locked(RwLockReadGuard<'a, RamMap>),
+    Concurrent {
+        _gate: RwLockReadGuard<'a, ()>,
+        map: &'a DashMap<Vec<u8>, Vec<u8>>,
+        shards: Vec<ShardReadGuard<'a>>,
+    },
+}
+
+impl RamReadGuard<'_> {
+    pub(crate) fn get(&self, key: &[u8]) -> Option<&Vec<u8>> {
+        match self {
+            Self::Locked(map) => map.get(key),
+            Self::Concurrent { map, shards, .. } => {
+                shards[map.determine_map(key)].get(key).map(SharedValue::get)
+            }
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &[u8]) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Locked(map) => map.len(),
+            Self::Concurrent { shards, .. } => shards.iter().map(|shard| shard.len()).sum(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Slots allocated, used or not.
+    pub(crate) fn capacity(&self) -> usize {
+        match self {
+            Self::Locked(map) => map.capacity(),
+            Self::Concurrent { shards, .. } => shards.iter().map(|shard| shard.capacity()).sum(),
+        }
+    }
+
+    /// Every entry, in hash order.
+    pub(crate) fn iter(&self) -> RamIter<'_> {
+        match self {
+            Self::Locked(map) => RamIter::Locked(map.iter()),
+            Self::Concurrent { shards, .. } => RamIter::Concurrent { shards: shards.iter(), current: None },
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.iter().map(|(key, _)| key)
+    }
+}
+
+/// Iterator over a [`RamReadGuard`]'s entries.
+pub(crate) enum RamIter<'a> {
+    Locked(hashbrown::hash_map::Iter<'a, Vec<u8>, Vec<u8>>),
+    Concurrent {
+        shards: std::slice::Iter<'a, ShardReadGuard<'a>>,
+        current: Option<hashbrown::hash_map::Iter<'a, Vec<u8>, SharedValue<Vec<u8>>>>,
+    },
+}
+
+impl<'a> Iterator for RamIter<'a> {
+    type Item = (&'a Vec<u8>, &'a Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Locked(entries) => entries.next(),
+            Self::Concurrent { shards, current } => loop {
+                if let Some((key, value)) = current.as_mut().and_then(Iterator::next) {
+                    return Some((key, value.get()));
+                }
+                *current = Some(shards.next()?.iter());
+            },
+        }
+    }
+}
+
+/// Exclusive access to the map, from [`RamStore::write`].
+pub(crate) enum RamWriteGuard<'a> {
+    Locked(RwLockWriteGuard<'a, RamMap>),
+    Concurrent {
+        _gate: RwLockWriteGuard<'a, ()>,
+        map: &'a DashMap<Vec<u8>, Vec<u8>>,
+    },
+}
+
+impl RamWriteGuard<'_> {
+    /// Copy of `key`'s value.
+    pub(crate) fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            Self::Locked(map) => map.get(key).cloned(),
+            Self::Concurrent { map, .. } => map.get(key).map(|r| r.value().clone()),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &[u8]) -> bool {
+        match self {
+            Self::Locked(map) => map.contains_key(key),
+            Self::Concurrent { map, .. } => map.contains_key(key),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        match self {
+            Self::Locked(map) => {
+                map.insert(key, value);
+            }
+            Self::Concurrent { map, .. } => {
+                map.insert(key, value);
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &[u8]) {
+        match self {
+            Self::Locked(map) => {
+                map.remove(key);
+            }
+            Self::Concurrent { map, .. } => {
+                map.remove(key);
+            }
+        }
+    }
+
+    /// Drop every entry and take those of `entries` instead.
+    pub(crate) fn replace_all(&mut self, entries: RamMap) {
+        match self {
+            Self::Locked(map) => **map = entries,
+            Self::Concurrent { map, .. } => {
+                map.clear();
+                for (key, value) in entries {
+                    map.insert(key, value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RamMap {
+        (0..100u32).map(|i| (i.to_be_bytes().to_vec(), vec![i as u8])).collect()
+    }
+
+    #[test]
+    fn test_layouts_agree() {
+        for concurrent in [false, true] {
+            let store = RamStore::new(sample(), concurrent);
+            {
+                let mut data = store.write();
+                data.insert(b"new".to_vec(), b"v".to_vec());
+                data.remove(&7u32.to_be_bytes());
+                assert!(data.contains_key(b"new"));
+                assert_eq!(data.get(&8u32.to_be_bytes()), Some(vec![8]));
+            }
+            assert_eq!(store.get(b"new"), Some(b"v".to_vec()));
+            assert_eq!(store.get(&7u32.to_be_bytes()), None);
+
+            let data = store.read();
+            assert_eq!(data.len(), 100);
+            assert_eq!(data.iter().count(), 100);
+            assert!(data.capacity() >= data.len());
+            assert_eq!(data.get(&9u32.to_be_bytes()), Some(&vec![9]));
+            assert!(!data.contains_key(&7u32.to_be_bytes()));
+            drop(data);
+
+            store.write().replace_all(RamMap::new());
+            assert!(store.read().is_empty());
+        }
+    }
+}
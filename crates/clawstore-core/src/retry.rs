@@ -0,0 +1,90 @@
+//! Retrying operations that fail with transient errors
+//!
+//! Only errors for which [`ClawError::is_transient`] is true are retried;
+//! anything else is returned immediately.
+//!
+//! [`ClawError::is_transient`]: crate::ClawError::is_transient
+
+use std::thread;
+use std::time::Duration;
+
+use crate::error::ClawResult;
+
+/// Delay before the first retry; doubles after each further attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(1);
+
+/// Upper bound on the delay between attempts
+const MAX_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Run `f` up to `max_attempts` times (at least once), sleeping with
+/// exponential backoff between attempts while it fails transiently.
+///
+/// Returns the first success, the first non-transient error, or the last
+/// transient error once the attempts are used up.
+pub fn with_backoff<T>(f: impl Fn() -> ClawResult<T>, max_attempts: u8) -> ClawResult<T> {
+    let mut delay = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(e) if e.is_transient() && attempt < max_attempts => {
+                thread::sleep(delay);
+                delay = (delay * 2).min(MAX_BACKOFF);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClawError;
+    use std::cell::Cell;
+    use std::io;
+
+    fn io_error(kind: io::ErrorKind) -> ClawError {
+        io::Error::new(kind, "test").into()
+    }
+
+    #[test]
+    fn test_retries_transient_until_success() {
+        let calls = Cell::new(0);
+        let result = with_backoff(|| {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 { Err(io_error(io::ErrorKind::Interrupted)) } else { Ok(7) }
+        }, 5);
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let calls = Cell::new(0);
+        let result: ClawResult<()> = with_backoff(|| {
+            calls.set(calls.get() + 1);
+            Err(io_error(io::ErrorKind::TimedOut))
+        }, 4);
+        assert!(result.unwrap_err().is_transient());
+        assert_eq!(calls.get(), 4);
+
+        // Zero attempts still runs once
+        calls.set(0);
+        let _: ClawResult<()> = with_backoff(|| {
+            calls.set(calls.get() + 1);
+            Err(io_error(io::ErrorKind::TimedOut))
+        }, 0);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_permanent_error_not_retried() {
+        let calls = Cell::new(0);
+        let result: ClawResult<()> = with_backoff(|| {
+            calls.set(calls.get() + 1);
+            Err(ClawError::OversizedEntry { entry_size: 2, max_size: 1, component: "key".into() })
+        }, 5);
+        assert!(matches!(result, Err(ClawError::OversizedEntry { .. })));
+        assert_eq!(calls.get(), 1);
+    }
+}
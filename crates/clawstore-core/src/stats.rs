@@ -0,0 +1,148 @@
+//! Entry size statistics for diagnosing table layouts
+//!
+//! Computed on demand from the RAM hash table — nothing is tracked on the
+//! write path. `ClawStoreEngine::save_db_stats` persists the averages to
+//! `db.stats` so a later open can estimate its load size.
+
+/// Key and value size statistics for a set of entries.
+///
+/// All fields are zero for an empty set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntryStats {
+    /// Smallest key length in bytes
+    pub min_key_len: usize,
+    /// Largest key length in bytes
+    pub max_key_len: usize,
+    /// Mean key length in bytes
+    pub avg_key_len: f64,
+    /// Smallest value length in bytes
+    pub min_val_len: usize,
+    /// Largest value length in bytes
+    pub max_val_len: usize,
+    /// Mean value length in bytes
+    pub avg_val_len: f64,
+    /// Number of entries measured
+    pub entry_count: u64,
+}
+
+impl EntryStats {
+    /// Build statistics from `(key_len, value_len)` pairs in a single pass.
+    pub fn from_sizes<I: IntoIterator<Item = (usize, usize)>>(sizes: I) -> Self {
+        let mut stats = EntryStats {
+            min_key_len: usize::MAX,
+            min_val_len: usize::MAX,
+            ..Default::default()
+        };
+        let mut total_key: u64 = 0;
+        let mut total_val: u64 = 0;
+
+        for (key_len, val_len) in sizes {
+            stats.min_key_len = stats.min_key_len.min(key_len);
+            stats.max_key_len = stats.max_key_len.max(key_len);
+            stats.min_val_len = stats.min_val_len.min(val_len);
+            stats.max_val_len = stats.max_val_len.max(val_len);
+            total_key += key_len as u64;
+            total_val += val_len as u64;
+            stats.entry_count += 1;
+        }
+
+        if stats.entry_count == 0 {
+            return EntryStats::default();
+        }
+        stats.avg_key_len = total_key as f64 / stats.entry_count as f64;
+        stats.avg_val_len = total_val as f64 / stats.entry_count as f64;
+        stats
+    }
+}
+
+/// Estimated memory held by an engine, from
+/// `ClawStoreEngine::memory_usage_breakdown`.
+///
+/// Estimates, not allocator measurements: useful for attributing RSS, not
+/// for exact accounting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    /// Key + value bytes of every entry in RAM
+    pub hashmap_data_bytes: u64,
+    /// Unused hash table slots: `(capacity - len) * (2 * pointer size + 8)`
+    pub hashmap_overhead_bytes: u64,
+    /// Dirty keys plus their per-entry bookkeeping
+    pub dirty_tracker_bytes: u64,
+    /// Keys and positions in the per-key last-WAL-record index used by
+    /// `get_with_metadata`
+    pub wal_positions_bytes: u64,
+    /// Cached `get_at_wal_sequence` results and their access order
+    pub history_cache_bytes: u64,
+    /// Bytes buffered by the WAL writer. Always 0: `WalWriter` keeps no
+    /// buffer, every append is written straight to the file
+    pub wal_buffer_bytes: u64,
+    /// Sum of the fields above
+    pub total_estimate_bytes: u64,
+}
+
+/// File in the store directory written by `ClawStoreEngine::save_db_stats`
+pub(crate) const DB_STATS_FILE: &str = "db.stats";
+
+/// Average entry sizes stored in `db.stats`, as TOML `key = value` lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct DbStats {
+    pub(crate) entry_count: u64,
+    pub(crate) avg_key_len: f64,
+    pub(crate) avg_val_len: f64,
+}
+
+impl DbStats {
+    pub(crate) fn from_entry_stats(stats: &EntryStats) -> Self {
+        Self { entry_count: stats.entry_count, avg_key_len: stats.avg_key_len, avg_val_len: stats.avg_val_len }
+    }
+
+    pub(crate) fn to_toml(self) -> String {
+        format!(
+            "# Written by ClawStoreEngine::save_db_stats\nentry_count = {}\navg_key_len = {:.3}\navg_val_len = {:.3}\n",
+            self.entry_count, self.avg_key_len, self.avg_val_len
+        )
+    }
+
+    /// Parse `to_toml` output; `None` if any field is missing or malformed.
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let (mut entry_count, mut avg_key_len, mut avg_val_len) = (None, None, None);
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (name, value) = line.split_once('=')?;
+            match name.trim() {
+                "entry_count" => entry_count = Some(value.trim().parse().ok()?),
+                "avg_key_len" => avg_key_len = Some(value.trim().parse().ok()?),
+                "avg_val_len" => avg_val_len = Some(value.trim().parse().ok()?),
+                _ => {}
+            }
+        }
+        Some(Self { entry_count: entry_count?, avg_key_len: avg_key_len?, avg_val_len: avg_val_len? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_sizes() {
+        let stats = EntryStats::from_sizes([(4, 10), (8, 30), (6, 20)]);
+        assert_eq!(stats.entry_count, 3);
+        assert_eq!((stats.min_key_len, stats.max_key_len), (4, 8));
+        assert_eq!((stats.min_val_len, stats.max_val_len), (10, 30));
+        assert_eq!(stats.avg_key_len, 6.0);
+        assert_eq!(stats.avg_val_len, 20.0);
+    }
+
+    #[test]
+    fn test_db_stats_toml_roundtrip() {
+        let stats = DbStats { entry_count: 42, avg_key_len: 9.5, avg_val_len: 32.25 };
+        assert_eq!(DbStats::parse(&stats.to_toml()), Some(stats));
+        assert_eq!(DbStats::parse("entry_count = 1\navg_key_len = 2"), None);
+        assert_eq!(DbStats::parse("entry_count = x\navg_key_len = 2\navg_val_len = 3"), None);
+    }
+
+    #[test]
+    fn test_empty_is_zeroed() {
+        assert_eq!(EntryStats::from_sizes(std::iter::empty()), EntryStats::default());
+    }
+}
@@ -0,0 +1,247 @@
+//! Write subscriptions for change-data-capture
+//!
+//! [`ClawStoreEngine::subscribe_to_writes`] registers a channel that
+//! receives a [`WriteEvent`] for every key a successful write changes,
+//! batch writes included, once RAM has been updated. Dropping the returned
+//! [`SubscriptionHandle`] unregisters the channel; a channel whose receiver
+//! is gone is dropped on the next write.
+//!
+//! [`ClawStoreEngine::create_replica_stream`] builds on the same channel to
+//! feed a replica: a [`ReplicaStream`] yields a snapshot of the store, then
+//! every later write together with its value.
+//!
+//! [`ClawStoreEngine::subscribe_to_writes`]: crate::ClawStoreEngine::subscribe_to_writes
+//! [`ClawStoreEngine::create_replica_stream`]: crate::ClawStoreEngine::create_replica_stream
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::{Receiver, Sender};
+use parking_lot::Mutex;
+
+use crate::error::ClawResult;
+use crate::format::{deserialize_entry, serialize_entry, Operation, WalEntry};
+use crate::ram::RamStore;
+
+/// A completed write, delivered to every subscriber.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteEvent {
+    /// Key as stored in the engine
+    pub key: Vec<u8>,
+    /// `Put` or `Delete`
+    pub operation: Operation,
+    /// Wall-clock time the event was published, in nanoseconds since the epoch
+    pub timestamp_ns: u64,
+}
+
+/// Registered subscriber channels, keyed by subscription id.
+#[derive(Default)]
+pub(crate) struct WriteSubscribers {
+    next_id: AtomicU64,
+    senders: Mutex<Vec<(u64, Sender<WriteEvent>)>>,
+}
+
+impl WriteSubscribers {
+    /// Register `tx`; the handle unregisters it when dropped.
+    pub(crate) fn subscribe(self: &Arc<Self>, tx: Sender<WriteEvent>) -> SubscriptionHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.senders.lock().push((id, tx));
+        SubscriptionHandle { id, subscribers: Arc::downgrade(self) }
+    }
+
+    /// Send an event for `key` to every subscriber, dropping closed channels.
+    pub(crate) fn publish(&self, key: &[u8], operation: Operation) {
+        let mut senders = self.senders.lock();
+        if senders.is_empty() {
+            return;
+        }
+        let event = WriteEvent { key: key.to_vec(), operation, timestamp_ns: now_ns() };
+        senders.retain(|(_, tx)| tx.send(event.clone()).is_ok());
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.senders.lock().retain(|(sub_id, _)| *sub_id != id);
+    }
+}
+
+/// Keeps a write subscription alive; dropping it unsubscribes.
+///
+/// Holds only a weak reference to the engine's subscriber list, so it may
+/// outlive the engine.
+#[must_use = "dropping the handle unsubscribes immediately"]
+pub struct SubscriptionHandle {
+    id: u64,
+    subscribers: Weak<WriteSubscribers>,
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.unsubscribe(self.id);
+        }
+    }
+}
+
+/// Wall-clock time in nanoseconds since the epoch (0 if the clock is before it).
+fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// One item of a [`ReplicaStream`]. Sequence numbers start at 0 with the
+/// snapshot and increase by one per event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplicaEvent {
+    /// Every key-value pair in the store when the stream was created, sorted by key
+    Snapshot {
+        sequence: u64,
+        entries: Vec<(Vec<u8>, Vec<u8>)>,
+        timestamp_ns: u64,
+    },
+    /// A key changed: `Put` with its value, or `Delete` with an empty value
+    Write {
+        sequence: u64,
+        operation: Operation,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        timestamp_ns: u64,
+    },
+}
+
+impl ReplicaEvent {
+    /// Position of this event in its stream.
+    pub fn sequence(&self) -> u64 {
+        match self {
+            ReplicaEvent::Snapshot { sequence, .. } | ReplicaEvent::Write { sequence, .. } => *sequence,
+        }
+    }
+
+    /// WAL entries that reproduce this event, for
+    /// `ClawStoreEngine::apply_wal_entries_batch` on the replica.
+    ///
+    /// A snapshot becomes one `Put` per pair; it adds to the replica's
+    /// contents rather than replacing them, so start from an empty replica.
+    pub fn to_wal_entries(&self) -> ClawResult<Vec<WalEntry>> {
+        match self {
+            ReplicaEvent::Snapshot { entries, .. } => entries.iter()
+                .map(|(key, value)| deserialize_entry(&serialize_entry(key, value, Operation::Put)?))
+                .collect(),
+            ReplicaEvent::Write { operation, key, value, .. } => {
+                Ok(vec![deserialize_entry(&serialize_entry(key, value, *operation)?)?])
+            }
+        }
+    }
+}
+
+/// Blocking iterator of [`ReplicaEvent`]s, created by
+/// `ClawStoreEngine::create_replica_stream`.
+///
+/// The first item is the snapshot. Each later item is produced from a
+/// `WriteEvent` and carries the key's value as of when the item is
+/// yielded, so `operation` says whether the key exists then, not what the
+/// original write was. Applying the items in order therefore always
+/// converges on the source's state, even though intermediate values may be
+/// skipped. `next` blocks until a write arrives and returns `None` once the
+/// engine is dropped.
+///
+/// Writers never wait for the stream: pending events sit in an unbounded
+/// channel, one key-sized `WriteEvent` per write. A stream that is read
+/// more slowly than the store is written grows without limit, so drain it
+/// promptly or drop it.
+pub struct ReplicaStream {
+    data: Arc<RamStore>,
+    rx: Receiver<WriteEvent>,
+    snapshot: Option<Vec<(Vec<u8>, Vec<u8>)>>,
+    next_sequence: u64,
+    _subscription: SubscriptionHandle,
+}
+
+impl ReplicaStream {
+    /// Subscribe first, then copy RAM, so every write after the copy is
+    /// also delivered as an event.
+    pub(crate) fn new(
+        data: Arc<RamStore>,
+        subscribers: &Arc<WriteSubscribers>,
+    ) -> Self {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let subscription = subscribers.subscribe(tx);
+        let mut snapshot: Vec<(Vec<u8>, Vec<u8>)> = data.read()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        snapshot.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Self { data, rx, snapshot: Some(snapshot), next_sequence: 0, _subscription: subscription }
+    }
+}
+
+impl Iterator for ReplicaStream {
+    type Item = ClawResult<ReplicaEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sequence = self.next_sequence;
+        let event = match self.snapshot.take() {
+            Some(entries) => ReplicaEvent::Snapshot { sequence, entries, timestamp_ns: now_ns() },
+            None => {
+                let write = self.rx.recv().ok()?;
+                let (operation, value) = match self.data.get(&write.key) {
+                    Some(value) => (Operation::Put, value),
+                    None => (Operation::Delete, Vec::new()),
+                };
+                ReplicaEvent::Write {
+                    sequence,
+                    operation,
+                    key: write.key,
+                    value,
+                    timestamp_ns: write.timestamp_ns,
+                }
+            }
+        };
+        self.next_sequence += 1;
+        Some(Ok(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ClawStoreEngine, Config, Operation};
+    use crossbeam_channel::unbounded;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_two_subscribers_then_one_dropped() {
+        let dir = TempDir::new().unwrap();
+        let engine = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+        let (tx_a, rx_a) = unbounded();
+        let (tx_b, rx_b) = unbounded();
+        let handle_a = engine.subscribe_to_writes(tx_a);
+        let _handle_b = engine.subscribe_to_writes(tx_b);
+
+        engine.put(b"k1", b"v1").unwrap();
+        engine.put_fast(b"k2", b"v2").unwrap();
+        engine.delete(b"k1").unwrap();
+
+        for rx in [&rx_a, &rx_b] {
+            let events: Vec<_> = rx.try_iter().collect();
+            let ops: Vec<_> = events.iter().map(|e| (e.key.as_slice(), e.operation)).collect();
+            assert_eq!(ops, vec![
+                (&b"k1"[..], Operation::Put),
+                (&b"k2"[..], Operation::Put),
+                (&b"k1"[..], Operation::Delete),
+            ]);
+            assert!(events.windows(2).all(|w| w[0].timestamp_ns <= w[1].timestamp_ns));
+        }
+
+        // Unsubscribed: a stops receiving, b continues
+        drop(handle_a);
+        engine.put(b"k3", b"v3").unwrap();
+        assert!(rx_a.try_recv().is_err());
+        assert_eq!(rx_b.try_recv().unwrap().key, b"k3");
+
+        // A closed receiver is pruned on the next write without error
+        drop(rx_b);
+        engine.put(b"k4", b"v4").unwrap();
+    }
+}
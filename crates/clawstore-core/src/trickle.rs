@@ -22,40 +22,187 @@ use hashbrown::HashMap;
 use parking_lot::{Mutex, RwLock};
 
 use crate::config::Config;
-use crate::datafile::DataFileWriter;
+use crate::datafile::{latest_data_file, DataFileReader, DataFileWriter};
 use crate::error::{ClawError, ClawResult};
+use crate::ram::RamStore;
+
+/// How often the trickle engine flushes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrickleMode {
+    /// Flush on a constant interval
+    Fixed(Duration),
+    /// Shorten the interval as dirty keys pile up: `min_cadence` above
+    /// `2 * target_dirty_keys`, `max_cadence` below `target_dirty_keys / 2`,
+    /// linearly interpolated in between
+    Adaptive {
+        min_cadence: Duration,
+        max_cadence: Duration,
+        target_dirty_keys: usize,
+    },
+}
+
+impl TrickleMode {
+    /// Flush interval to use while `dirty_count` keys are waiting.
+    pub fn cadence_for(&self, dirty_count: usize) -> Duration {
+        match *self {
+            TrickleMode::Fixed(cadence) => cadence,
+            TrickleMode::Adaptive { min_cadence, max_cadence, target_dirty_keys } => {
+                let low = target_dirty_keys / 2;
+                let high = target_dirty_keys.saturating_mul(2);
+                if dirty_count > high {
+                    return min_cadence;
+                }
+                if dirty_count < low || high == low {
+                    return max_cadence;
+                }
+                // Linear from max_cadence at `low` down to min_cadence at `high`
+                let pressure = (dirty_count - low) as f64 / (high - low) as f64;
+                max_cadence - (max_cadence - min_cadence).mul_f64(pressure)
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match *self {
+            TrickleMode::Fixed(cadence) => {
+                if cadence.as_millis() == 0 {
+                    return Err("trickle cadence must be > 0".into());
+                }
+            }
+            TrickleMode::Adaptive { min_cadence, max_cadence, target_dirty_keys } => {
+                if min_cadence.as_millis() == 0 || min_cadence > max_cadence {
+                    return Err("adaptive trickle cadence must satisfy 0 < min_cadence <= max_cadence".into());
+                }
+                if target_dirty_keys == 0 {
+                    return Err("adaptive trickle target_dirty_keys must be > 0".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
 /// Tracks which keys are dirty (modified in RAM but not yet flushed to data files).
 pub struct DirtyTracker {
-    /// Set of keys that have been modified since last flush
-    dirty_keys: Mutex<HashSet<Vec<u8>>>,
+    /// Keys modified since last flush, with the write sequence that dirtied them
+    /// (0 for unsequenced writes)
+    dirty_keys: Mutex<HashMap<Vec<u8>, u64>>,
     /// Total number of entries flushed since engine start
     total_flushed: AtomicU64,
     /// Total number of trickle cycles completed
     total_cycles: AtomicU64,
+    /// Last entry sequence number stamped into a data file, shared by every
+    /// writer that flushes this tracker's keys
+    flush_sequence: Arc<AtomicU64>,
 }
 
 impl DirtyTracker {
     /// Create a new dirty tracker.
     pub fn new() -> Self {
         Self {
-            dirty_keys: Mutex::new(HashSet::new()),
+            dirty_keys: Mutex::new(HashMap::new()),
             total_flushed: AtomicU64::new(0),
             total_cycles: AtomicU64::new(0),
+            flush_sequence: Arc::new(AtomicU64::new(0)),
         }
     }
 
     /// Mark a key as dirty (called after RAM update in engine.put/delete).
     pub fn mark_dirty(&self, key: &[u8]) {
         let mut dirty = self.dirty_keys.lock();
-        dirty.insert(key.to_vec());
+        dirty.entry(key.to_vec()).or_insert(0);
+    }
+
+    /// Mark a key as dirty by the write with the given sequence number.
+    /// Keys are flushed in ascending sequence order.
+    pub fn mark_dirty_sequenced(&self, key: &[u8], sequence: u64) {
+        let mut dirty = self.dirty_keys.lock();
+        dirty.insert(key.to_vec(), sequence);
     }
 
     /// Take all dirty keys, leaving the set empty.
     /// Returns the set of keys to flush in this trickle cycle.
     pub fn take_dirty(&self) -> HashSet<Vec<u8>> {
         let mut dirty = self.dirty_keys.lock();
-        std::mem::take(&mut *dirty)
+        dirty.drain().map(|(key, _)| key).collect()
+    }
+
+    /// Take all dirty keys sorted by write sequence, leaving the set empty.
+    /// Unsequenced keys come first, in arbitrary order.
+    pub fn take_dirty_ordered(&self) -> Vec<Vec<u8>> {
+        let mut keys: Vec<(Vec<u8>, u64)> = {
+            let mut dirty = self.dirty_keys.lock();
+            dirty.drain().collect()
+        };
+        keys.sort_by_key(|(_, seq)| *seq);
+        keys.into_iter().map(|(key, _)| key).collect()
+    }
+
+    /// Take all dirty keys in ascending lexicographic order, leaving the set empty.
+    pub fn drain_sorted(&self) -> Vec<Vec<u8>> {
+        let mut keys: Vec<Vec<u8>> = self.take_dirty().into_iter().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Take all dirty keys grouped by first byte (table prefix), groups ascending.
+    ///
+    /// Within a group, unsequenced keys come first in ascending key order,
+    /// then keys dirtied by ordered puts in write-sequence order, so the
+    /// `put_durable_ordered` flush guarantee still holds. An empty key
+    /// falls in group 0.
+    pub fn drain_in_prefix_groups(&self) -> Vec<(u8, Vec<Vec<u8>>)> {
+        let keys: Vec<(Vec<u8>, u64)> = {
+            let mut dirty = self.dirty_keys.lock();
+            dirty.drain().collect()
+        };
+        group_by_prefix(keys)
+    }
+
+    /// Take the dirty keys of one partition, leaving the others dirty.
+    ///
+    /// Partition `partition_id` of `total_partitions` owns keys whose first
+    /// byte is in `[id * 256 / total, (id + 1) * 256 / total)`; an empty key
+    /// counts as first byte 0. Each trickle worker drains its own partition.
+    pub fn take_dirty_partition(&self, partition_id: u8, total_partitions: u8) -> HashSet<Vec<u8>> {
+        self.take_partition(partition_id, total_partitions)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// `drain_in_prefix_groups` restricted to one partition (see
+    /// `take_dirty_partition`).
+    fn drain_partition_in_prefix_groups(&self, partition_id: u8, total_partitions: u8) -> Vec<(u8, Vec<Vec<u8>>)> {
+        group_by_prefix(self.take_partition(partition_id, total_partitions))
+    }
+
+    /// Remove and return one partition's keys with their write sequences.
+    fn take_partition(&self, partition_id: u8, total_partitions: u8) -> Vec<(Vec<u8>, u64)> {
+        let total = total_partitions.max(1) as usize;
+        let low = partition_id as usize * 256 / total;
+        let high = (partition_id as usize + 1) * 256 / total;
+        let in_partition = |key: &[u8]| {
+            let first = key.first().copied().unwrap_or(0) as usize;
+            first >= low && first < high
+        };
+
+        let mut dirty = self.dirty_keys.lock();
+        let keys: Vec<Vec<u8>> = dirty.keys().filter(|k| in_partition(k)).cloned().collect();
+        keys.into_iter()
+            .filter_map(|key| dirty.remove_entry(&key))
+            .collect()
+    }
+
+    /// True if `key` is waiting to be flushed.
+    pub fn is_dirty(&self, key: &[u8]) -> bool {
+        self.dirty_keys.lock().contains_key(key)
+    }
+
+    /// Copy of the dirty keys, in no particular order. Unlike the `take_*`
+    /// and `drain_*` methods this leaves the set untouched.
+    pub fn dirty_keys(&self) -> Vec<Vec<u8>> {
+        self.dirty_keys.lock().keys().cloned().collect()
     }
 
     /// Number of keys currently dirty.
@@ -64,6 +211,13 @@ impl DirtyTracker {
         dirty.len()
     }
 
+    /// Approximate heap and table bytes held by the dirty set.
+    pub(crate) fn approx_bytes(&self) -> u64 {
+        let dirty = self.dirty_keys.lock();
+        let per_entry = std::mem::size_of::<(Vec<u8>, u64)>();
+        dirty.keys().map(|key| (key.len() + per_entry) as u64).sum()
+    }
+
     /// Total entries flushed since engine start.
     pub fn total_flushed(&self) -> u64 {
         self.total_flushed.load(Ordering::Relaxed)
@@ -74,6 +228,17 @@ impl DirtyTracker {
         self.total_cycles.load(Ordering::Relaxed)
     }
 
+    /// Counter for `DataFileWriter::set_sequence_source`.
+    pub(crate) fn flush_sequence(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.flush_sequence)
+    }
+
+    /// Make the next stamped sequence number exceed `sequence`, e.g. the
+    /// highest one already in the data files.
+    pub(crate) fn seed_flush_sequence(&self, sequence: u64) {
+        self.flush_sequence.fetch_max(sequence, Ordering::Relaxed);
+    }
+
     /// Record that a flush cycle completed.
     fn record_cycle(&self, flushed_count: u64) {
         self.total_flushed.fetch_add(flushed_count, Ordering::Relaxed);
@@ -85,40 +250,102 @@ impl Default for DirtyTracker {
     fn default() -> Self { Self::new() }
 }
 
-/// Handle to a running trickle engine background thread.
-/// Dropping this handle signals the thread to stop.
+/// Group `(key, sequence)` pairs by first byte (table prefix), groups ascending.
+///
+/// Within a group, unsequenced keys come first in ascending key order, then
+/// sequenced keys in write-sequence order. An empty key falls in group 0.
+fn group_by_prefix(mut keys: Vec<(Vec<u8>, u64)>) -> Vec<(u8, Vec<Vec<u8>>)> {
+    keys.sort_by(|(a, a_seq), (b, b_seq)| {
+        let a_prefix = a.first().copied().unwrap_or(0);
+        let b_prefix = b.first().copied().unwrap_or(0);
+        a_prefix.cmp(&b_prefix)
+            .then(a_seq.cmp(b_seq))
+            .then_with(|| a.cmp(b))
+    });
+
+    let mut groups: Vec<(u8, Vec<Vec<u8>>)> = Vec::new();
+    for (key, _) in keys {
+        let prefix = key.first().copied().unwrap_or(0);
+        match groups.last_mut() {
+            Some((p, group)) if *p == prefix => group.push(key),
+            _ => groups.push((prefix, vec![key])),
+        }
+    }
+    groups
+}
+
+/// Handle to the running trickle engine background threads.
+/// Dropping this handle signals the threads to stop.
 pub struct TrickleHandle {
-    /// Signal the background thread to stop
+    /// Signal the background threads to stop
     shutdown: Arc<AtomicBool>,
-    /// Background thread join handle
-    thread: Option<thread::JoinHandle<()>>,
+    /// One join handle per trickle worker
+    threads: Vec<thread::JoinHandle<()>>,
+    /// Flush interval chosen by the most recent worker cycle, in nanoseconds
+    current_cadence: Arc<AtomicU64>,
 }
 
 impl TrickleHandle {
-    /// Request graceful shutdown and wait for the background thread to finish.
+    /// Request graceful shutdown and wait for every worker to finish.
     pub fn shutdown(mut self) {
-        self.shutdown.store(true, Ordering::Release);
-        if let Some(handle) = self.thread.take() {
-            let _ = handle.join();
-        }
+        self.stop_and_join();
     }
 
-    /// Check if the trickle engine is still running.
+    /// Check if the trickle engine is still running (any worker alive).
     pub fn is_running(&self) -> bool {
-        self.thread.as_ref().map_or(false, |h| !h.is_finished())
+        self.threads.iter().any(|h| !h.is_finished())
     }
-}
 
-impl Drop for TrickleHandle {
-    fn drop(&mut self) {
+    /// Flush interval currently in effect (changes over time in adaptive mode).
+    pub fn current_cadence(&self) -> Duration {
+        Duration::from_nanos(self.current_cadence.load(Ordering::Relaxed))
+    }
+
+    fn stop_and_join(&mut self) {
         self.shutdown.store(true, Ordering::Release);
-        if let Some(handle) = self.thread.take() {
+        for handle in self.threads.drain(..) {
             let _ = handle.join();
         }
     }
 }
 
-/// Start the trickle engine background thread.
+impl Drop for TrickleHandle {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+/// RAM map the trickle engine reads dirty values from.
+pub trait TrickleSource: Send + Sync {
+    /// Current value of each key (`None` if deleted), all read under one lock.
+    fn read_values(&self, keys: Vec<Vec<u8>>) -> Vec<(Vec<u8>, Option<Vec<u8>>)>;
+}
+
+impl TrickleSource for RwLock<HashMap<Vec<u8>, Vec<u8>>> {
+    fn read_values(&self, keys: Vec<Vec<u8>>) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+        let data = self.read();
+        keys.into_iter()
+            .map(|key| {
+                let value = data.get(&key).cloned();
+                (key, value)
+            })
+            .collect()
+    }
+}
+
+impl TrickleSource for RamStore {
+    fn read_values(&self, keys: Vec<Vec<u8>>) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+        let data = self.read();
+        keys.into_iter()
+            .map(|key| {
+                let value = data.get(&key).cloned();
+                (key, value)
+            })
+            .collect()
+    }
+}
+
+/// Start the trickle engine background threads.
 ///
 /// The trickle engine periodically:
 /// 1. Takes the set of dirty keys from the tracker
@@ -126,119 +353,217 @@ impl Drop for TrickleHandle {
 /// 3. Writes the key-value pair to a data file with CRC32C + durable_sync
 /// 4. Records flush statistics
 ///
+/// With `config.trickle_workers > 1`, one thread per worker drains its own
+/// partition of keys (see `DirtyTracker::take_dirty_partition`) into its
+/// own data file. A key always maps to the same worker, so last-write-wins
+/// still holds within each file. Every worker cycle counts towards
+/// `DirtyTracker::total_cycles`.
+///
 /// # Arguments
 /// * `data_dir` - Directory for data files (engine_path/data/)
 /// * `data` - Shared reference to the RAM hash table
 /// * `tracker` - Shared dirty key tracker
-/// * `config` - Engine configuration (trickle_cadence controls flush interval)
-pub fn start_trickle(
+/// * `config` - Engine configuration (trickle_mode controls flush interval)
+pub fn start_trickle<S: TrickleSource + 'static>(
     data_dir: PathBuf,
-    data: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
+    data: Arc<S>,
     tracker: Arc<DirtyTracker>,
     config: Config,
 ) -> ClawResult<TrickleHandle> {
     let shutdown = Arc::new(AtomicBool::new(false));
-    let shutdown_clone = Arc::clone(&shutdown);
-    let cadence = config.trickle_cadence;
-
-    // Create initial data file writer
-    let data_dir_clone = data_dir.clone();
-
-    let thread = thread::Builder::new()
-        .name("clawstore-trickle".to_string())
-        .spawn(move || {
-            trickle_loop(data_dir_clone, data, tracker, cadence, shutdown_clone);
-        })
-        .map_err(|e| ClawError::Io {
-            path: Some(data_dir),
-            kind: std::io::ErrorKind::Other,
-            message: format!("Failed to spawn trickle thread: {}", e),
-        })?;
-
-    Ok(TrickleHandle {
-        shutdown,
-        thread: Some(thread),
-    })
+    let mode = config.trickle_mode;
+    let current_cadence = Arc::new(AtomicU64::new(cadence_nanos(mode.cadence_for(tracker.dirty_count()))));
+    let max_file_size = config.max_data_file_size_bytes;
+    let sector_aligned = config.sector_aligned_writes;
+    let verify_writes = config.verify_writes_after_flush;
+    let workers = config.trickle_workers.clamp(1, u8::MAX as usize) as u8;
+
+    // Continue the latest data file so restarts don't leave a trail of small
+    // files; further workers each start a new file. A file whose tail was
+    // torn by a crash is left alone and the first worker starts a new one.
+    let mut existing_file = match latest_data_file(&data_dir) {
+        Some(latest) if !DataFileReader::ends_cleanly(&latest)? => None,
+        latest => latest,
+    };
+
+    let mut handle = TrickleHandle {
+        shutdown: Arc::clone(&shutdown),
+        threads: Vec::new(),
+        current_cadence: Arc::clone(&current_cadence),
+    };
+    for worker in 0..workers {
+        let partition = (workers > 1).then_some((worker, workers));
+        let data_dir_clone = data_dir.clone();
+        let existing_file = existing_file.take();
+        let data = Arc::clone(&data);
+        let tracker = Arc::clone(&tracker);
+        let shutdown_clone = Arc::clone(&shutdown);
+        let cadence = Arc::clone(&current_cadence);
+        let name = if workers > 1 {
+            format!("clawstore-trickle-{}", worker)
+        } else {
+            "clawstore-trickle".to_string()
+        };
+
+        let thread = thread::Builder::new()
+            .name(name)
+            .spawn(move || {
+                trickle_loop(
+                    data_dir_clone, existing_file, max_file_size, sector_aligned,
+                    verify_writes, data, tracker, mode, cadence, shutdown_clone, partition,
+                );
+            })
+            .map_err(|e| ClawError::Io {
+                path: Some(data_dir.clone()),
+                kind: std::io::ErrorKind::Other,
+                message: format!("Failed to spawn trickle thread: {}", e),
+                inner: Some(Arc::new(e)),
+            })?;
+        handle.threads.push(thread);
+    }
+
+    Ok(handle)
 }
 
-/// Main trickle loop — runs on the background thread.
-fn trickle_loop(
+/// Main trickle loop — runs on one background thread per worker.
+///
+/// `partition` is `(worker, total_workers)` when several workers share the
+/// dirty set, `None` for a single worker that drains everything.
+#[allow(clippy::too_many_arguments)]
+fn trickle_loop<S: TrickleSource>(
     data_dir: PathBuf,
-    data: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
+    existing_file: Option<PathBuf>,
+    max_file_size: u64,
+    sector_aligned: bool,
+    verify_writes: bool,
+    data: Arc<S>,
     tracker: Arc<DirtyTracker>,
-    cadence: Duration,
+    mode: TrickleMode,
+    current_cadence: Arc<AtomicU64>,
     shutdown: Arc<AtomicBool>,
+    partition: Option<(u8, u8)>,
 ) {
     // Create data file writer — if this fails, log and exit
-    let mut writer = match DataFileWriter::new(&data_dir) {
+    let opened = match &existing_file {
+        Some(path) => DataFileWriter::open_existing(path, max_file_size),
+        None => DataFileWriter::new(&data_dir, max_file_size),
+    };
+    let mut writer = match opened {
         Ok(w) => w,
         Err(e) => {
             eprintln!("[TRICKLE] Failed to create data file writer: {}", e);
             return;
         }
     };
+    writer.set_sector_aligned(sector_aligned);
+    writer.set_sequence_source(tracker.flush_sequence());
 
     loop {
-        // Sleep for the configured cadence, checking shutdown periodically
-        let wake_time = Instant::now() + cadence;
-        while Instant::now() < wake_time {
+        // Sleep until the cadence has elapsed since the last cycle, checking
+        // shutdown periodically. The cadence is re-evaluated on every check,
+        // so in adaptive mode a burst of writes cuts the current wait short.
+        let cycle_start = Instant::now();
+        loop {
             if shutdown.load(Ordering::Acquire) {
                 // Final flush before shutdown
-                flush_dirty(&data, &tracker, &mut writer);
+                flush_dirty(&*data, &tracker, &mut writer, partition, verify_writes);
                 return;
             }
-            thread::sleep(Duration::from_millis(100));
+            let cadence = mode.cadence_for(tracker.dirty_count());
+            current_cadence.store(cadence_nanos(cadence), Ordering::Relaxed);
+            let elapsed = cycle_start.elapsed();
+            if elapsed >= cadence {
+                break;
+            }
+            thread::sleep((cadence - elapsed).min(Duration::from_millis(100)));
         }
 
         if shutdown.load(Ordering::Acquire) {
-            flush_dirty(&data, &tracker, &mut writer);
+            flush_dirty(&*data, &tracker, &mut writer, partition, verify_writes);
             return;
         }
 
         // Execute one trickle cycle
-        flush_dirty(&data, &tracker, &mut writer);
+        flush_dirty(&*data, &tracker, &mut writer, partition, verify_writes);
     }
 }
 
-/// Execute one flush cycle: take dirty keys, write to data files.
-fn flush_dirty(
-    data: &RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+fn cadence_nanos(cadence: Duration) -> u64 {
+    cadence.as_nanos().min(u64::MAX as u128) as u64
+}
+
+/// Execute one flush cycle: take dirty keys and write them to data files
+/// one table-prefix group at a time, in ascending key order within a group
+/// (ordered-put keys keep their write-sequence order). `partition` limits
+/// the cycle to one `(worker, total_workers)` share of the dirty keys.
+///
+/// With `verify_writes`, the cycle's entries are read back afterwards and
+/// every key of the cycle is re-marked dirty if any of them fails its
+/// checksum, so the next cycle writes them again.
+pub(crate) fn flush_dirty<S: TrickleSource + ?Sized>(
+    data: &S,
     tracker: &DirtyTracker,
     writer: &mut DataFileWriter,
+    partition: Option<(u8, u8)>,
+    verify_writes: bool,
 ) {
-    let dirty_keys = tracker.take_dirty();
-    if dirty_keys.is_empty() {
+    let groups = match partition {
+        Some((id, total)) => tracker.drain_partition_in_prefix_groups(id, total),
+        None => tracker.drain_in_prefix_groups(),
+    };
+    if groups.is_empty() {
         tracker.record_cycle(0);
         return;
     }
 
     let mut flushed = 0u64;
+    let mut flushed_keys = Vec::new();
+    if verify_writes {
+        writer.set_verify_window(groups.iter().map(|(_, keys)| keys.len()).sum());
+    }
 
-    // Read lock on HashMap — snapshot the values for dirty keys
-    // We hold the read lock briefly to copy values, then release it
-    let to_flush: Vec<(Vec<u8>, Option<Vec<u8>>)> = {
-        let data = data.read();
-        dirty_keys.into_iter()
-            .map(|key| {
-                let value = data.get(&key).cloned();
-                (key, value)
-            })
-            .collect()
-    };
-
-    // Write to data files (no lock held — this is the slow I/O part)
-    for (key, value) in to_flush {
-        let result = match value {
-            Some(val) => writer.write_entry(&key, &val),
-            None => writer.write_tombstone(&key), // key was deleted
-        };
+    for (_prefix, dirty_keys) in groups {
+        // Read lock on HashMap — snapshot the values for this group's keys
+        // We hold the read lock briefly to copy values, then release it
+        let to_flush = data.read_values(dirty_keys);
+
+        // Write to data files (no lock held — this is the slow I/O part)
+        for (key, value) in to_flush {
+            let result = match value {
+                Some(val) => writer.write_entry(&key, &val),
+                None => writer.write_tombstone(&key), // key was deleted
+            };
+
+            match result {
+                Ok(_) => {
+                    flushed += 1;
+                    if verify_writes {
+                        flushed_keys.push(key);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[TRICKLE] Failed to flush key ({} bytes): {}", key.len(), e);
+                    // Re-mark as dirty so it gets retried next cycle
+                    tracker.mark_dirty(&key);
+                }
+            }
+        }
+    }
 
-        match result {
-            Ok(_) => { flushed += 1; }
+    if verify_writes && flushed > 0 {
+        match writer.verify_last_n_entries(flushed as usize) {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!("[TRICKLE] Verification failed, re-flushing {} entries next cycle", flushed);
+                for key in &flushed_keys {
+                    tracker.mark_dirty(key);
+                }
+            }
             Err(e) => {
-                eprintln!("[TRICKLE] Failed to flush key ({} bytes): {}", key.len(), e);
-                // Re-mark as dirty so it gets retried next cycle
-                tracker.mark_dirty(&key);
+                eprintln!("[TRICKLE] Failed to verify flushed entries: {}", e);
+                for key in &flushed_keys {
+                    tracker.mark_dirty(key);
+                }
             }
         }
     }
@@ -253,6 +578,7 @@ fn flush_dirty(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::datafile::DEFAULT_MAX_DATA_FILE_SIZE;
     use tempfile::TempDir;
 
     #[test]
@@ -273,6 +599,49 @@ mod tests {
         assert_eq!(tracker.dirty_count(), 0); // cleared after take
     }
 
+    #[test]
+    fn test_drain_sorted_and_prefix_groups() {
+        let tracker = DirtyTracker::new();
+        for key in [&b"\x02zz"[..], b"\x01b", b"\x02a", b"\x01a", b"\x01ab"] {
+            tracker.mark_dirty(key);
+        }
+        let sorted = tracker.drain_sorted();
+        assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(sorted.len(), 5);
+        assert_eq!(tracker.dirty_count(), 0);
+
+        tracker.mark_dirty(b"\x02b");
+        tracker.mark_dirty(b"\x01z");
+        tracker.mark_dirty_sequenced(b"\x01c", 2);
+        tracker.mark_dirty_sequenced(b"\x01d", 1);
+        tracker.mark_dirty(b"\x02a");
+        let groups = tracker.drain_in_prefix_groups();
+        assert_eq!(groups, vec![
+            (1, vec![b"\x01z".to_vec(), b"\x01d".to_vec(), b"\x01c".to_vec()]),
+            (2, vec![b"\x02a".to_vec(), b"\x02b".to_vec()]),
+        ]);
+    }
+
+    #[test]
+    fn test_take_dirty_partition() {
+        let tracker = DirtyTracker::new();
+        for first in [0x00u8, 0x3f, 0x40, 0x7f, 0x80, 0xff] {
+            tracker.mark_dirty(&[first, 1]);
+        }
+        tracker.mark_dirty(b"");
+
+        let p0 = tracker.take_dirty_partition(0, 4);
+        assert_eq!(p0.len(), 3);
+        assert!(p0.contains(&b""[..]) && p0.contains(&[0x3f, 1][..]));
+        let p3 = tracker.take_dirty_partition(3, 4);
+        assert_eq!(p3, HashSet::from([vec![0xff, 1]]));
+        assert_eq!(tracker.dirty_count(), 3);
+
+        let rest: usize = (1..3).map(|i| tracker.take_dirty_partition(i, 4).len()).sum();
+        assert_eq!(rest, 3);
+        assert_eq!(tracker.dirty_count(), 0);
+    }
+
     #[test]
     fn test_dirty_tracker_stats() {
         let tracker = DirtyTracker::new();
@@ -306,8 +675,8 @@ mod tests {
         tracker.mark_dirty(b"k2");
 
         // Flush
-        let mut writer = DataFileWriter::new(&data_dir).unwrap();
-        flush_dirty(&data, &tracker, &mut writer);
+        let mut writer = DataFileWriter::new(&data_dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        flush_dirty(&*data, &tracker, &mut writer, None, false);
 
         assert_eq!(tracker.total_flushed(), 2);
         assert_eq!(tracker.total_cycles(), 1);
@@ -335,8 +704,8 @@ mod tests {
         // Mark a key dirty that doesn't exist in RAM (was deleted)
         tracker.mark_dirty(b"deleted_key");
 
-        let mut writer = DataFileWriter::new(&data_dir).unwrap();
-        flush_dirty(&data, &tracker, &mut writer);
+        let mut writer = DataFileWriter::new(&data_dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        flush_dirty(&*data, &tracker, &mut writer, None, false);
 
         assert_eq!(tracker.total_flushed(), 1); // tombstone written
         assert_eq!(tracker.total_cycles(), 1);
@@ -353,7 +722,7 @@ mod tests {
 
         // Use a fast cadence for testing
         let mut config = Config::default();
-        config.trickle_cadence = Duration::from_millis(50);
+        config.trickle_mode = TrickleMode::Fixed(Duration::from_millis(50));
 
         // Put some data and mark dirty
         {
@@ -382,6 +751,159 @@ mod tests {
         handle.shutdown();
     }
 
+    #[test]
+    fn test_trickle_skips_data_file_with_torn_tail() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        let mut writer = DataFileWriter::new(&data_dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        writer.write_entry(b"old", b"value").unwrap();
+        let torn_path = latest_data_file(&data_dir).unwrap();
+        drop(writer);
+        assert!(DataFileReader::ends_cleanly(&torn_path).unwrap());
+
+        // A crash mid-write leaves part of the next entry behind
+        let whole = std::fs::read(&torn_path).unwrap();
+        let mut torn = whole.clone();
+        torn.extend_from_slice(&whole[..whole.len() / 2]);
+        std::fs::write(&torn_path, &torn).unwrap();
+        assert!(!DataFileReader::ends_cleanly(&torn_path).unwrap());
+
+        let data = Arc::new(RwLock::new(HashMap::new()));
+        data.write().insert(b"new".to_vec(), b"value".to_vec());
+        let tracker = Arc::new(DirtyTracker::new());
+        tracker.mark_dirty(b"new");
+        let config = Config { trickle_mode: TrickleMode::Fixed(Duration::from_millis(10)), ..Config::default() };
+        let handle = start_trickle(data_dir.clone(), data, Arc::clone(&tracker), config).unwrap();
+        handle.shutdown();
+
+        // The torn file is untouched; the flush went to a new file
+        assert_eq!(std::fs::read(&torn_path).unwrap(), torn);
+        let latest = latest_data_file(&data_dir).unwrap();
+        assert_ne!(latest, torn_path);
+        let keys: Vec<Vec<u8>> = DataFileReader::scan_all(&latest).unwrap().into_iter().map(|e| e.key).collect();
+        assert_eq!(keys, vec![b"new".to_vec()]);
+    }
+
+    #[test]
+    fn test_adaptive_cadence_for() {
+        let mode = TrickleMode::Adaptive {
+            min_cadence: Duration::from_millis(100),
+            max_cadence: Duration::from_millis(900),
+            target_dirty_keys: 100,
+        };
+        assert_eq!(mode.cadence_for(0), Duration::from_millis(900));
+        assert_eq!(mode.cadence_for(49), Duration::from_millis(900));
+        assert_eq!(mode.cadence_for(50), Duration::from_millis(900));
+        assert_eq!(mode.cadence_for(125), Duration::from_millis(500));
+        assert_eq!(mode.cadence_for(200), Duration::from_millis(100));
+        assert_eq!(mode.cadence_for(10_000), Duration::from_millis(100));
+        let steps: Vec<_> = (50..=200).step_by(10).map(|n| mode.cadence_for(n)).collect();
+        assert!(steps.windows(2).all(|w| w[0] >= w[1]));
+
+        assert_eq!(TrickleMode::Fixed(Duration::from_secs(3)).cadence_for(10_000), Duration::from_secs(3));
+        assert!(TrickleMode::Adaptive {
+            min_cadence: Duration::from_secs(2),
+            max_cadence: Duration::from_secs(1),
+            target_dirty_keys: 10,
+        }.validate().is_err());
+    }
+
+    #[test]
+    fn test_adaptive_cadence_follows_dirty_keys() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let data = Arc::new(RwLock::new(HashMap::new()));
+        let tracker = Arc::new(DirtyTracker::new());
+        let min_cadence = Duration::from_millis(300);
+        let max_cadence = Duration::from_secs(1);
+        let config = Config {
+            trickle_mode: TrickleMode::Adaptive { min_cadence, max_cadence, target_dirty_keys: 10 },
+            ..Config::default()
+        };
+        let mark_dirty_batch = |tag: u8| {
+            let mut map = data.write();
+            for n in 0..100u8 {
+                map.insert(vec![tag, n], vec![n]);
+                tracker.mark_dirty(&[tag, n]);
+            }
+        };
+        let wait_for_cadence = |handle: &TrickleHandle, want: Duration| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while handle.current_cadence() != want && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(5));
+            }
+            assert_eq!(handle.current_cadence(), want);
+        };
+
+        // Idle: longest cadence
+        let handle = start_trickle(data_dir, Arc::clone(&data), Arc::clone(&tracker), config).unwrap();
+        wait_for_cadence(&handle, max_cadence);
+
+        // Write pressure shortens it, even in the middle of a wait
+        mark_dirty_batch(1);
+        wait_for_cadence(&handle, min_cadence);
+
+        // Once flushed, it relaxes back
+        wait_for_cadence(&handle, max_cadence);
+        assert_eq!(tracker.dirty_count(), 0);
+        assert_eq!(tracker.total_flushed(), 100);
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_trickle_parallel_workers_flush_everything() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let data = Arc::new(RwLock::new(HashMap::new()));
+        let tracker = Arc::new(DirtyTracker::new());
+
+        let config = Config {
+            trickle_mode: TrickleMode::Fixed(Duration::from_millis(50)),
+            trickle_workers: 4,
+            ..Config::default()
+        };
+
+        // Two keys for every possible first byte, so all partitions get work
+        let mut expected = HashSet::new();
+        {
+            let mut map = data.write();
+            for first in 0..=255u8 {
+                for n in 0..2u8 {
+                    let key = vec![first, n];
+                    map.insert(key.clone(), vec![first ^ n; 8]);
+                    tracker.mark_dirty(&key);
+                    expected.insert(key);
+                }
+            }
+        }
+
+        let handle = start_trickle(data_dir.clone(), Arc::clone(&data), Arc::clone(&tracker), config).unwrap();
+        assert!(handle.is_running());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while tracker.total_flushed() < expected.len() as u64 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        handle.shutdown();
+        assert_eq!(tracker.dirty_count(), 0);
+        assert_eq!(tracker.total_flushed(), expected.len() as u64);
+
+        // Each worker wrote its own file; together they hold every key once
+        let files = crate::compaction::list_data_files(&data_dir).unwrap();
+        assert!(files.len() >= 4, "expected one file per worker, got {}", files.len());
+        let mut flushed = HashSet::new();
+        for file in &files {
+            for entry in crate::datafile::DataFileReader::scan_all(file).unwrap() {
+                assert!(flushed.insert(entry.key), "key flushed twice");
+            }
+        }
+        assert_eq!(flushed, expected);
+    }
+
     #[test]
     fn test_trickle_no_dirty_noop() {
         let tmp = TempDir::new().unwrap();
@@ -391,8 +913,8 @@ mod tests {
         let tracker = DirtyTracker::new();
 
         // Flush with nothing dirty
-        let mut writer = DataFileWriter::new(&data_dir).unwrap();
-        flush_dirty(&data, &tracker, &mut writer);
+        let mut writer = DataFileWriter::new(&data_dir, DEFAULT_MAX_DATA_FILE_SIZE).unwrap();
+        flush_dirty(&*data, &tracker, &mut writer, None, false);
 
         assert_eq!(tracker.total_flushed(), 0);
         assert_eq!(tracker.total_cycles(), 1); // cycle counted even if nothing flushed
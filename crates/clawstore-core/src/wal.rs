@@ -9,16 +9,47 @@
 //! "RAM-first" means the READ path serves from RAM.
 //! The WRITE path is WAL-first. This is the fundamental durability contract.
 
-use crate::error::{ClawError, ClawResult};
-use crate::format::{serialize_entry, deserialize_entry, Operation, WalEntry, MAGIC_ARRAY, HEADER_SIZE};
+use crate::error::{ClawError, ClawResult, ClawResultExt};
+use crate::format::{serialize_batch_with_key, serialize_entry_with_key, serialize_entry_sequenced, deserialize_entry, deserialize_entries_with_key, BulkEntryBuffer, ChunkHeader, Operation, WalEntry, MAGIC_ARRAY, HEADER_SIZE, MAX_KEY_SIZE};
 use crate::platform_durability::durable_sync;
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// WAL file rotation threshold (100MB)
 const WAL_ROTATION_SIZE: u64 = 100 * 1024 * 1024;
 
+/// Pack a WAL file sequence (high 32 bits) and an offset within that file
+/// (low 32 bits) into one `u64`, as stored by `WalReader::recover_with_index`.
+/// `None` if either does not fit in 32 bits.
+pub fn pack_wal_position(file_sequence: u64, offset: u64) -> Option<u64> {
+    let file_sequence = u32::try_from(file_sequence).ok()?;
+    let offset = u32::try_from(offset).ok()?;
+    Some((u64::from(file_sequence) << 32) | u64::from(offset))
+}
+
+/// Key to packed position of its latest WAL record, from `WalReader::recover_with_index`.
+pub type WalIndex = HashMap<Vec<u8>, u64>;
+
+/// Split a `pack_wal_position` value into `(file_sequence, offset)`.
+pub fn unpack_wal_position(position: u64) -> (u64, u64) {
+    (position >> 32, position & u64::from(u32::MAX))
+}
+
+/// A point in the WAL stream: a file sequence and a byte offset within it.
+///
+/// Orders by sequence, then offset, so a larger position is newer. Taken
+/// from `WalWriter::position` and resumed from with `WalReader::seek_to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct WalPosition {
+    /// Sequence number of the WAL file
+    pub sequence: u64,
+    /// Byte offset within that file
+    pub offset: u64,
+}
+
 /// WAL writer handles appending entries and ensuring durability.
 ///
 /// CRITICAL INVARIANT: append_durable() must complete (including durable_sync)
@@ -34,44 +65,74 @@ pub struct WalWriter {
     wal_dir: PathBuf,
     /// Monotonic sequence number for WAL file naming
     sequence: u64,
+    /// Number of durable_sync calls issued (diagnostics)
+    sync_count: AtomicU64,
+    /// AES-256-GCM key; when set, every entry is written encrypted
+    encryption_key: Option<[u8; 32]>,
+    /// Set once a SEAL record is written; the next append starts a new file
+    sealed: bool,
+    /// Entries written to the current file since it was opened or rotated to
+    entries_in_file: u64,
+    /// Bytes handed to the file by single-entry appends, headers included
+    total_bytes_written: AtomicU64,
+    /// Logical key + value bytes of those same appends
+    total_payload_bytes: AtomicU64,
+    /// Where the most recent append started writing
+    last_record: WalPosition,
 }
 
 impl WalWriter {
     /// Create a new WAL writer in the specified directory.
-    /// If WAL files already exist, resumes from the highest sequence number.
+    /// If WAL files already exist, continues numbering after the highest
+    /// sequence number. A non-empty latest file is never appended to: it is
+    /// either sealed or ends at a crash point, and appending after a torn
+    /// write would hide the new entries from recovery.
     pub fn new<P: AsRef<Path>>(wal_dir: P) -> ClawResult<Self> {
         let wal_dir = wal_dir.as_ref().to_path_buf();
 
         // Ensure WAL directory exists
-        std::fs::create_dir_all(&wal_dir).map_err(|e| ClawError::Io {
-            path: Some(wal_dir.clone()),
-            kind: e.kind(),
-            message: format!("Failed to create WAL directory: {}", e),
-        })?;
+        std::fs::create_dir_all(&wal_dir)
+            .with_context("Failed to create WAL directory")
+            .with_path(&wal_dir)?;
 
         // Find the highest existing sequence number
-        let sequence = Self::find_max_sequence(&wal_dir)?;
-        let path = wal_dir.join(format!("wal-{:016x}.claw", sequence));
+        let mut sequence = Self::find_max_sequence(&wal_dir)?;
+        let mut path = wal_dir.join(format!("wal-{:016x}.claw", sequence));
+        if std::fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false) {
+            sequence += 1;
+            path = wal_dir.join(format!("wal-{:016x}.claw", sequence));
+        }
 
         let file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&path)
-            .map_err(|e| ClawError::Io {
-                path: Some(path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to open WAL file: {}", e),
-            })?;
+            .with_context("Failed to open WAL file").with_path(&path)?;
 
         let size = file.metadata()
-            .map_err(|e| ClawError::Io {
-                path: Some(path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to stat WAL file: {}", e),
-            })?
+            .with_context("Failed to stat WAL file").with_path(&path)?
             .len();
 
-        Ok(Self { file, path, size, wal_dir, sequence })
+        Ok(Self {
+            file,
+            path,
+            size,
+            wal_dir,
+            sequence,
+            sync_count: AtomicU64::new(0),
+            encryption_key: None,
+            sealed: false,
+            entries_in_file: 0,
+            total_bytes_written: AtomicU64::new(0),
+            total_payload_bytes: AtomicU64::new(0),
+            last_record: WalPosition { sequence, offset: size },
+        })
+    }
+
+    /// Encrypt all subsequently written entries with the given AES-256-GCM key.
+    pub fn with_encryption_key(mut self, encryption_key: Option<[u8; 32]>) -> Self {
+        self.encryption_key = encryption_key;
+        self
     }
 
     /// Find the highest WAL sequence number in the directory.
@@ -80,13 +141,8 @@ impl WalWriter {
 
         if let Ok(entries) = std::fs::read_dir(wal_dir) {
             for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.starts_with("wal-") && name.ends_with(".claw") {
-                        let hex = &name[4..name.len() - 5]; // strip "wal-" and ".claw"
-                        if let Ok(seq) = u64::from_str_radix(hex, 16) {
-                            max_seq = max_seq.max(seq);
-                        }
-                    }
+                if let Some(seq) = entry.file_name().to_str().and_then(parse_wal_sequence) {
+                    max_seq = max_seq.max(seq);
                 }
             }
         }
@@ -107,69 +163,137 @@ impl WalWriter {
     /// may or may not survive — this is acceptable for non-DURABLE tier.
     /// If crash occurs after step 3: data is on persistent media, will be
     /// recovered on next startup via WAL replay.
-    pub fn append_durable(&mut self, key: &[u8], value: &[u8], op: Operation) -> ClawResult<()> {
+    ///
+    /// Returns the offset of the entry in `current_path()`.
+    pub fn append_durable(&mut self, key: &[u8], value: &[u8], op: Operation) -> ClawResult<u64> {
         // Step 1: Serialize entry to buffer (includes CRC32C computation)
         // This happens in memory — no I/O, no failure modes except OversizedEntry
-        let entry_bytes = serialize_entry(key, value, op)?;
+        let entry_bytes = serialize_entry_with_key(key, value, op, self.encryption_key.as_ref())?;
 
         // Check if we need to rotate before writing
-        if self.size + entry_bytes.len() as u64 > WAL_ROTATION_SIZE {
+        if self.sealed || self.size + entry_bytes.len() as u64 > WAL_ROTATION_SIZE {
             self.rotate()?;
         }
+        self.last_record = self.position();
 
         // Step 2: Append serialized bytes to WAL file
         // After this, data is in the OS page cache (or disk write cache)
-        self.file.write_all(&entry_bytes).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()),
-            kind: e.kind(),
-            message: format!("WAL write failed: {}", e),
-        })?;
+        self.file.write_all(&entry_bytes).with_context("WAL write failed").with_path(&self.path)?;
 
         // Step 3: Ensure data reaches persistent storage
         // On Linux: fdatasync(), on macOS: F_FULLFSYNC, on Windows: FlushFileBuffers
         // This is the expensive operation (~100μs SSD, ~5ms HDD)
         // After this returns Ok, the entry WILL survive power loss
-        durable_sync(&self.file).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()),
-            kind: e.kind(),
-            message: format!("WAL durable_sync failed: {}", e),
-        })?;
+        durable_sync(&self.file).with_context("WAL durable_sync failed").with_path(&self.path)?;
+        self.sync_count.fetch_add(1, Ordering::Relaxed);
 
         // Update internal size tracker
+        let offset = self.size;
         self.size += entry_bytes.len() as u64;
+        self.entries_in_file += 1;
+        self.record_write(entry_bytes.len(), key.len() + value.len());
 
         // Step 4: Return Ok — caller may NOW safely update the RAM hash table
-        Ok(())
+        Ok(offset)
     }
 
     /// Append an entry WITHOUT calling durable_sync (DISK tier only).
     /// Data is written to the OS page cache but NOT guaranteed to survive power loss.
     /// Use this only for non-critical writes where speed matters more than durability.
     pub fn append_fast(&mut self, key: &[u8], value: &[u8], op: Operation) -> ClawResult<()> {
-        let entry_bytes = serialize_entry(key, value, op)?;
+        let entry_bytes = serialize_entry_with_key(key, value, op, self.encryption_key.as_ref())?;
 
-        if self.size + entry_bytes.len() as u64 > WAL_ROTATION_SIZE {
+        if self.sealed || self.size + entry_bytes.len() as u64 > WAL_ROTATION_SIZE {
             self.rotate()?;
         }
+        self.last_record = self.position();
 
-        self.file.write_all(&entry_bytes).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()),
-            kind: e.kind(),
-            message: format!("WAL write failed: {}", e),
-        })?;
+        self.file.write_all(&entry_bytes).with_context("WAL write failed").with_path(&self.path)?;
 
         self.size += entry_bytes.len() as u64;
+        self.entries_in_file += 1;
+        self.record_write(entry_bytes.len(), key.len() + value.len());
+        Ok(())
+    }
+
+    /// Append several entries as one `Batch` record with a single durable_sync.
+    ///
+    /// The entries share one header and one CRC, so recovery restores either
+    /// all of them or none. An oversized entry rejects the whole batch before
+    /// any byte reaches the file.
+    pub fn append_batch_durable(&mut self, entries: &[(&[u8], &[u8], Operation)]) -> ClawResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let batch_bytes = serialize_batch_with_key(entries, self.encryption_key.as_ref())?;
+        self.write_batch_durable(&batch_bytes, entries.len())
+    }
+
+    /// Append the entries of a `BulkEntryBuffer` with a single durable_sync.
+    ///
+    /// Unlike `append_batch_durable`, each entry stays a separate record, so
+    /// a crash mid-write can keep a prefix of the buffer. With encryption
+    /// enabled every entry is re-serialized under the key first.
+    pub fn append_buffer_durable(&mut self, buffer: &BulkEntryBuffer) -> ClawResult<()> {
+        let Some(enc_key) = self.encryption_key else {
+            return self.write_batch_durable(buffer.as_bytes(), buffer.len());
+        };
+        let mut batch_bytes = Vec::with_capacity(buffer.as_bytes().len() + buffer.len() * 16);
+        for index in 0..buffer.len() {
+            let entry = deserialize_entry(buffer.entry_bytes(index))?;
+            batch_bytes.extend_from_slice(&serialize_entry_with_key(
+                &entry.key, &entry.value, entry.operation, Some(&enc_key),
+            )?);
+        }
+        self.write_batch_durable(&batch_bytes, buffer.len())
+    }
+
+    /// Append `(key, value, sequence)` puts as one batch with a single durable_sync.
+    ///
+    /// Each entry records its sequence number so recovery can restore the
+    /// original write order.
+    pub fn append_sequenced_batch_durable(&mut self, entries: &[(&[u8], &[u8], u64)]) -> ClawResult<()> {
+        let mut batch_bytes = Vec::new();
+        for (key, value, seq) in entries {
+            batch_bytes.extend_from_slice(&serialize_entry_sequenced(
+                key, value, Operation::Put, Some(*seq), self.encryption_key.as_ref(),
+            )?);
+        }
+        self.write_batch_durable(&batch_bytes, entries.len())
+    }
+
+    /// Write `entry_count` pre-serialized entries in one write + one durable_sync.
+    fn write_batch_durable(&mut self, batch_bytes: &[u8], entry_count: usize) -> ClawResult<()> {
+        if batch_bytes.is_empty() {
+            return Ok(());
+        }
+
+        if self.sealed || self.size + batch_bytes.len() as u64 > WAL_ROTATION_SIZE {
+            self.rotate()?;
+        }
+        self.last_record = self.position();
+
+        self.file.write_all(batch_bytes)
+            .with_context("WAL batch write failed")
+            .with_path(&self.path)?;
+
+        durable_sync(&self.file)
+            .with_context("WAL batch durable_sync failed")
+            .with_path(&self.path)?;
+        self.sync_count.fetch_add(1, Ordering::Relaxed);
+
+        self.size += batch_bytes.len() as u64;
+        self.entries_in_file += entry_count as u64;
         Ok(())
     }
 
     /// Rotate to a new WAL file. Syncs current file before switching.
     fn rotate(&mut self) -> ClawResult<()> {
         // Sync current file to ensure all data is durable before moving on
-        durable_sync(&self.file).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()),
-            kind: e.kind(),
-            message: format!("WAL sync before rotation failed: {}", e),
-        })?;
+        durable_sync(&self.file)
+            .with_context("WAL sync before rotation failed")
+            .with_path(&self.path)?;
+        self.sync_count.fetch_add(1, Ordering::Relaxed);
 
         // Create new WAL file with incremented sequence
         self.sequence += 1;
@@ -179,16 +303,52 @@ impl WalWriter {
             .create(true)
             .append(true)
             .open(&new_path)
-            .map_err(|e| ClawError::Io {
-                path: Some(new_path.clone()),
-                kind: e.kind(),
-                message: format!("Failed to create rotated WAL file: {}", e),
-            })?;
+            .with_context("Failed to create rotated WAL file").with_path(&new_path)?;
 
         self.file = new_file;
         self.path = new_path;
         self.size = 0;
+        self.sealed = false;
+        self.entries_in_file = 0;
+
+        Ok(())
+    }
 
+    /// Seal the current file and switch to a new one immediately.
+    ///
+    /// Returns the path of the file just closed, which is complete from now
+    /// on (e.g. safe to copy for a backup). Rotating an already sealed file
+    /// adds no second SEAL.
+    pub fn rotate_now(&mut self) -> ClawResult<PathBuf> {
+        self.seal("rotate")?;
+        let closed = self.path.clone();
+        self.rotate()?;
+        Ok(closed)
+    }
+
+    /// Every WAL file in the directory except the current one, oldest first.
+    pub fn archived_paths(&self) -> ClawResult<Vec<PathBuf>> {
+        let mut paths = WalReader::new(&self.wal_dir).wal_file_paths()?;
+        paths.retain(|p| *p != self.path);
+        Ok(paths)
+    }
+
+    /// Write a SEAL record and sync, marking the current file as complete.
+    ///
+    /// Recovery stops reading a file at its SEAL record. Any later append
+    /// rotates to a fresh file first, so a SEAL is always the last record.
+    pub fn seal(&mut self, reason: &str) -> ClawResult<()> {
+        if self.sealed {
+            return Ok(());
+        }
+        let entry_bytes = serialize_entry_with_key(&[], reason.as_bytes(), Operation::Seal, self.encryption_key.as_ref())?;
+
+        self.file.write_all(&entry_bytes).with_context("WAL seal write failed").with_path(&self.path)?;
+        durable_sync(&self.file).with_context("WAL seal durable_sync failed").with_path(&self.path)?;
+        self.sync_count.fetch_add(1, Ordering::Relaxed);
+
+        self.size += entry_bytes.len() as u64;
+        self.sealed = true;
         Ok(())
     }
 
@@ -202,26 +362,124 @@ impl WalWriter {
         self.size
     }
 
+    /// Sequence number of the current WAL file (`wal-{seq:016x}.claw`).
+    pub fn current_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Position just past the last entry written, where the next one will go.
+    ///
+    /// Entries appended with `append_fast` may not be durable yet; `sync`
+    /// first if the position is handed to a replica as a checkpoint.
+    pub fn position(&self) -> WalPosition {
+        WalPosition { sequence: self.sequence, offset: self.size }
+    }
+
+    /// Where the most recent append started writing: its record, or the
+    /// first of its records for a multi-record write such as
+    /// `append_sequenced_batch_durable`. After a failed append it may point
+    /// at bytes that were never written.
+    pub fn last_record_position(&self) -> WalPosition {
+        self.last_record
+    }
+
+    /// Entries written to the current WAL file by this writer.
+    ///
+    /// Resets to zero on rotation. A batch counts as its entry count; SEAL
+    /// records are not counted.
+    pub fn entries_since_last_rotation(&self) -> u64 {
+        self.entries_in_file
+    }
+
     /// Sync the current WAL file to persistent storage without writing any entry.
     /// Call this after a batch of `append_fast` writes to make them all durable at once.
     pub fn sync(&self) -> ClawResult<()> {
-        durable_sync(&self.file).map_err(|e| ClawError::Io {
-            path: Some(self.path.clone()),
-            kind: e.kind(),
-            message: format!("WAL sync failed: {}", e),
-        })
+        durable_sync(&self.file).with_context("WAL sync failed").with_path(&self.path)?;
+        self.sync_count.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Number of durable_sync calls issued by this writer (diagnostics).
+    pub fn sync_count(&self) -> u64 {
+        self.sync_count.load(Ordering::Relaxed)
     }
+
+    /// Bytes written to the WAL per logical key + value byte.
+    ///
+    /// Covers `append_durable` and `append_fast`; batch records and SEAL
+    /// records are not counted. Returns 1.0 until some payload is written.
+    pub fn write_amplification(&self) -> f64 {
+        let payload = self.total_payload_bytes.load(Ordering::Relaxed);
+        if payload == 0 {
+            return 1.0;
+        }
+        self.total_bytes_written.load(Ordering::Relaxed) as f64 / payload as f64
+    }
+
+    /// Zero the counters behind `write_amplification`.
+    pub fn reset_stats(&self) {
+        self.total_bytes_written.store(0, Ordering::Relaxed);
+        self.total_payload_bytes.store(0, Ordering::Relaxed);
+    }
+
+    fn record_write(&self, written: usize, payload: usize) {
+        self.total_bytes_written.fetch_add(written as u64, Ordering::Relaxed);
+        self.total_payload_bytes.fetch_add(payload as u64, Ordering::Relaxed);
+    }
+}
+
+/// Metadata about a single WAL file on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalFileInfo {
+    /// Full path to the WAL file
+    pub path: PathBuf,
+    /// Sequence number parsed from the file name
+    pub sequence: u64,
+    /// File size in bytes
+    pub size_bytes: u64,
+    /// Upper-bound entry count (`size_bytes / HEADER_SIZE`), no parsing done
+    pub entry_count_estimate: usize,
+}
+
+/// How WAL recovery treats corrupted or torn entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalRecoveryMode {
+    /// Any corruption, including a torn tail, fails recovery with `WalCorrupted`
+    Strict,
+    /// Skip corrupted entries and stop at a torn tail, logging a warning
+    #[default]
+    Lenient,
+    /// Per file: `Strict` if it ends in a SEAL (clean shutdown), else `Lenient`
+    Auto,
 }
 
 /// WAL reader handles recovery by replaying entries from WAL files.
 pub struct WalReader {
     wal_dir: PathBuf,
+    encryption_key: Option<[u8; 32]>,
+    recovery_mode: WalRecoveryMode,
 }
 
 impl WalReader {
     /// Create a new WAL reader for the specified directory.
     pub fn new<P: AsRef<Path>>(wal_dir: P) -> Self {
-        Self { wal_dir: wal_dir.as_ref().to_path_buf() }
+        Self {
+            wal_dir: wal_dir.as_ref().to_path_buf(),
+            encryption_key: None,
+            recovery_mode: WalRecoveryMode::default(),
+        }
+    }
+
+    /// Decrypt encrypted entries with the given AES-256-GCM key during recovery.
+    pub fn with_encryption_key(mut self, encryption_key: Option<[u8; 32]>) -> Self {
+        self.encryption_key = encryption_key;
+        self
+    }
+
+    /// Choose how corruption is handled during recovery (default `Lenient`).
+    pub fn with_recovery_mode(mut self, recovery_mode: WalRecoveryMode) -> Self {
+        self.recovery_mode = recovery_mode;
+        self
     }
 
     /// Recover all entries from WAL files in sequence order.
@@ -233,63 +491,325 @@ impl WalReader {
     /// 4. Read payload, compute CRC32C, compare with header.checksum
     /// 5. On mismatch/corruption: find_next_magic() to resync
     /// 6. On torn write (incomplete entry at EOF): stop — this is the crash point
+    /// 7. On SEAL record: the file was closed cleanly — stop, nothing follows
+    ///
+    /// SEAL records are included in the result; use `WalEntry::is_seal` to skip them.
+    ///
+    /// Steps 5 and 6 apply in `Lenient` mode. In `Strict` mode (and `Auto`
+    /// for a sealed file) they fail recovery with `WalCorrupted` instead.
+    ///
+    /// An encrypted entry with no key configured aborts recovery with
+    /// `EncryptionKeyRequired`, and one that fails authentication under the
+    /// configured key with `DecryptionFailed`, instead of being skipped as
+    /// corruption. An
+    /// intact entry with an unknown `format_version` is skipped in every mode
+    /// and does not count as corruption.
     pub fn recover_entries(&self) -> ClawResult<Vec<WalEntry>> {
         let mut all_entries = Vec::new();
 
-        // Collect and sort WAL files by name (= by sequence number)
+        for wal_path in &self.wal_file_paths()? {
+            let entries = self.recover_from_file(wal_path)?;
+            all_entries.extend(entries);
+        }
+
+        Ok(all_entries)
+    }
+
+    /// `recover_entries`, plus an index from each key to the position of the
+    /// most recent record for it, packed with `pack_wal_position`.
+    ///
+    /// Deletes are indexed like puts, since the latest record is what a
+    /// point-in-time read must see. Entries of a batch share their batch
+    /// record's position. SEAL records are returned but not indexed. Fails
+    /// with `WalCorrupted` if a position does not fit the packed layout.
+    pub fn recover_with_index(&self) -> ClawResult<(Vec<WalEntry>, WalIndex)> {
+        let mut all_entries = Vec::new();
+        let mut index = HashMap::new();
+
+        for (position, entry) in self.recover_with_positions()? {
+            if !entry.is_seal() {
+                let WalPosition { sequence, offset } = position;
+                let packed = pack_wal_position(sequence, offset).ok_or_else(|| ClawError::WalCorrupted {
+                    path: self.wal_dir.join(format!("wal-{:016x}.claw", sequence)),
+                    offset,
+                    reason: format!("Position (file {}, offset {}) does not fit the WAL index", sequence, offset),
+                })?;
+                index.insert(entry.key.clone(), packed);
+            }
+            all_entries.push(entry);
+        }
+
+        Ok((all_entries, index))
+    }
+
+    /// `recover_entries`, pairing each entry with the position of the record
+    /// holding it. Entries of a batch share their batch record's position.
+    pub fn recover_with_positions(&self) -> ClawResult<Vec<(WalPosition, WalEntry)>> {
+        let mut all_entries = Vec::new();
+
+        for wal_path in &self.wal_file_paths()? {
+            let sequence = wal_path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(parse_wal_sequence)
+                .unwrap_or_default();
+            all_entries.extend(self.recover_from_file_with_offsets(wal_path)?
+                .into_iter()
+                .map(|(offset, entry)| (WalPosition { sequence, offset }, entry)));
+        }
+
+        Ok(all_entries)
+    }
+
+    /// Stream entries written at or after `position`: the rest of its file,
+    /// then every later WAL file in sequence order.
+    ///
+    /// Files are recovered one at a time as the iterator reaches them, with
+    /// this reader's recovery mode; a failure is yielded as an `Err` item.
+    /// SEAL records are skipped. Fails with a `NotFound` I/O error if the
+    /// file at `position.sequence` no longer exists.
+    pub fn seek_to(&self, position: WalPosition) -> ClawResult<impl Iterator<Item = ClawResult<WalEntry>> + '_> {
+        let files = self.sequenced_file_paths(|sequence| sequence >= position.sequence)?;
+        if files.first().map(|(sequence, _)| *sequence) != Some(position.sequence) {
+            let path = self.wal_dir.join(format!("wal-{:016x}.claw", position.sequence));
+            return Err(ClawError::Io {
+                path: Some(path),
+                kind: std::io::ErrorKind::NotFound,
+                message: format!("WAL file for position {:?} not found", position),
+                inner: None,
+            });
+        }
+
+        Ok(self.stream_files(files, position))
+    }
+
+    /// Stream entries of every WAL file up to and including sequence
+    /// `last_sequence`, oldest first; later files are never read.
+    ///
+    /// Same per-file recovery as `seek_to`: a failure is yielded as an
+    /// `Err` item and SEAL records are skipped.
+    pub fn read_through(&self, last_sequence: u64) -> ClawResult<impl Iterator<Item = ClawResult<WalEntry>> + '_> {
+        let files = self.sequenced_file_paths(|sequence| sequence <= last_sequence)?;
+        Ok(self.stream_files(files, WalPosition::default()))
+    }
+
+    /// WAL files whose sequence passes `keep`, with their sequences, in order.
+    fn sequenced_file_paths(&self, keep: impl Fn(u64) -> bool) -> ClawResult<Vec<(u64, PathBuf)>> {
+        Ok(self.wal_file_paths()?
+            .into_iter()
+            .filter_map(|path| {
+                let sequence = path.file_name().and_then(|n| n.to_str()).and_then(parse_wal_sequence)?;
+                keep(sequence).then_some((sequence, path))
+            })
+            .collect())
+    }
+
+    /// Entries of `files`, recovering each file only once the iterator
+    /// reaches it. Entries of file `start.sequence` before `start.offset`
+    /// are skipped.
+    fn stream_files(
+        &self,
+        files: Vec<(u64, PathBuf)>,
+        start: WalPosition,
+    ) -> impl Iterator<Item = ClawResult<WalEntry>> + '_ {
+        files.into_iter().flat_map(move |(sequence, path)| {
+            // Only the first file starts mid-way
+            let from = if sequence == start.sequence { start.offset } else { 0 };
+            let entries: Vec<ClawResult<WalEntry>> = match self.recover_from_file_with_offsets(&path) {
+                Ok(entries) => entries.into_iter()
+                    .filter(|(offset, entry)| *offset >= from && !entry.is_seal())
+                    .map(|(_, entry)| Ok(entry))
+                    .collect(),
+                Err(e) => vec![Err(e)],
+            };
+            entries
+        })
+    }
+
+    /// Every Put/Delete entry for `key`, oldest first, with the sequence
+    /// number of the WAL file holding it.
+    ///
+    /// A full recovery scan of every file, meant for auditing a key's
+    /// history while debugging, not for serving reads.
+    pub fn extract_entries_for_key(&self, key: &[u8]) -> ClawResult<Vec<(u64, WalEntry)>> {
+        self.extract_entries(|entry_key| entry_key == key)
+    }
+
+    /// `extract_entries_for_key` for every key starting with `prefix`.
+    pub fn extract_entries_for_prefix(&self, prefix: &[u8]) -> ClawResult<Vec<(u64, WalEntry)>> {
+        self.extract_entries(|entry_key| entry_key.starts_with(prefix))
+    }
+
+    fn extract_entries(&self, matches: impl Fn(&[u8]) -> bool) -> ClawResult<Vec<(u64, WalEntry)>> {
+        let mut found = Vec::new();
+        for wal_path in &self.wal_file_paths()? {
+            let sequence = wal_path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(parse_wal_sequence)
+                .unwrap_or_default();
+            found.extend(self.recover_from_file(wal_path)?
+                .into_iter()
+                .filter(|e| !e.is_seal() && matches(&e.key))
+                .map(|e| (sequence, e)));
+        }
+        Ok(found)
+    }
+
+    /// Collect WAL file paths sorted by name (= by sequence number).
+    fn wal_file_paths(&self) -> ClawResult<Vec<PathBuf>> {
         let mut wal_files: Vec<PathBuf> = Vec::new();
 
-        let dir_entries = std::fs::read_dir(&self.wal_dir).map_err(|e| ClawError::Io {
-            path: Some(self.wal_dir.clone()),
-            kind: e.kind(),
-            message: format!("Failed to read WAL directory: {}", e),
-        })?;
+        let dir_entries = std::fs::read_dir(&self.wal_dir)
+            .with_context("Failed to read WAL directory")
+            .with_path(&self.wal_dir)?;
 
         for entry in dir_entries {
-            let entry = entry.map_err(|e| ClawError::Io {
-                path: Some(self.wal_dir.clone()),
-                kind: e.kind(),
-                message: format!("Failed to read directory entry: {}", e),
-            })?;
+            let entry = entry
+                .with_context("Failed to read directory entry")
+                .with_path(&self.wal_dir)?;
             let path = entry.path();
             if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name.starts_with("wal-") && name.ends_with(".claw") {
+                if parse_wal_sequence(name).is_some() {
                     wal_files.push(path);
                 }
             }
         }
 
         wal_files.sort(); // lexicographic sort = sequence order (hex-padded)
+        Ok(wal_files)
+    }
 
-        for wal_path in &wal_files {
-            let entries = self.recover_from_file(wal_path)?;
-            all_entries.extend(entries);
+    /// Highest sequence number among the WAL files in the directory (0 if none).
+    pub fn max_sequence(&self) -> ClawResult<u64> {
+        Ok(self.wal_file_paths()?
+            .iter()
+            .filter_map(|path| path.file_name().and_then(|n| n.to_str()).and_then(parse_wal_sequence))
+            .max()
+            .unwrap_or(0))
+    }
+
+    /// List WAL files with size metadata, sorted by sequence number.
+    ///
+    /// Cheap: only stats each file, never parses entries. Useful for
+    /// disk usage estimates and operator dashboards.
+    pub fn list_files(&self) -> ClawResult<Vec<WalFileInfo>> {
+        let mut files = Vec::new();
+        for path in self.wal_file_paths()? {
+            let sequence = path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(parse_wal_sequence)
+                .unwrap_or_default();
+            let size_bytes = std::fs::metadata(&path)
+                .with_context("Failed to stat WAL file")
+                .with_path(&path)?
+                .len();
+            files.push(WalFileInfo {
+                path,
+                sequence,
+                size_bytes,
+                entry_count_estimate: (size_bytes / HEADER_SIZE as u64) as usize,
+            });
         }
+        files.sort_by_key(|f| f.sequence);
+        Ok(files)
+    }
 
-        Ok(all_entries)
+    /// Count entries in one WAL file without deserializing them.
+    ///
+    /// Checks only the magic bytes and length field of each header and skips
+    /// the payload, so no CRC is computed. Bad magic resyncs and a torn tail
+    /// stops the count, as in recovery. A `Batch` record counts as the entry
+    /// count in its header. A corrupt payload with a valid header still
+    /// counts, so this can exceed `recover_entries().len()`.
+    pub fn count_entries(path: &Path) -> ClawResult<usize> {
+        let mut buffer = Vec::new();
+        File::open(path)
+            .with_context("Failed to open WAL file for counting")
+            .with_path(path)?
+            .read_to_end(&mut buffer)
+            .with_context("Failed to read WAL file")
+            .with_path(path)?;
+
+        let mut count = 0;
+        let mut offset = 0;
+        while offset + HEADER_SIZE <= buffer.len() {
+            if buffer[offset..offset + 4] != MAGIC_ARRAY {
+                match find_next_magic(&buffer, offset + 1) {
+                    Some(next) => { offset = next; continue; }
+                    None => break,
+                }
+            }
+            let length = u32::from_le_bytes([
+                buffer[offset + 4], buffer[offset + 5],
+                buffer[offset + 6], buffer[offset + 7],
+            ]) as usize;
+            if offset + HEADER_SIZE + length > buffer.len() {
+                break; // torn write
+            }
+            let header_bytes: [u8; HEADER_SIZE] = buffer[offset..offset + HEADER_SIZE].try_into().unwrap();
+            let header = ChunkHeader::from_bytes(&header_bytes);
+            count += if header.is_batch() { header.batch_count() as usize } else { 1 };
+            if buffer[offset + 12] == Operation::Seal as u8 {
+                break; // nothing follows a SEAL
+            }
+            offset += HEADER_SIZE + length;
+        }
+
+        Ok(count)
+    }
+
+    /// O(1) entry count estimate: file size / (`HEADER_SIZE` + 50-byte average payload).
+    pub fn estimate_entry_count(path: &Path) -> ClawResult<usize> {
+        let size = std::fs::metadata(path)
+            .with_context("Failed to stat WAL file")
+            .with_path(path)?
+            .len();
+        Ok((size / (HEADER_SIZE as u64 + 50)) as usize)
+    }
+
+    /// Total size in bytes of all WAL files in the directory.
+    pub fn total_size_bytes(&self) -> ClawResult<u64> {
+        Ok(self.list_files()?.iter().map(|f| f.size_bytes).sum())
     }
 
     /// Recover entries from a single WAL file.
     fn recover_from_file(&self, path: &Path) -> ClawResult<Vec<WalEntry>> {
-        let mut file = File::open(path).map_err(|e| ClawError::Io {
-            path: Some(path.to_path_buf()),
-            kind: e.kind(),
-            message: format!("Failed to open WAL file for recovery: {}", e),
-        })?;
+        Ok(self.recover_from_file_with_offsets(path)?
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect())
+    }
+
+    /// `recover_from_file`, pairing each entry with the offset of the record
+    /// holding it.
+    fn recover_from_file_with_offsets(&self, path: &Path) -> ClawResult<Vec<(u64, WalEntry)>> {
+        let mut file = File::open(path)
+            .with_context("Failed to open WAL file for recovery")
+            .with_path(path)?;
 
         let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer).map_err(|e| ClawError::Io {
-            path: Some(path.to_path_buf()),
-            kind: e.kind(),
-            message: format!("Failed to read WAL file: {}", e),
-        })?;
+        file.read_to_end(&mut buffer).with_context("Failed to read WAL file").with_path(path)?;
+
+        let strict = self.recovery_mode == WalRecoveryMode::Strict;
+        // First corruption seen, reported if Auto mode finds a SEAL
+        let mut first_corruption: Option<(usize, String)> = None;
+        let corrupted = |offset: usize, reason: String| ClawError::WalCorrupted {
+            path: path.to_path_buf(),
+            offset: offset as u64,
+            reason,
+        };
 
         let mut entries = Vec::new();
         let mut offset = 0;
+        let mut sealed = false;
 
         while offset + HEADER_SIZE <= buffer.len() {
             // Step 1: Check magic bytes at current position
             if buffer[offset..offset + 4] != MAGIC_ARRAY {
+                let reason = "Bad magic".to_string();
+                if strict {
+                    return Err(corrupted(offset, reason));
+                }
+                first_corruption.get_or_insert((offset, reason));
                 // Not a valid entry start — try to resync
                 eprintln!("[WAL RECOVERY] Bad magic at offset {}, scanning for next entry", offset);
                 match find_next_magic(&buffer, offset + 1) {
@@ -308,22 +828,53 @@ impl WalReader {
 
             // Step 3: Check if full entry fits in remaining data
             if offset + total_entry_size > buffer.len() {
+                let reason = format!("Torn write: need {} bytes, have {}", total_entry_size, buffer.len() - offset);
+                if strict {
+                    return Err(corrupted(offset, reason));
+                }
                 // Torn write — entry started but didn't complete. This is the crash point.
-                eprintln!("[WAL RECOVERY] Torn write at offset {}: need {} bytes, have {}",
-                         offset, total_entry_size, buffer.len() - offset);
+                eprintln!("[WAL RECOVERY] {} at offset {}", reason, offset);
+                first_corruption.get_or_insert((offset, reason));
                 break; // stop recovery here — everything after is incomplete
             }
 
             // Step 4: Deserialize and verify CRC32C
             let entry_slice = &buffer[offset..offset + total_entry_size];
-            match deserialize_entry(entry_slice) {
-                Ok(entry) => {
-                    entries.push(entry);
+            match deserialize_entries_with_key(entry_slice, self.encryption_key.as_ref()) {
+                Ok(record) => {
+                    sealed = record.iter().any(WalEntry::is_seal);
+                    entries.extend(record.into_iter().map(|entry| (offset as u64, entry)));
+                    offset += total_entry_size;
+                    if sealed {
+                        break; // file is complete — no torn-write scan needed
+                    }
+                }
+                Err(ClawError::EncryptionKeyRequired { .. }) => {
+                    // Skipping would silently drop every entry — fail loudly instead
+                    return Err(ClawError::EncryptionKeyRequired {
+                        path: path.to_path_buf(),
+                        offset: offset as u64,
+                    });
+                }
+                Err(ClawError::DecryptionFailed { .. }) => {
+                    // Same for a wrong key: every entry would fail the same way
+                    return Err(ClawError::DecryptionFailed {
+                        path: path.to_path_buf(),
+                        offset: offset as u64,
+                    });
+                }
+                Err(ClawError::UnsupportedFormatVersion { version }) => {
+                    // Written by a newer build: the CRC checked out, so step over it whole
+                    eprintln!("[WAL RECOVERY] Skipping format version {} entry at offset {}", version, offset);
                     offset += total_entry_size;
                 }
                 Err(e) => {
+                    if strict {
+                        return Err(corrupted(offset, e.to_string()));
+                    }
                     // CRC mismatch or other corruption — skip and resync
                     eprintln!("[WAL RECOVERY] Corrupt entry at offset {}: {}", offset, e);
+                    first_corruption.get_or_insert((offset, e.to_string()));
                     match find_next_magic(&buffer, offset + 1) {
                         Some(next) => { offset = next; continue; }
                         None => break,
@@ -332,10 +883,257 @@ impl WalReader {
             }
         }
 
+        // A sealed file was closed cleanly, so corruption in it is not a crash artifact
+        if self.recovery_mode == WalRecoveryMode::Auto && sealed {
+            if let Some((offset, reason)) = first_corruption {
+                return Err(corrupted(offset, reason));
+            }
+        }
+
         Ok(entries)
     }
 }
 
+/// Kind of damage found by `WalRepairTool::scan_issues`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalIssueKind {
+    /// Bytes where an entry should start lack the magic; runs to the next magic
+    BadMagic,
+    /// Header intact but the payload CRC32C doesn't match
+    ChecksumMismatch,
+    /// CRC matches but the payload can't be parsed
+    Malformed,
+    /// Incomplete entry (or header fragment) at the end of the file
+    TornWrite,
+}
+
+/// One damaged region of a WAL file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalIssue {
+    /// WAL file containing the damage
+    pub path: PathBuf,
+    /// Byte offset where the damaged region starts
+    pub offset: u64,
+    pub kind: WalIssueKind,
+    /// Key of the damaged entry, if its plaintext fields were still readable
+    pub key: Option<Vec<u8>>,
+}
+
+/// Outcome of `WalRepairTool::repair_in_place`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Intact WAL records kept (a batch record counts once)
+    pub kept_entries: usize,
+    /// Damaged regions dropped, one per `WalIssue`
+    pub removed_entries: usize,
+    /// Bytes dropped across all rewritten files
+    pub removed_bytes: u64,
+    /// Files that had issues and were rewritten
+    pub files_rewritten: Vec<PathBuf>,
+}
+
+/// Offline WAL inspection and repair, for a WAL directory no engine has open.
+///
+/// Uses the same record validation as recovery. Encrypted entries are
+/// checked by CRC only, so no key is needed; entries with an unknown
+/// format version count as intact. Like recovery, nothing after a SEAL
+/// record is examined.
+pub struct WalRepairTool {
+    wal_dir: PathBuf,
+}
+
+/// Intact records and damaged regions of one WAL file.
+struct WalFileScan {
+    buffer: Vec<u8>,
+    /// Byte ranges of intact records, in file order, with their SEAL flag
+    records: Vec<(std::ops::Range<usize>, bool)>,
+    issues: Vec<WalIssue>,
+}
+
+impl WalRepairTool {
+    /// Tool for the WAL files in `wal_dir`.
+    pub fn new(wal_dir: &Path) -> Self {
+        Self { wal_dir: wal_dir.to_path_buf() }
+    }
+
+    /// Report every damaged region, file by file in sequence order.
+    pub fn scan_issues(&self) -> ClawResult<Vec<WalIssue>> {
+        let mut issues = Vec::new();
+        for path in WalReader::new(&self.wal_dir).wal_file_paths()? {
+            issues.extend(Self::scan_file(&path)?.issues);
+        }
+        Ok(issues)
+    }
+
+    /// Rewrite each damaged WAL file with only its intact records.
+    ///
+    /// Each file is rebuilt in a `.repair` temp file, synced and renamed
+    /// over the original, so a crash leaves either the old or the repaired
+    /// file. Files without issues are left untouched.
+    pub fn repair_in_place(&self) -> ClawResult<RepairReport> {
+        let mut report = RepairReport::default();
+        for path in WalReader::new(&self.wal_dir).wal_file_paths()? {
+            let scan = Self::scan_file(&path)?;
+            report.kept_entries += scan.records.len();
+            if scan.issues.is_empty() {
+                continue;
+            }
+            report.removed_entries += scan.issues.len();
+
+            let repaired = scan.valid_bytes(true);
+            report.removed_bytes += (scan.buffer.len() - repaired.len()) as u64;
+
+            let temp_path = path.with_extension("claw.repair");
+            write_synced(&temp_path, &repaired)?;
+            std::fs::rename(&temp_path, &path)
+                .with_context("Failed to replace WAL file with repaired copy")
+                .with_path(&path)?;
+            report.files_rewritten.push(path);
+        }
+        if !report.files_rewritten.is_empty() {
+            let dir = File::open(&self.wal_dir)
+                .with_context("Failed to open WAL directory for sync")
+                .with_path(&self.wal_dir)?;
+            durable_sync(&dir).with_context("WAL directory sync failed").with_path(&self.wal_dir)?;
+        }
+        Ok(report)
+    }
+
+    /// Write the intact records of every WAL file, in order, to a new file
+    /// at `dest`. Returns the number of records copied.
+    ///
+    /// SEAL records are left out so recovery of `dest` reads past the
+    /// original file boundaries. Name `dest` `wal-{seq:016x}.claw` to
+    /// replay it with `WalReader`.
+    pub fn export_valid_entries(&self, dest: &Path) -> ClawResult<usize> {
+        let mut out = Vec::new();
+        let mut copied = 0;
+        for path in WalReader::new(&self.wal_dir).wal_file_paths()? {
+            let scan = Self::scan_file(&path)?;
+            copied += scan.records.iter().filter(|(_, seal)| !seal).count();
+            out.extend_from_slice(&scan.valid_bytes(false));
+        }
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dest)
+            .with_context("Failed to create WAL export file")
+            .with_path(dest)?;
+        file.write_all(&out).with_context("WAL export write failed").with_path(dest)?;
+        durable_sync(&file).with_context("WAL export durable_sync failed").with_path(dest)?;
+        Ok(copied)
+    }
+
+    /// Walk one file with the recovery resync rules, recording intact
+    /// records and damaged regions instead of entries.
+    fn scan_file(path: &Path) -> ClawResult<WalFileScan> {
+        let mut buffer = Vec::new();
+        File::open(path)
+            .with_context("Failed to open WAL file for repair")
+            .with_path(path)?
+            .read_to_end(&mut buffer)
+            .with_context("Failed to read WAL file")
+            .with_path(path)?;
+
+        let mut records = Vec::new();
+        let mut issues = Vec::new();
+        let issue = |offset: usize, kind, key| WalIssue { path: path.to_path_buf(), offset: offset as u64, kind, key };
+        let mut offset = 0;
+
+        while offset < buffer.len() {
+            if offset + HEADER_SIZE > buffer.len() {
+                // Header fragment at the tail; recovery ignores it
+                issues.push(issue(offset, WalIssueKind::TornWrite, None));
+                break;
+            }
+            if buffer[offset..offset + 4] != MAGIC_ARRAY {
+                issues.push(issue(offset, WalIssueKind::BadMagic, None));
+                match find_next_magic(&buffer, offset + 1) {
+                    Some(next) => { offset = next; continue; }
+                    None => break,
+                }
+            }
+
+            let header_bytes: [u8; HEADER_SIZE] = buffer[offset..offset + HEADER_SIZE].try_into().unwrap();
+            let header = ChunkHeader::from_bytes(&header_bytes);
+            let end = offset + HEADER_SIZE + header.length as usize;
+            if end > buffer.len() {
+                let key = salvageable_key(&header, &buffer[offset + HEADER_SIZE..]);
+                issues.push(issue(offset, WalIssueKind::TornWrite, key));
+                break;
+            }
+
+            let kind = match deserialize_entries_with_key(&buffer[offset..end], None) {
+                Ok(_)
+                | Err(ClawError::EncryptionKeyRequired { .. })
+                | Err(ClawError::UnsupportedFormatVersion { .. }) => None,
+                Err(ClawError::ChecksumMismatch { .. }) => Some(WalIssueKind::ChecksumMismatch),
+                Err(_) => Some(WalIssueKind::Malformed),
+            };
+            match kind {
+                None => {
+                    let seal = header.entry_type == Operation::Seal as u8;
+                    records.push((offset..end, seal));
+                    offset = end;
+                    if seal {
+                        break;
+                    }
+                }
+                Some(kind) => {
+                    let key = salvageable_key(&header, &buffer[offset + HEADER_SIZE..end]);
+                    issues.push(issue(offset, kind, key));
+                    match find_next_magic(&buffer, offset + 1) {
+                        Some(next) => offset = next,
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        Ok(WalFileScan { buffer, records, issues })
+    }
+}
+
+impl WalFileScan {
+    /// Concatenated intact records, optionally including SEAL records.
+    fn valid_bytes(&self, include_seal: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (range, seal) in &self.records {
+            if include_seal || !seal {
+                out.extend_from_slice(&self.buffer[range.clone()]);
+            }
+        }
+        out
+    }
+}
+
+/// Key of a damaged single-entry record, if its plaintext payload still
+/// holds the key_len field and the whole key.
+fn salvageable_key(header: &ChunkHeader, payload: &[u8]) -> Option<Vec<u8>> {
+    if header.is_encrypted() || header.is_batch() {
+        return None;
+    }
+    let fields = if header.is_sequenced() { payload.get(8..)? } else { payload };
+    let key_len = u16::from_le_bytes([*fields.first()?, *fields.get(1)?]) as usize;
+    if key_len > MAX_KEY_SIZE {
+        return None;
+    }
+    fields.get(8..8 + key_len).map(<[u8]>::to_vec)
+}
+
+/// Create (or truncate) `path`, write `bytes` and durable_sync it.
+fn write_synced(path: &Path, bytes: &[u8]) -> ClawResult<()> {
+    let mut file = File::create(path).with_context("Failed to create WAL repair file").with_path(path)?;
+    file.write_all(bytes).with_context("WAL repair write failed").with_path(path)?;
+    durable_sync(&file).with_context("WAL repair durable_sync failed").with_path(path)
+}
+
+/// Parse the sequence number from a `wal-{seq:016x}.claw` file name.
+fn parse_wal_sequence(name: &str) -> Option<u64> {
+    let hex = name.strip_prefix("wal-")?.strip_suffix(".claw")?;
+    u64::from_str_radix(hex, 16).ok()
+}
+
 /// Scan forward in buffer to find next occurrence of CLAW magic bytes.
 /// Used for resynchronization after encountering corruption.
 fn find_next_magic(buffer: &[u8], start: usize) -> Option<usize> {
@@ -350,6 +1148,7 @@ fn find_next_magic(buffer: &[u8], start: usize) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::format::serialize_entry;
     use tempfile::TempDir;
 
     #[test]
@@ -375,6 +1174,129 @@ mod tests {
         assert_eq!(entries[2].operation, Operation::Delete);
     }
 
+    #[test]
+    fn test_sequence_and_entry_count_across_rotation() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        let first_seq = writer.current_sequence();
+        writer.append_durable(b"a", b"1", Operation::Put).unwrap();
+        writer.append_fast(b"b", b"2", Operation::Put).unwrap();
+        writer.append_batch_durable(&[(b"c", b"3", Operation::Put), (b"d", b"4", Operation::Put)]).unwrap();
+        assert_eq!(writer.entries_since_last_rotation(), 4);
+
+        // A sealed file rotates on the next append
+        writer.seal("test").unwrap();
+        assert_eq!(writer.entries_since_last_rotation(), 4);
+        writer.append_durable(b"e", b"5", Operation::Put).unwrap();
+        assert_eq!(writer.current_sequence(), first_seq + 1);
+        assert_eq!(writer.entries_since_last_rotation(), 1);
+
+        let reader = WalReader::new(temp.path());
+        assert_eq!(reader.max_sequence().unwrap(), writer.current_sequence());
+    }
+
+    #[test]
+    fn test_rotate_now_and_archived_paths() {
+        let temp = TempDir::new().unwrap();
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"k1", b"v1", Operation::Put).unwrap();
+        assert!(writer.archived_paths().unwrap().is_empty());
+
+        let closed = writer.rotate_now().unwrap();
+        assert!(std::fs::metadata(&closed).unwrap().len() > 0);
+        assert_ne!(writer.current_path(), closed);
+        assert_eq!(writer.archived_paths().unwrap(), vec![closed.clone()]);
+
+        writer.append_durable(b"k2", b"v2", Operation::Put).unwrap();
+        let second = writer.rotate_now().unwrap();
+        assert_eq!(writer.archived_paths().unwrap(), vec![closed, second]);
+
+        // The closed files are sealed: nothing appended to them is lost
+        let entries = WalReader::new(temp.path()).recover_entries().unwrap();
+        let keys: Vec<&[u8]> = entries.iter().filter(|e| !e.is_seal()).map(|e| e.key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"k1"[..], b"k2"]);
+    }
+
+    /// One WAL file holding `k0..k4`, with k1's value flipped, k3's magic
+    /// overwritten and a torn `torn` entry at the end. Returns its path.
+    fn write_damaged_wal(dir: &Path) -> PathBuf {
+        let mut writer = WalWriter::new(dir).unwrap();
+        for i in 0..5u8 {
+            writer.append_durable(&[b'k', b'0' + i], b"value", Operation::Put).unwrap();
+        }
+        let wal_path = writer.current_path().to_path_buf();
+        drop(writer);
+
+        let entry_size = serialize_entry(b"k0", b"value", Operation::Put).unwrap().len();
+        let mut data = std::fs::read(&wal_path).unwrap();
+        data[2 * entry_size - 1] ^= 0xFF;
+        data[3 * entry_size..3 * entry_size + 4].copy_from_slice(b"XXXX");
+        let torn = serialize_entry(b"torn", b"never finished", Operation::Put).unwrap();
+        data.extend_from_slice(&torn[..torn.len() - 5]);
+        std::fs::write(&wal_path, data).unwrap();
+        wal_path
+    }
+
+    #[test]
+    fn test_repair_tool_scan_and_repair_in_place() {
+        let temp = TempDir::new().unwrap();
+        let wal_path = write_damaged_wal(temp.path());
+        let entry_size = serialize_entry(b"k0", b"value", Operation::Put).unwrap().len() as u64;
+        let tool = WalRepairTool::new(temp.path());
+
+        let issues = tool.scan_issues().unwrap();
+        let found: Vec<_> = issues.iter().map(|i| (i.offset, i.kind, i.key.clone())).collect();
+        assert_eq!(found, vec![
+            (entry_size, WalIssueKind::ChecksumMismatch, Some(b"k1".to_vec())),
+            (3 * entry_size, WalIssueKind::BadMagic, None),
+            (5 * entry_size, WalIssueKind::TornWrite, Some(b"torn".to_vec())),
+        ]);
+        assert!(issues.iter().all(|i| i.path == wal_path));
+
+        let damaged_len = std::fs::metadata(&wal_path).unwrap().len();
+        let report = tool.repair_in_place().unwrap();
+        assert_eq!(report.kept_entries, 3);
+        assert_eq!(report.removed_entries, 3);
+        assert_eq!(report.files_rewritten, vec![wal_path.clone()]);
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 3 * entry_size);
+        assert_eq!(report.removed_bytes, damaged_len - 3 * entry_size);
+
+        // Repaired file is clean, even for strict recovery
+        assert!(tool.scan_issues().unwrap().is_empty());
+        let entries = WalReader::new(temp.path()).with_recovery_mode(WalRecoveryMode::Strict).recover_entries().unwrap();
+        let keys: Vec<&[u8]> = entries.iter().map(|e| e.key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"k0"[..], b"k2", b"k4"]);
+        assert_eq!(tool.repair_in_place().unwrap().files_rewritten.len(), 0);
+    }
+
+    #[test]
+    fn test_repair_tool_export_valid_entries() {
+        let temp = TempDir::new().unwrap();
+        let wal_dir = temp.path().join("wal");
+        // A sealed first file, then the damaged one
+        let mut writer = WalWriter::new(&wal_dir).unwrap();
+        writer.append_durable(b"sealed", b"value", Operation::Put).unwrap();
+        writer.seal("test").unwrap();
+        drop(writer);
+        write_damaged_wal(&wal_dir);
+
+        let export_dir = temp.path().join("export");
+        std::fs::create_dir_all(&export_dir).unwrap();
+        let dest = export_dir.join("wal-0000000000000001.claw");
+        let tool = WalRepairTool::new(&wal_dir);
+        assert_eq!(tool.export_valid_entries(&dest).unwrap(), 4);
+        // Refuses to overwrite
+        assert!(tool.export_valid_entries(&dest).is_err());
+
+        // The SEAL between the source files is dropped, so recovery reads everything
+        let entries = WalReader::new(&export_dir).with_recovery_mode(WalRecoveryMode::Strict).recover_entries().unwrap();
+        let keys: Vec<&[u8]> = entries.iter().map(|e| e.key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"sealed"[..], b"k0", b"k2", b"k4"]);
+        // Source files untouched
+        assert_eq!(tool.scan_issues().unwrap().len(), 3);
+    }
+
     #[test]
     fn test_corruption_recovery_skips_bad_entry() {
         let temp = TempDir::new().unwrap();
@@ -403,6 +1325,66 @@ mod tests {
         assert_eq!(entries[0].key, b"good1");
     }
 
+    #[test]
+    fn test_recovery_modes_on_corrupt_middle_entry() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"good1", b"val1", Operation::Put).unwrap();
+        writer.append_durable(b"good2", b"val2", Operation::Put).unwrap();
+        writer.append_durable(b"good3", b"val3", Operation::Put).unwrap();
+        let wal_path = writer.current_path().to_path_buf();
+        let entry_size = serialize_entry(b"good1", b"val1", Operation::Put).unwrap().len();
+        drop(writer);
+
+        // Flip the last value byte of the middle entry
+        let mut data = std::fs::read(&wal_path).unwrap();
+        data[2 * entry_size - 1] ^= 0xFF;
+        std::fs::write(&wal_path, &data).unwrap();
+
+        let recover = |mode| WalReader::new(temp.path()).with_recovery_mode(mode).recover_entries();
+
+        assert!(matches!(recover(WalRecoveryMode::Strict), Err(ClawError::WalCorrupted { .. })));
+        let keys: Vec<Vec<u8>> = recover(WalRecoveryMode::Lenient).unwrap().into_iter().map(|e| e.key).collect();
+        assert_eq!(keys, vec![b"good1".to_vec(), b"good3".to_vec()]);
+
+        // Unsealed (crash) file: Auto is lenient
+        assert_eq!(recover(WalRecoveryMode::Auto).unwrap().len(), 2);
+
+        // Sealed (clean shutdown) file: Auto is strict
+        let seal = serialize_entry(&[], b"graceful shutdown", Operation::Seal).unwrap();
+        data.extend_from_slice(&seal);
+        std::fs::write(&wal_path, &data).unwrap();
+        assert!(matches!(recover(WalRecoveryMode::Auto), Err(ClawError::WalCorrupted { .. })));
+        assert_eq!(recover(WalRecoveryMode::Lenient).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_format_version_skipped_not_corrupt() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"v1_a", b"old", Operation::Put).unwrap();
+        let wal_path = writer.current_path().to_path_buf();
+        drop(writer);
+
+        // A hypothetical version 2 entry from a newer build, then more v1
+        let mut v2 = serialize_entry(b"v2_key", b"new layout", Operation::Put).unwrap();
+        v2[15] = 2;
+        let mut data = std::fs::read(&wal_path).unwrap();
+        data.extend_from_slice(&v2);
+        data.extend_from_slice(&serialize_entry(b"v1_b", b"old", Operation::Put).unwrap());
+        data.extend_from_slice(&serialize_entry(&[], b"graceful shutdown", Operation::Seal).unwrap());
+        std::fs::write(&wal_path, &data).unwrap();
+
+        // Sealed file: Strict and Auto would both fail on real corruption
+        for mode in [WalRecoveryMode::Strict, WalRecoveryMode::Auto, WalRecoveryMode::Lenient] {
+            let entries = WalReader::new(temp.path()).with_recovery_mode(mode).recover_entries().unwrap();
+            let keys: Vec<&[u8]> = entries.iter().filter(|e| !e.is_seal()).map(|e| e.key.as_slice()).collect();
+            assert_eq!(keys, vec![&b"v1_a"[..], &b"v1_b"[..]]);
+        }
+    }
+
     #[test]
     fn test_torn_write_stops_cleanly() {
         let temp = TempDir::new().unwrap();
@@ -426,6 +1408,304 @@ mod tests {
         assert_eq!(entries[0].key, b"complete");
     }
 
+    #[test]
+    fn test_append_batch_single_sync() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        let batch: Vec<(&[u8], &[u8], Operation)> = vec![
+            (b"a", b"1", Operation::Put),
+            (b"b", b"2", Operation::Put),
+            (b"a", b"", Operation::Delete),
+        ];
+        writer.append_batch_durable(&batch).unwrap();
+        assert_eq!(writer.sync_count(), 1);
+        drop(writer);
+
+        let entries = WalReader::new(temp.path()).recover_entries().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].key, b"b");
+        assert_eq!(entries[2].operation, Operation::Delete);
+    }
+
+    #[test]
+    fn test_extract_entries_for_key_and_prefix() {
+        let temp = TempDir::new().unwrap();
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        for i in 0..5u8 {
+            writer.append_durable(b"acct:1", &[i], Operation::Put).unwrap();
+            writer.append_durable(b"acct:2", &[i], Operation::Put).unwrap();
+            if i == 2 {
+                writer.rotate_now().unwrap();
+            }
+        }
+        writer.append_durable(b"other", b"x", Operation::Put).unwrap();
+        writer.append_durable(b"acct:2", b"", Operation::Delete).unwrap();
+        let first_seq = writer.current_sequence() - 1;
+        drop(writer);
+
+        let reader = WalReader::new(temp.path());
+        let history = reader.extract_entries_for_key(b"acct:1").unwrap();
+        let values: Vec<u8> = history.iter().map(|(_, e)| e.value[0]).collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+        let files: Vec<u64> = history.iter().map(|(seq, _)| *seq).collect();
+        assert_eq!(files, vec![first_seq, first_seq, first_seq, first_seq + 1, first_seq + 1]);
+
+        let accounts = reader.extract_entries_for_prefix(b"acct:").unwrap();
+        assert_eq!(accounts.len(), 11);
+        assert_eq!(accounts.last().unwrap().1.operation, Operation::Delete);
+    }
+
+    #[test]
+    fn test_recover_with_index_points_at_latest_entry() {
+        let temp = TempDir::new().unwrap();
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"k", b"v1", Operation::Put).unwrap();
+        writer.append_durable(b"other", b"x", Operation::Put).unwrap();
+        writer.append_durable(b"k", b"v2", Operation::Put).unwrap();
+        writer.rotate_now().unwrap();
+        let third = writer.append_durable(b"k", b"v3", Operation::Put).unwrap();
+        let sequence = writer.current_sequence();
+        drop(writer);
+
+        let reader = WalReader::new(temp.path());
+        let (entries, index) = reader.recover_with_index().unwrap();
+        assert_eq!(entries.len(), reader.recover_entries().unwrap().len());
+        assert_eq!(index.len(), 2);
+        assert_eq!(unpack_wal_position(index[&b"k".to_vec()]), (sequence, third));
+        assert_eq!(unpack_wal_position(index[&b"other".to_vec()]).0, sequence - 1);
+
+        assert_eq!(pack_wal_position(1 << 32, 0), None);
+        assert_eq!(pack_wal_position(7, u64::from(u32::MAX)).map(unpack_wal_position), Some((7, u64::from(u32::MAX))));
+    }
+
+    #[test]
+    fn test_seek_to_streams_from_position() {
+        let temp = TempDir::new().unwrap();
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        let mut checkpoint = None;
+        for i in 0..100u32 {
+            if i == 50 {
+                checkpoint = Some(writer.position());
+            }
+            if i == 75 {
+                writer.rotate_now().unwrap();
+            }
+            writer.append_durable(&i.to_be_bytes(), b"v", Operation::Put).unwrap();
+        }
+        let checkpoint = checkpoint.unwrap();
+        assert!(writer.position() > checkpoint);
+        let missing = WalPosition { sequence: writer.current_sequence() + 1, offset: 0 };
+        drop(writer);
+
+        let reader = WalReader::new(temp.path());
+        let keys: Vec<Vec<u8>> = reader.seek_to(checkpoint).unwrap()
+            .map(|entry| entry.unwrap().key)
+            .collect();
+        let expected: Vec<Vec<u8>> = (50..100u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        assert_eq!(keys, expected);
+
+        let from_start = WalPosition { sequence: checkpoint.sequence, offset: 0 };
+        assert_eq!(reader.seek_to(from_start).unwrap().count(), 100);
+        assert!(matches!(reader.seek_to(missing), Err(ClawError::Io { kind: std::io::ErrorKind::NotFound, .. })));
+
+        // read_through stops after the given file: entries 0..75 precede the rotation
+        assert_eq!(reader.read_through(checkpoint.sequence).unwrap().count(), 75);
+        assert_eq!(reader.read_through(missing.sequence).unwrap().count(), 100);
+    }
+
+    #[test]
+    fn test_recovery_after_simulated_sync_failure() {
+        use crate::platform_durability::{clear_fault_injector, set_fault_injector, SimulatedDisk};
+
+        let temp = TempDir::new().unwrap();
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        set_fault_injector(SimulatedDisk::fail_after(2));
+        writer.append_durable(b"k1", b"v1", Operation::Put).unwrap();
+        writer.append_durable(b"k2", b"v2", Operation::Put).unwrap();
+        let acknowledged = writer.current_size();
+        assert!(writer.append_durable(b"k3", b"v3", Operation::Put).is_err());
+        clear_fault_injector();
+        let wal_path = writer.current_path().to_path_buf();
+        drop(writer);
+
+        // Power loss: only part of the unsynced third entry reached the disk
+        let file = OpenOptions::new().write(true).open(&wal_path).unwrap();
+        file.set_len(acknowledged + 10).unwrap();
+
+        let entries = WalReader::new(temp.path()).recover_entries().unwrap();
+        let keys: Vec<&[u8]> = entries.iter().filter(|e| !e.is_seal()).map(|e| e.key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"k1"[..], b"k2"]);
+    }
+
+    #[test]
+    fn test_write_amplification_matches_entry_layout() {
+        let temp = TempDir::new().unwrap();
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        assert_eq!(writer.write_amplification(), 1.0);
+
+        // 8-byte key + 8-byte value behind a header and the 8 bytes of
+        // key_len / value_len / op / pad fields.
+        for i in 0..100u64 {
+            let key = i.to_be_bytes();
+            if i % 2 == 0 {
+                writer.append_durable(&key, &[0xAB; 8], Operation::Put).unwrap();
+            } else {
+                writer.append_fast(&key, &[0xCD; 8], Operation::Put).unwrap();
+            }
+        }
+        let expected = (HEADER_SIZE + 8 + 16) as f64 / 16.0;
+        assert!((writer.write_amplification() - expected).abs() < 1e-9);
+
+        writer.reset_stats();
+        assert_eq!(writer.write_amplification(), 1.0);
+    }
+
+    #[test]
+    fn test_append_buffer_single_sync_plain_and_encrypted() {
+        let mut buffer = BulkEntryBuffer::new(256);
+        buffer.append_entry(b"a", b"1", Operation::Put).unwrap();
+        buffer.append_entry(b"b", b"2", Operation::Put).unwrap();
+        buffer.append_entry(b"a", b"", Operation::Delete).unwrap();
+
+        for enc_key in [None, Some([7u8; 32])] {
+            let temp = TempDir::new().unwrap();
+            let mut writer = WalWriter::new(temp.path()).unwrap().with_encryption_key(enc_key);
+            writer.append_buffer_durable(&buffer).unwrap();
+            assert_eq!(writer.sync_count(), 1);
+            assert_eq!(writer.entries_since_last_rotation(), 3);
+            drop(writer);
+
+            let entries = WalReader::new(temp.path()).with_encryption_key(enc_key).recover_entries().unwrap();
+            let ops: Vec<_> = entries.iter().map(|e| (e.key.as_slice(), e.operation)).collect();
+            assert_eq!(ops, vec![
+                (&b"a"[..], Operation::Put),
+                (&b"b"[..], Operation::Put),
+                (&b"a"[..], Operation::Delete),
+            ]);
+            assert_eq!(entries.iter().any(|e| e.header.is_encrypted()), enc_key.is_some());
+        }
+    }
+
+    #[test]
+    fn test_corrupt_batch_dropped_whole() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"before", b"1", Operation::Put).unwrap();
+        let batch: Vec<(&[u8], &[u8], Operation)> = vec![
+            (b"x", b"10", Operation::Put),
+            (b"y", b"20", Operation::Put),
+        ];
+        writer.append_batch_durable(&batch).unwrap();
+        let path = writer.current_path().to_path_buf();
+        drop(writer);
+        assert_eq!(WalReader::count_entries(&path).unwrap(), 3);
+
+        // Flip the last byte of the batch payload (the final value byte)
+        let mut bytes = std::fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(&path, bytes).unwrap();
+
+        let entries = WalReader::new(temp.path()).recover_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, b"before");
+    }
+
+    #[test]
+    fn test_list_files_in_sequence_order() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"a", b"1", Operation::Put).unwrap();
+        writer.rotate().unwrap();
+        writer.append_durable(b"b", b"22", Operation::Put).unwrap();
+        writer.append_durable(b"c", b"333", Operation::Put).unwrap();
+        writer.rotate().unwrap();
+        writer.append_durable(b"d", b"4", Operation::Put).unwrap();
+        drop(writer);
+
+        let reader = WalReader::new(temp.path());
+        let files = reader.list_files().unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files.iter().map(|f| f.sequence).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        for f in &files {
+            assert_eq!(f.size_bytes, std::fs::metadata(&f.path).unwrap().len());
+            assert_eq!(f.entry_count_estimate, f.size_bytes as usize / HEADER_SIZE);
+        }
+        let one_entry = serialize_entry(b"a", b"1", Operation::Put).unwrap().len() as u64;
+        assert_eq!(files[0].size_bytes, one_entry);
+
+        let total: u64 = files.iter().map(|f| f.size_bytes).sum();
+        assert_eq!(reader.total_size_bytes().unwrap(), total);
+    }
+
+    #[test]
+    fn test_encrypted_wal_requires_key() {
+        let temp = TempDir::new().unwrap();
+        let enc_key = [42u8; 32];
+        let mut writer = WalWriter::new(temp.path()).unwrap().with_encryption_key(Some(enc_key));
+        writer.append_durable(b"k1", b"v1", Operation::Put).unwrap();
+        writer.append_durable(b"k2", b"v2", Operation::Put).unwrap();
+        drop(writer);
+
+        let entries = WalReader::new(temp.path())
+            .with_encryption_key(Some(enc_key))
+            .recover_entries()
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].value, b"v2");
+
+        let result = WalReader::new(temp.path()).recover_entries();
+        assert!(matches!(result, Err(ClawError::EncryptionKeyRequired { .. })));
+    }
+
+    #[test]
+    fn test_sealed_wal_fully_recovered() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        writer.append_durable(b"k1", b"v1", Operation::Put).unwrap();
+        writer.append_fast(b"last", b"before-seal", Operation::Put).unwrap();
+        writer.seal("graceful shutdown").unwrap();
+        let sealed_path = writer.current_path().to_path_buf();
+
+        // Appending after a seal starts a new file
+        writer.append_durable(b"k2", b"v2", Operation::Put).unwrap();
+        assert_ne!(writer.current_path(), sealed_path);
+        drop(writer);
+
+        let entries = WalReader::new(temp.path()).recover_entries().unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1].key, b"last");
+        assert_eq!(entries[1].value, b"before-seal");
+        assert!(entries[2].is_seal());
+        assert_eq!(entries[2].value, b"graceful shutdown");
+        assert_eq!(entries[3].key, b"k2");
+    }
+
+    #[test]
+    fn test_count_entries_matches_recovery() {
+        let temp = TempDir::new().unwrap();
+
+        let mut writer = WalWriter::new(temp.path()).unwrap();
+        for i in 0..100u32 {
+            writer.append_fast(&i.to_le_bytes(), &vec![7u8; i as usize], Operation::Put).unwrap();
+        }
+        writer.append_durable(b"gone", b"", Operation::Delete).unwrap();
+        writer.seal("test").unwrap();
+        let path = writer.current_path().to_path_buf();
+        drop(writer);
+
+        let recovered = WalReader::new(temp.path()).recover_entries().unwrap().len();
+        assert_eq!(WalReader::count_entries(&path).unwrap(), recovered);
+        assert_eq!(recovered, 102);
+
+        let size = std::fs::metadata(&path).unwrap().len() as usize;
+        assert_eq!(WalReader::estimate_entry_count(&path).unwrap(), size / (HEADER_SIZE + 50));
+    }
+
     #[test]
     fn test_empty_wal_directory() {
         let temp = TempDir::new().unwrap();
@@ -444,3 +1724,83 @@ mod tests {
         assert!(name.ends_with(".claw"));
     }
 }
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::format::{deserialize_entry, serialize_entry, MAX_KEY_SIZE};
+    use proptest::prelude::*;
+    use tempfile::TempDir;
+
+    /// Values stop at 4KB to keep 1000 cases fast; the MAX_VALUE_SIZE
+    /// boundary itself is covered by the unit tests in format.rs.
+    const MAX_TEST_VALUE: usize = 4096;
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![Just(Operation::Put), Just(Operation::Delete), Just(Operation::Seal)]
+    }
+
+    fn entry() -> impl Strategy<Value = (Vec<u8>, Vec<u8>, Operation)> {
+        (
+            prop::collection::vec(any::<u8>(), 0..=MAX_KEY_SIZE),
+            prop::collection::vec(any::<u8>(), 0..=MAX_TEST_VALUE),
+            operation(),
+        )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        #[test]
+        fn prop_serialize_roundtrip((key, value, op) in entry()) {
+            let bytes = serialize_entry(&key, &value, op).unwrap();
+            let decoded = deserialize_entry(&bytes).unwrap();
+            prop_assert_eq!(decoded.key, key);
+            prop_assert_eq!(decoded.value, value);
+            prop_assert_eq!(decoded.operation, op);
+        }
+
+        /// Flips in the magic, length, checksum or payload bytes must be
+        /// rejected. Header bytes 12..32 (type, flags, padding) are outside
+        /// the CRC and are not part of this property.
+        #[test]
+        fn prop_byte_flip_detected(
+            (key, value, op) in entry(),
+            index in any::<prop::sample::Index>(),
+            mask in 1u8..=255,
+        ) {
+            let mut bytes = serialize_entry(&key, &value, op).unwrap();
+            let protected: Vec<usize> = (0..12).chain(HEADER_SIZE..bytes.len()).collect();
+            let pos = protected[index.index(protected.len())];
+            bytes[pos] ^= mask;
+            prop_assert!(deserialize_entry(&bytes).is_err());
+        }
+
+        #[test]
+        fn prop_writer_reader_preserve_order(
+            entries in prop::collection::vec(
+                (
+                    prop::collection::vec(any::<u8>(), 0..=MAX_KEY_SIZE),
+                    prop::collection::vec(any::<u8>(), 0..=256),
+                    prop_oneof![Just(Operation::Put), Just(Operation::Delete)],
+                ),
+                1..50,
+            ),
+        ) {
+            let temp = TempDir::new().unwrap();
+            let mut writer = WalWriter::new(temp.path()).unwrap();
+            for (key, value, op) in &entries {
+                writer.append_fast(key, value, *op).unwrap();
+            }
+            writer.sync().unwrap();
+            drop(writer);
+
+            let recovered = WalReader::new(temp.path()).recover_entries().unwrap();
+            let recovered: Vec<(Vec<u8>, Vec<u8>, Operation)> = recovered
+                .into_iter()
+                .map(|e| (e.key, e.value, e.operation))
+                .collect();
+            prop_assert_eq!(recovered, entries);
+        }
+    }
+}
@@ -6,6 +6,9 @@
 //!
 //! This is the trade-off: cursor creation is O(n) where n = entries in table,
 //! but individual operations (seek, next, prev) are O(log n) via BTreeMap.
+//!
+//! DupSort cursors store each duplicate under `[encoded_key][subkey]`, so the
+//! snapshot orders duplicates by subkey within each key.
 
 use std::collections::BTreeMap;
 use std::marker::PhantomData;
@@ -23,7 +26,8 @@ use reth_db_api::{
 };
 
 use clawstore_core::ClawStoreEngine;
-use crate::table_ids::table_id_for_name;
+use crate::table_ids::{dup_subkey_len, table_id_for_name};
+use crate::tx::UndoLog;
 
 // ---------------------------------------------------------------------------
 // Helper: snapshot table data from engine into a BTreeMap
@@ -33,10 +37,20 @@ use crate::table_ids::table_id_for_name;
 ///
 /// Scans the engine's HashMap for keys with the table's prefix byte,
 /// strips the prefix, and collects into a BTreeMap<Vec<u8>, Vec<u8>>.
-fn snapshot_table(engine: &ClawStoreEngine, table_id: u8) -> BTreeMap<Vec<u8>, Vec<u8>> {
+pub(crate) fn snapshot_table(engine: &ClawStoreEngine, table_id: u8) -> BTreeMap<Vec<u8>, Vec<u8>> {
     engine.prefix_scan(&[table_id]).into_iter().collect()
 }
 
+/// How table keys map onto engine keys.
+#[derive(Debug, Clone, Copy)]
+enum KeyLayout {
+    /// One engine key per table key
+    Flat,
+    /// DupSort: engine key is `[encoded_key][subkey]`, where the subkey is the
+    /// first `subkey_len` bytes of the compressed value (whole value if None)
+    Dup { subkey_len: Option<usize> },
+}
+
 // ---------------------------------------------------------------------------
 // Read-only cursor
 // ---------------------------------------------------------------------------
@@ -46,30 +60,262 @@ fn snapshot_table(engine: &ClawStoreEngine, table_id: u8) -> BTreeMap<Vec<u8>, V
 /// Currently a stub that satisfies the trait bounds. Full cursor iteration
 /// requires adding prefix_scan to ClawStoreEngine (next development phase).
 pub struct ClawCursor<T: Table> {
-    /// Sorted snapshot of table data (encoded key bytes -> compressed value bytes)
-    data: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// Sorted snapshot of table data (encoded key bytes -> compressed value bytes),
+    /// shared with a read pool's warm snapshot until this cursor writes to it
+    data: Arc<BTreeMap<Vec<u8>, Vec<u8>>>,
     /// Current position in the sorted data (encoded key bytes)
     position: Option<Vec<u8>>,
+    /// Flat or DupSort key layout
+    layout: KeyLayout,
+    /// Set by `delete_current`: the position already holds the entry after
+    /// the deleted one, so the next `next()` returns it without advancing
+    after_delete: bool,
+    /// Last decoded table key and the stored key bytes it came from; reused
+    /// while the cursor stays on those bytes
+    last_decoded: Option<(Vec<u8>, T::Key)>,
+    /// Table keys decoded so far (diagnostics)
+    key_decodes: u64,
     _phantom: PhantomData<T>,
 }
 
 impl<T: Table> ClawCursor<T> {
     pub(crate) fn new(engine: Arc<ClawStoreEngine>) -> Self {
+        Self::with_layout(engine, KeyLayout::Flat)
+    }
+
+    /// Cursor over a DupSort table using `[encoded_key][subkey]` engine keys.
+    pub(crate) fn new_dup(engine: Arc<ClawStoreEngine>) -> Self {
+        Self::with_layout(engine, KeyLayout::Dup { subkey_len: dup_subkey_len(T::NAME) })
+    }
+
+    /// Cursor over this table's entries in a whole-store snapshot taken
+    /// earlier (keys carry the table prefix byte). Later engine writes are
+    /// never visible.
+    pub(crate) fn from_store_snapshot(snapshot: &BTreeMap<Vec<u8>, Vec<u8>>) -> Self {
         let table_id = table_id_for_name(T::NAME);
-        let data = snapshot_table(&engine, table_id);
+        let data = snapshot
+            .range(vec![table_id]..)
+            .take_while(|(k, _)| k.first() == Some(&table_id))
+            .map(|(k, v)| (k[1..].to_vec(), v.clone()))
+            .collect();
+        Self::from_data(Arc::new(data), KeyLayout::Flat)
+    }
+
+    /// Cursor over an already-built table snapshot (keys without the prefix
+    /// byte), e.g. a read pool's warm snapshot. The map is shared, not copied.
+    pub(crate) fn from_table_snapshot(data: Arc<BTreeMap<Vec<u8>, Vec<u8>>>) -> Self {
+        Self::from_data(data, KeyLayout::Flat)
+    }
+
+    fn with_layout(engine: Arc<ClawStoreEngine>, layout: KeyLayout) -> Self {
+        let table_id = table_id_for_name(T::NAME);
+        Self::from_data(Arc::new(snapshot_table(&engine, table_id)), layout)
+    }
+
+    fn from_data(data: Arc<BTreeMap<Vec<u8>, Vec<u8>>>, layout: KeyLayout) -> Self {
         Self {
             data,
             position: None,
+            layout,
+            after_delete: false,
+            last_decoded: None,
+            key_decodes: 0,
             _phantom: PhantomData,
         }
     }
 
-    /// Decode a key-value pair from raw bytes.
-    fn decode_pair(key_bytes: &[u8], val_bytes: &[u8]) -> PairResult<T> {
-        let key = <T::Key as Decode>::decode(key_bytes)?;
+    /// Length of the table-key part of a stored key.
+    fn primary_len(&self, key_bytes: &[u8], val_bytes: &[u8]) -> usize {
+        match self.layout {
+            KeyLayout::Flat => key_bytes.len(),
+            KeyLayout::Dup { subkey_len } => {
+                let subkey_len = subkey_len.unwrap_or(val_bytes.len()).min(val_bytes.len());
+                key_bytes.len().saturating_sub(subkey_len)
+            }
+        }
+    }
+
+    /// Stored key (without table prefix) for an encoded key and compressed value.
+    fn storage_key(&self, key_bytes: &[u8], val_bytes: &[u8]) -> Vec<u8> {
+        let mut storage = key_bytes.to_vec();
+        if let KeyLayout::Dup { subkey_len } = self.layout {
+            let subkey_len = subkey_len.unwrap_or(val_bytes.len()).min(val_bytes.len());
+            storage.extend_from_slice(&val_bytes[..subkey_len]);
+        }
+        storage
+    }
+
+    /// Whether the stored entry `(key_bytes, val_bytes)` belongs to table key `primary`.
+    fn has_primary(&self, key_bytes: &[u8], val_bytes: &[u8], primary: &[u8]) -> bool {
+        key_bytes[..self.primary_len(key_bytes, val_bytes)] == *primary
+    }
+
+    /// Decode the entry at the current position (the subkey suffix is
+    /// dropped from the key). The table key is decoded only when the
+    /// position moved since the last decode; the value is always decompressed.
+    fn decode_current(&mut self) -> PairResult<T> {
+        let Some(pos) = self.position.as_ref() else { return Ok(None) };
+        let Some(val_bytes) = self.data.get(pos) else { return Ok(None) };
+        let key = match &self.last_decoded {
+            Some((bytes, key)) if bytes == pos => key.clone(),
+            _ => {
+                let split = self.primary_len(pos, val_bytes);
+                let key = <T::Key as Decode>::decode(&pos[..split])?;
+                self.key_decodes += 1;
+                self.last_decoded = Some((pos.clone(), key.clone()));
+                key
+            }
+        };
         let value = <T::Value as Decompress>::decompress(val_bytes)?;
         Ok(Some((key, value)))
     }
+
+    /// Move to a stored key (if any) and decode the entry there. With
+    /// `None` the position is kept and nothing is returned.
+    fn move_to(&mut self, key_bytes: Option<Vec<u8>>) -> PairResult<T> {
+        self.after_delete = false;
+        let Some(key_bytes) = key_bytes else { return Ok(None) };
+        self.position = Some(key_bytes);
+        self.decode_current()
+    }
+
+    /// Number of table keys this cursor has decoded. Repeated `current()`
+    /// calls, or a `seek_exact` to the current key, don't add to it.
+    pub fn key_decode_count(&self) -> u64 {
+        self.key_decodes
+    }
+
+    /// Table-key bytes of the entry at the current position.
+    fn current_primary(&self) -> Option<Vec<u8>> {
+        let pos = self.position.as_ref()?;
+        let value = self.data.get(pos)?;
+        Some(pos[..self.primary_len(pos, value)].to_vec())
+    }
+
+    /// Walk backwards from the last entry strictly before `start_key`.
+    ///
+    /// Unlike `walk_back(Some(key))`, which starts at the first entry
+    /// `>= key`, the first result here is always `< start_key`.
+    pub fn walk_back_from(&mut self, start_key: T::Key) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let encoded = start_key.encode();
+        let found = self.data.range(..encoded.as_ref().to_vec())
+            .next_back()
+            .map(|(k, _)| k.clone());
+        let start = self.move_to(found).transpose();
+        Ok(ReverseWalker::new(self, start))
+    }
+
+    /// Walk backwards from `start_key` if present, else from the last entry
+    /// before it: the first result is `<= start_key`. For a DupSort table
+    /// the walk starts at the key's last duplicate.
+    pub fn walk_back_from_inclusive(&mut self, start_key: T::Key) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let encoded = start_key.encode();
+        let key_bytes = encoded.as_ref().to_vec();
+        let found = self.data.range(key_bytes.clone()..)
+            .take_while(|(k, v)| self.has_primary(k, v, &key_bytes))
+            .last()
+            .or_else(|| self.data.range(..key_bytes.clone()).next_back())
+            .map(|(k, _)| k.clone());
+        let start = self.move_to(found).transpose();
+        Ok(ReverseWalker::new(self, start))
+    }
+
+    /// Every entry of the table, in key order.
+    pub fn collect_all(&mut self) -> Result<Vec<(T::Key, T::Value)>, DatabaseError> {
+        let first = self.first()?;
+        self.collect_following(first, usize::MAX)
+    }
+
+    /// The first `n` entries of the table, in key order.
+    pub fn collect_n(&mut self, n: usize) -> Result<Vec<(T::Key, T::Value)>, DatabaseError> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let first = self.first()?;
+        self.collect_following(first, n)
+    }
+
+    /// Every entry from `start_key` (inclusive) to the end, in key order.
+    pub fn collect_from(&mut self, start_key: T::Key) -> Result<Vec<(T::Key, T::Value)>, DatabaseError> {
+        let first = self.seek(start_key)?;
+        self.collect_following(first, usize::MAX)
+    }
+
+    /// `entry` and the entries after it, up to `limit` in total. The cursor
+    /// is left on the last one collected.
+    fn collect_following(
+        &mut self,
+        mut entry: Option<(T::Key, T::Value)>,
+        limit: usize,
+    ) -> Result<Vec<(T::Key, T::Value)>, DatabaseError> {
+        let mut entries = Vec::with_capacity(self.data.len().min(limit));
+        while let Some(pair) = entry {
+            entries.push(pair);
+            if entries.len() >= limit {
+                break;
+            }
+            entry = self.next()?;
+        }
+        Ok(entries)
+    }
+
+    // -- DupSort navigation (shared by the dup cursors) --
+
+    /// Next duplicate of the current key; None (position kept) past the last.
+    fn next_dup(&mut self) -> PairResult<T> {
+        let (Some(pos), Some(primary)) = (self.position.clone(), self.current_primary()) else {
+            return Ok(None);
+        };
+        let found = self.data.range((Bound::Excluded(pos), Bound::Unbounded))
+            .next()
+            .filter(|(k, v)| self.has_primary(k, v, &primary))
+            .map(|(k, _)| k.clone());
+        self.move_to(found)
+    }
+
+    /// Previous duplicate of the current key; None (position kept) before the first.
+    fn prev_dup(&mut self) -> PairResult<T> {
+        let (Some(pos), Some(primary)) = (self.position.clone(), self.current_primary()) else {
+            return Ok(None);
+        };
+        let found = self.data.range(..pos)
+            .next_back()
+            .filter(|(k, v)| self.has_primary(k, v, &primary))
+            .map(|(k, _)| k.clone());
+        self.move_to(found)
+    }
+
+    /// First entry of the next distinct key.
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let (Some(pos), Some(primary)) = (self.position.clone(), self.current_primary()) else {
+            return self.first();
+        };
+        let found = self.data.range((Bound::Excluded(pos), Bound::Unbounded))
+            .find(|(k, v)| !self.has_primary(k, v, &primary))
+            .map(|(k, _)| k.clone());
+        self.move_to(found)
+    }
+
+    /// Move to the last duplicate of the current key and return its value.
+    fn last_dup(&mut self) -> ValueOnlyResult<T> {
+        let Some(primary) = self.current_primary() else { return Ok(None) };
+        let found = self.data.range(primary.clone()..)
+            .take_while(|(k, v)| self.has_primary(k, v, &primary))
+            .last()
+            .map(|(k, _)| k.clone());
+        Ok(self.move_to(found)?.map(|(_, v)| v))
+    }
+
+    /// First duplicate of `key_bytes` whose subkey is >= `subkey_bytes`.
+    fn seek_by_key_subkey(&mut self, key_bytes: &[u8], subkey_bytes: &[u8]) -> ValueOnlyResult<T> {
+        let mut target = key_bytes.to_vec();
+        target.extend_from_slice(subkey_bytes);
+        let found = self.data.range(target..)
+            .next()
+            .filter(|(k, v)| self.has_primary(k, v, key_bytes))
+            .map(|(k, _)| k.clone());
+        Ok(self.move_to(found)?.map(|(_, v)| v))
+    }
 }
 
 impl<T: Table> std::fmt::Debug for ClawCursor<T> {
@@ -84,53 +330,49 @@ impl<T: Table> std::fmt::Debug for ClawCursor<T> {
 
 impl<T: Table> DbCursorRO<T> for ClawCursor<T> {
     fn first(&mut self) -> PairResult<T> {
-        match self.data.iter().next() {
-            Some((k, v)) => {
-                self.position = Some(k.clone());
-                Self::decode_pair(k, v)
-            }
-            None => Ok(None),
-        }
+        let found = self.data.keys().next().cloned();
+        self.move_to(found)
     }
 
     fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
         let encoded = key.encode();
-        let key_bytes = encoded.as_ref().to_vec();
-        match self.data.get(&key_bytes) {
-            Some(v) => {
-                self.position = Some(key_bytes.clone());
-                Self::decode_pair(&key_bytes, v)
-            }
-            None => Ok(None),
+        let key_bytes = encoded.as_ref();
+        if let KeyLayout::Dup { .. } = self.layout {
+            // First duplicate stored under this key
+            let found = self.data.range(key_bytes.to_vec()..)
+                .next()
+                .filter(|(k, v)| self.has_primary(k, v, key_bytes))
+                .map(|(k, _)| k.clone());
+            return self.move_to(found);
         }
+        // Already there: skip the map search and the key decode
+        if self.position.as_deref() == Some(key_bytes) {
+            self.after_delete = false;
+            return self.decode_current();
+        }
+        let found = self.data.contains_key(key_bytes).then(|| key_bytes.to_vec());
+        self.move_to(found)
     }
 
     fn seek(&mut self, key: T::Key) -> PairResult<T> {
         let encoded = key.encode();
         let key_bytes = encoded.as_ref().to_vec();
         // Find first entry >= key
-        match self.data.range(key_bytes.clone()..).next() {
-            Some((k, v)) => {
-                self.position = Some(k.clone());
-                Self::decode_pair(k, v)
-            }
-            None => Ok(None),
-        }
+        let found = self.data.range(key_bytes..).next().map(|(k, _)| k.clone());
+        self.move_to(found)
     }
 
     fn next(&mut self) -> PairResult<T> {
+        if std::mem::take(&mut self.after_delete) {
+            return self.current();
+        }
         let pos = match &self.position {
             Some(p) => p.clone(),
             None => return self.first(),
         };
         // Find next entry after current position
-        match self.data.range((Bound::Excluded(pos), Bound::Unbounded)).next() {
-            Some((k, v)) => {
-                self.position = Some(k.clone());
-                Self::decode_pair(k, v)
-            }
-            None => Ok(None),
-        }
+        let found = self.data.range((Bound::Excluded(pos), Bound::Unbounded)).next().map(|(k, _)| k.clone());
+        self.move_to(found)
     }
 
     fn prev(&mut self) -> PairResult<T> {
@@ -138,34 +380,17 @@ impl<T: Table> DbCursorRO<T> for ClawCursor<T> {
             Some(p) => p.clone(),
             None => return self.last(),
         };
-        match self.data.range(..pos).next_back() {
-            Some((k, v)) => {
-                self.position = Some(k.clone());
-                Self::decode_pair(k, v)
-            }
-            None => Ok(None),
-        }
+        let found = self.data.range(..pos).next_back().map(|(k, _)| k.clone());
+        self.move_to(found)
     }
 
     fn last(&mut self) -> PairResult<T> {
-        match self.data.iter().next_back() {
-            Some((k, v)) => {
-                self.position = Some(k.clone());
-                Self::decode_pair(k, v)
-            }
-            None => Ok(None),
-        }
+        let found = self.data.keys().next_back().cloned();
+        self.move_to(found)
     }
 
     fn current(&mut self) -> PairResult<T> {
-        let pos = match &self.position {
-            Some(p) => p.clone(),
-            None => return Ok(None),
-        };
-        match self.data.get(&pos) {
-            Some(v) => Self::decode_pair(&pos, v),
-            None => Ok(None),
-        }
+        self.decode_current()
     }
 
     fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError>
@@ -236,7 +461,14 @@ pub struct ClawDupCursor<T: DupSort> {
 impl<T: DupSort> ClawDupCursor<T> {
     pub(crate) fn new(engine: Arc<ClawStoreEngine>) -> Self {
         Self {
-            inner: ClawCursor::new(engine),
+            inner: ClawCursor::new_dup(engine),
+        }
+    }
+
+    /// DupSort cursor over an already-built table snapshot, shared not copied.
+    pub(crate) fn from_table_snapshot(data: Arc<BTreeMap<Vec<u8>, Vec<u8>>>) -> Self {
+        Self {
+            inner: ClawCursor::from_data(data, KeyLayout::Dup { subkey_len: dup_subkey_len(T::NAME) }),
         }
     }
 }
@@ -308,56 +540,70 @@ impl<T: DupSort> DbCursorRO<T> for ClawDupCursor<T> {
 
 impl<T: DupSort> DbDupCursorRO<T> for ClawDupCursor<T> {
     fn prev_dup(&mut self) -> PairResult<T> {
-        // In a flat KV store, prev_dup is the same as prev
-        self.inner.prev()
+        self.inner.prev_dup()
     }
 
     fn next_dup(&mut self) -> PairResult<T> {
-        self.inner.next()
+        self.inner.next_dup()
     }
 
     fn last_dup(&mut self) -> ValueOnlyResult<T> {
-        // Return the value at the last position
-        match self.inner.current()? {
-            Some((_k, v)) => Ok(Some(v)),
-            None => Ok(None),
-        }
+        self.inner.last_dup()
     }
 
     fn next_no_dup(&mut self) -> PairResult<T> {
-        self.inner.next()
+        self.inner.next_no_dup()
     }
 
     fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
-        match self.inner.next()? {
-            Some((_k, v)) => Ok(Some(v)),
-            None => Ok(None),
-        }
+        Ok(self.inner.next_dup()?.map(|(_k, v)| v))
     }
 
-    fn seek_by_key_subkey(&mut self, key: T::Key, _subkey: T::SubKey) -> ValueOnlyResult<T> {
-        match self.inner.seek(key)? {
-            Some((_k, v)) => Ok(Some(v)),
-            None => Ok(None),
-        }
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        self.inner.seek_by_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())
     }
 
     fn walk_dup(
         &mut self,
         key: Option<T::Key>,
-        _subkey: Option<T::SubKey>,
+        subkey: Option<T::SubKey>,
     ) -> Result<DupWalker<'_, T, Self>, DatabaseError>
     where
         Self: Sized,
     {
-        let start = match key {
-            Some(k) => self.seek_exact(k).transpose(),
-            None => self.first().transpose(),
-        };
+        let start = dup_walk_start(&mut self.inner, key, subkey)?;
         Ok(DupWalker { cursor: self, start })
     }
 }
 
+/// Position `cursor` for `walk_dup` and return the first entry, if any.
+fn dup_walk_start<T: DupSort>(
+    cursor: &mut ClawCursor<T>,
+    key: Option<T::Key>,
+    subkey: Option<T::SubKey>,
+) -> Result<Option<Result<(T::Key, T::Value), DatabaseError>>, DatabaseError> {
+    let start = match (key, subkey) {
+        (Some(key), Some(subkey)) => {
+            match cursor.seek_by_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())? {
+                Some(_) => cursor.current().transpose(),
+                None => None,
+            }
+        }
+        (Some(key), None) => cursor.seek_exact(key).transpose(),
+        (None, Some(subkey)) => match cursor.first()? {
+            Some((key, _)) => {
+                match cursor.seek_by_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())? {
+                    Some(_) => cursor.current().transpose(),
+                    None => None,
+                }
+            }
+            None => None,
+        },
+        (None, None) => cursor.first().transpose(),
+    };
+    Ok(start)
+}
+
 // ---------------------------------------------------------------------------
 // Read-write cursor
 // ---------------------------------------------------------------------------
@@ -366,23 +612,93 @@ impl<T: DupSort> DbDupCursorRO<T> for ClawDupCursor<T> {
 pub struct ClawCursorMut<T: Table> {
     inner: ClawCursor<T>,
     engine: Arc<ClawStoreEngine>,
+    /// Undo log of the transaction that opened this cursor; every write
+    /// records the key's previous value here first
+    undo_log: UndoLog,
 }
 
 impl<T: Table> ClawCursorMut<T> {
-    pub(crate) fn new(engine: Arc<ClawStoreEngine>) -> Self {
+    pub(crate) fn new(engine: Arc<ClawStoreEngine>, undo_log: UndoLog) -> Self {
         let inner = ClawCursor::new(Arc::clone(&engine));
-        Self { inner, engine }
+        Self { inner, engine, undo_log }
     }
 
-    fn write_entry(&self, key_bytes: &[u8], val_bytes: &[u8]) -> Result<(), DatabaseError> {
+    /// Mutable cursor over a DupSort table (`[encoded_key][subkey]` engine keys).
+    pub(crate) fn new_dup(engine: Arc<ClawStoreEngine>, undo_log: UndoLog) -> Self {
+        let inner = ClawCursor::new_dup(Arc::clone(&engine));
+        Self { inner, engine, undo_log }
+    }
+
+    /// Engine key for stored key bytes, with its previous value pushed onto
+    /// the transaction's undo log.
+    fn prefixed_with_undo(&self, key_bytes: &[u8]) -> Result<Vec<u8>, DatabaseError> {
         let table_id = table_id_for_name(T::NAME);
         let mut prefixed = Vec::with_capacity(1 + key_bytes.len());
         prefixed.push(table_id);
         prefixed.extend_from_slice(key_bytes);
+        let previous = self.engine.get(&prefixed).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        self.undo_log.lock().push((prefixed.clone(), previous));
+        Ok(prefixed)
+    }
+
+    fn write_entry(&self, key_bytes: &[u8], val_bytes: &[u8]) -> Result<(), DatabaseError> {
+        let prefixed = self.prefixed_with_undo(key_bytes)?;
         self.engine.put_fast(&prefixed, val_bytes).map_err(|e| {
             DatabaseError::Other(e.to_string())
         })
     }
+
+    /// Delete every entry whose key falls in `range`. Returns the count
+    /// deleted, 0 for an empty or inverted range.
+    ///
+    /// Keys are collected from the snapshot in a single range pass, then each
+    /// is deleted from the engine and dropped from the snapshot. The cursor
+    /// position is left untouched, so navigation outside the range continues
+    /// to work.
+    pub fn delete_range(&mut self, range: impl RangeBounds<T::Key>) -> Result<usize, DatabaseError> {
+        let encode_bound = |bound: Bound<&T::Key>| match bound {
+            Bound::Included(key) => Bound::Included(key.clone().encode().as_ref().to_vec()),
+            Bound::Excluded(key) => Bound::Excluded(key.clone().encode().as_ref().to_vec()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let bounds = (encode_bound(range.start_bound()), encode_bound(range.end_bound()));
+        // BTreeMap::range panics on these instead of yielding nothing
+        let empty = match &bounds {
+            (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+            (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end)) => start > end,
+            _ => false,
+        };
+        if empty {
+            return Ok(0);
+        }
+
+        let doomed: Vec<Vec<u8>> = self.inner.data.range(bounds).map(|(k, _)| k.clone()).collect();
+        for key_bytes in &doomed {
+            self.delete_stored(key_bytes)?;
+        }
+
+        Ok(doomed.len())
+    }
+
+    /// Delete a stored key from the engine and the snapshot.
+    fn delete_stored(&mut self, key_bytes: &[u8]) -> Result<(), DatabaseError> {
+        let prefixed = self.prefixed_with_undo(key_bytes)?;
+        self.engine.delete(&prefixed).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        Arc::make_mut(&mut self.inner.data).remove(key_bytes);
+        Ok(())
+    }
+
+    /// Re-take the table snapshot from the engine's current RAM state.
+    ///
+    /// Picks up writes made outside this cursor (other cursors, `put`).
+    /// The position is kept; navigation continues from the same key.
+    pub fn refresh_snapshot(&mut self) {
+        self.inner.data = Arc::new(snapshot_table(&self.engine, table_id_for_name(T::NAME)));
+    }
 }
 
 impl<T: Table> std::fmt::Debug for ClawCursorMut<T> {
@@ -454,27 +770,25 @@ impl<T: Table> DbCursorRW<T> for ClawCursorMut<T> {
         let encoded_key = key.encode();
         let mut compressed_val = <<T::Value as Compress>::Compressed as Default>::default();
         value.compress_to_buf(&mut compressed_val);
-        self.write_entry(encoded_key.as_ref(), compressed_val.as_ref())?;
+        let key_bytes = self.inner.storage_key(encoded_key.as_ref(), compressed_val.as_ref());
+        self.write_entry(&key_bytes, compressed_val.as_ref())?;
         // Update snapshot
-        self.inner.data.insert(
-            encoded_key.as_ref().to_vec(),
-            compressed_val.as_ref().to_vec(),
-        );
+        Arc::make_mut(&mut self.inner.data).insert(key_bytes, compressed_val.as_ref().to_vec());
         Ok(())
     }
 
     fn insert(&mut self, key: T::Key, value: &T::Value) -> Result<(), DatabaseError> {
         let encoded_key = key.encode();
-        let key_bytes = encoded_key.as_ref().to_vec();
+        let mut compressed_val = <<T::Value as Compress>::Compressed as Default>::default();
+        value.compress_to_buf(&mut compressed_val);
+        let key_bytes = self.inner.storage_key(encoded_key.as_ref(), compressed_val.as_ref());
         if self.inner.data.contains_key(&key_bytes) {
             return Err(DatabaseError::Other(
                 format!("Key already exists in table {}", T::NAME)
             ));
         }
-        let mut compressed_val = <<T::Value as Compress>::Compressed as Default>::default();
-        value.compress_to_buf(&mut compressed_val);
         self.write_entry(&key_bytes, compressed_val.as_ref())?;
-        self.inner.data.insert(key_bytes, compressed_val.as_ref().to_vec());
+        Arc::make_mut(&mut self.inner.data).insert(key_bytes, compressed_val.as_ref().to_vec());
         Ok(())
     }
 
@@ -482,16 +796,21 @@ impl<T: Table> DbCursorRW<T> for ClawCursorMut<T> {
         self.upsert(key, value)
     }
 
+    /// Delete the current entry and move onto the one after it.
+    ///
+    /// Like MDBX, the following `next()` returns that entry rather than
+    /// skipping it, and `prev()` returns the entry before the deleted one.
     fn delete_current(&mut self) -> Result<(), DatabaseError> {
-        if let Some(pos) = self.inner.position.clone() {
-            let table_id = table_id_for_name(T::NAME);
-            let mut prefixed = Vec::with_capacity(1 + pos.len());
-            prefixed.push(table_id);
-            prefixed.extend_from_slice(&pos);
-            self.engine.delete(&prefixed).map_err(|e| {
-                DatabaseError::Other(e.to_string())
-            })?;
-            self.inner.data.remove(&pos);
+        let Some(pos) = self.inner.position.clone() else { return Ok(()) };
+        let following = self.inner.data
+            .range((Bound::Excluded(pos.clone()), Bound::Unbounded))
+            .next()
+            .map(|(k, _)| k.clone());
+        self.delete_stored(&pos)?;
+        // Past the last entry, keep the deleted key: `next()` then finds nothing
+        if following.is_some() {
+            self.inner.position = following;
+            self.inner.after_delete = true;
         }
         Ok(())
     }
@@ -507,9 +826,9 @@ pub struct ClawDupCursorMut<T: DupSort> {
 }
 
 impl<T: DupSort> ClawDupCursorMut<T> {
-    pub(crate) fn new(engine: Arc<ClawStoreEngine>) -> Self {
+    pub(crate) fn new(engine: Arc<ClawStoreEngine>, undo_log: UndoLog) -> Self {
         Self {
-            inner: ClawCursorMut::new(engine),
+            inner: ClawCursorMut::new_dup(engine, undo_log),
         }
     }
 }
@@ -594,44 +913,39 @@ impl<T: DupSort> DbCursorRW<T> for ClawDupCursorMut<T> {
 }
 
 impl<T: DupSort> DbDupCursorRO<T> for ClawDupCursorMut<T> {
-    fn prev_dup(&mut self) -> PairResult<T> { self.inner.prev() }
-    fn next_dup(&mut self) -> PairResult<T> { self.inner.next() }
-    fn last_dup(&mut self) -> ValueOnlyResult<T> {
-        match self.inner.current()? {
-            Some((_k, v)) => Ok(Some(v)),
-            None => Ok(None),
-        }
-    }
-    fn next_no_dup(&mut self) -> PairResult<T> { self.inner.next() }
+    fn prev_dup(&mut self) -> PairResult<T> { self.inner.inner.prev_dup() }
+    fn next_dup(&mut self) -> PairResult<T> { self.inner.inner.next_dup() }
+    fn last_dup(&mut self) -> ValueOnlyResult<T> { self.inner.inner.last_dup() }
+    fn next_no_dup(&mut self) -> PairResult<T> { self.inner.inner.next_no_dup() }
     fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
-        match self.inner.next()? {
-            Some((_k, v)) => Ok(Some(v)),
-            None => Ok(None),
-        }
+        Ok(self.inner.inner.next_dup()?.map(|(_k, v)| v))
     }
-    fn seek_by_key_subkey(&mut self, key: T::Key, _subkey: T::SubKey) -> ValueOnlyResult<T> {
-        match self.inner.seek(key)? {
-            Some((_k, v)) => Ok(Some(v)),
-            None => Ok(None),
-        }
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        self.inner.inner.seek_by_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())
     }
     fn walk_dup(
         &mut self,
         key: Option<T::Key>,
-        _subkey: Option<T::SubKey>,
+        subkey: Option<T::SubKey>,
     ) -> Result<DupWalker<'_, T, Self>, DatabaseError>
     where Self: Sized {
-        let start = match key {
-            Some(k) => self.seek_exact(k).transpose(),
-            None => self.first().transpose(),
-        };
+        let start = dup_walk_start(&mut self.inner.inner, key, subkey)?;
         Ok(DupWalker { cursor: self, start })
     }
 }
 
 impl<T: DupSort> DbDupCursorRW<T> for ClawDupCursorMut<T> {
     fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
-        self.inner.delete_current()
+        let Some(primary) = self.inner.inner.current_primary() else { return Ok(()) };
+        let cursor = &self.inner.inner;
+        let doomed: Vec<Vec<u8>> = cursor.data.range(primary.clone()..)
+            .take_while(|(k, v)| cursor.has_primary(k, v, &primary))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key_bytes in &doomed {
+            self.inner.delete_stored(key_bytes)?;
+        }
+        Ok(())
     }
 
     fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
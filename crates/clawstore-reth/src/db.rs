@@ -1,14 +1,24 @@
 //! `Database` trait implementation for ClawStore.
 
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use reth_db_api::cursor::DbCursorRO;
 use reth_db_api::database::Database;
+use reth_db_api::table::{Compress, Decode, Decompress, Encode, Table};
+use reth_db_api::tables::{RawTable, TableViewer, Tables};
+use reth_db_api::transaction::{DbTx, DbTxMut};
 use reth_storage_errors::db::DatabaseError;
 
-use clawstore_core::{ClawStoreEngine, Config as ClawConfig};
+use clawstore_core::{ClawStoreEngine, Config as ClawConfig, ConsistencyReport, EntryStats, MemoryBreakdown};
 
-use crate::tx::{ClawReadTx, ClawWriteTx};
+pub use clawstore_core::OpenMode;
+
+use crate::pool::{PooledReadTx, ReadTxPool};
+use crate::table_ids::{builtin_table_names, storage_key, table_id_for_name};
+use crate::tx::{ClawReadTx, ClawWriteTx, TxIds};
 
 /// ClawStore database implementing Reth's `Database` trait.
 ///
@@ -17,21 +27,63 @@ use crate::tx::{ClawReadTx, ClawWriteTx};
 pub struct ClawDatabase {
     engine: Arc<ClawStoreEngine>,
     path: PathBuf,
+    /// Source of transaction `snapshot_id`s
+    tx_ids: Arc<TxIds>,
+    /// Reusable read transactions for `pooled_tx`
+    read_pool: Arc<ReadTxPool>,
 }
 
 impl ClawDatabase {
     /// Open a ClawStore database at the given path.
     pub fn open<P: AsRef<Path>>(path: P, config: ClawConfig) -> Result<Self, DatabaseError> {
+        Self::open_with_mode(path, config, OpenMode::ReadWrite)
+    }
+
+    /// Open an existing ClawStore database without write access.
+    ///
+    /// Recovers the WAL into RAM but never writes to the store directory, and
+    /// holds a shared lock so other read-only openers can coexist. `tx_mut()`
+    /// returns an error.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
+        Self::open_with_mode(path, ClawConfig::default(), OpenMode::ReadOnly)
+    }
+
+    /// Open a ClawStore database in the given mode.
+    pub fn open_with_mode<P: AsRef<Path>>(
+        path: P,
+        config: ClawConfig,
+        mode: OpenMode,
+    ) -> Result<Self, DatabaseError> {
         let path = path.as_ref().to_path_buf();
-        let engine = ClawStoreEngine::open(&path, config).map_err(|e| {
-            DatabaseError::Other(e.to_string())
-        })?;
+        let pool_size = config.read_tx_pool_size;
+        let pre_warm_tables = config.pre_warm_tables.clone();
+        let engine = ClawStoreEngine::open_with_mode(&path, config, mode)
+            .map_err(|e| DatabaseError::Other(e.to_string()))?;
+        let engine = Arc::new(engine);
+        let tx_ids = Arc::new(TxIds::default());
+        let read_pool = Arc::new(ReadTxPool::new(
+            Arc::clone(&engine),
+            Arc::clone(&tx_ids),
+            pool_size,
+            &pre_warm_tables,
+        ));
         Ok(Self {
-            engine: Arc::new(engine),
+            engine,
             path,
+            tx_ids,
+            read_pool,
         })
     }
 
+    /// Whether this database was opened read-only.
+    pub fn mode(&self) -> OpenMode {
+        if self.engine.is_read_only() {
+            OpenMode::ReadOnly
+        } else {
+            OpenMode::ReadWrite
+        }
+    }
+
     /// Get a reference to the underlying engine.
     pub fn engine(&self) -> &ClawStoreEngine {
         &self.engine
@@ -42,6 +94,183 @@ impl ClawDatabase {
         &self.path
     }
 
+    /// Key/value size statistics for one Reth table (e.g. `"PlainStorageState"`).
+    pub fn table_stats_by_name(&self, table_name: &str) -> EntryStats {
+        self.engine.table_stats(table_id_for_name(table_name))
+    }
+
+    /// Estimated memory held by the engine; see
+    /// `ClawStoreEngine::memory_usage_breakdown`.
+    pub fn memory_breakdown(&self) -> MemoryBreakdown {
+        self.engine.memory_usage_breakdown()
+    }
+
+    /// Number of entries in table `T`, without opening a transaction.
+    pub fn table_cursor_count<T: Table>(&self) -> Result<usize, DatabaseError> {
+        Ok(self.engine.prefix_count(&[table_id_for_name(T::NAME)]))
+    }
+
+    /// Number of entries across all tables.
+    pub fn total_entry_count(&self) -> usize {
+        self.engine.len()
+    }
+
+    /// Highest `snapshot_id` of a committed write transaction (0 if none).
+    pub fn latest_committed_id(&self) -> u64 {
+        self.tx_ids.latest_committed()
+    }
+
+    /// Validate WAL files, data file checksums and leftover compaction files.
+    ///
+    /// Returns `Err` only for I/O failures; corruption shows up in the report.
+    pub fn verify_consistency(&self) -> Result<ConsistencyReport, DatabaseError> {
+        self.engine.verify_consistency().map_err(|e| DatabaseError::Other(e.to_string()))
+    }
+
+    /// Read transaction that copies the store when it starts.
+    ///
+    /// Cursors from `ClawReadTx::cursor_read_snapshot_at_tx_start` on it
+    /// never see writes made after this call.
+    pub fn tx_with_start_snapshot(&self) -> Result<ClawReadTx, DatabaseError> {
+        Ok(ClawReadTx::with_start_snapshot(self.engine_arc(), self.tx_ids.next()))
+    }
+
+    /// Read transaction from the pool; it goes back to the pool when dropped.
+    ///
+    /// Cursors over `Config::pre_warm_tables` share the pool's table
+    /// snapshot instead of scanning the store, as long as nothing has been
+    /// written since the snapshot was built.
+    pub fn pooled_tx(&self) -> PooledReadTx {
+        self.read_pool.checkout()
+    }
+
+    /// The read transaction pool behind `pooled_tx`.
+    pub fn read_pool(&self) -> &ReadTxPool {
+        &self.read_pool
+    }
+
+    /// Run `f` in a write transaction: commit when it returns `Ok`, abort
+    /// when it returns `Err`. The closure's result is passed through.
+    ///
+    /// Like `ClawWriteTx::abort`, aborting does not undo puts and deletes
+    /// that `f` already made — writes reach RAM and the WAL as they are
+    /// issued. Return `Err` before writing to leave the store untouched.
+    pub fn transaction<F, R>(&self, f: F) -> Result<R, DatabaseError>
+    where
+        F: FnOnce(&ClawWriteTx) -> Result<R, DatabaseError>,
+    {
+        let tx = self.tx_mut()?;
+        match f(&tx) {
+            Ok(result) => {
+                tx.commit()?;
+                Ok(result)
+            }
+            Err(e) => {
+                tx.abort();
+                Err(e)
+            }
+        }
+    }
+
+    /// Run `f` in a read transaction and return its result.
+    pub fn read_transaction<F, R>(&self, f: F) -> Result<R, DatabaseError>
+    where
+        F: FnOnce(&ClawReadTx) -> Result<R, DatabaseError>,
+    {
+        let tx = self.tx()?;
+        let result = f(&tx);
+        tx.commit()?;
+        result
+    }
+
+    /// Write every entry of table `T` to `path` as a JSON array, for debugging.
+    ///
+    /// Each element has `key` / `value` (the `Debug` rendering, for reading)
+    /// and `key_hex` / `value_hex` (encoded key and compressed value bytes,
+    /// used by `load_table_from_json`). Returns the number of entries.
+    pub fn dump_table_to_json<T: Table>(&self, path: &Path) -> Result<usize, DatabaseError> {
+        let tx = self.tx()?;
+        let mut cursor = tx.cursor_read::<T>()?;
+        let mut entries = Vec::new();
+        for row in cursor.walk(None)? {
+            let (key, value) = row?;
+            let (key_debug, value_debug) = (format!("{:?}", key), format!("{:?}", value));
+            entries.push(serde_json::json!({
+                "key": key_debug,
+                "value": value_debug,
+                "key_hex": to_hex(key.encode().as_ref()),
+                "value_hex": to_hex(value.compress().as_ref()),
+            }));
+        }
+
+        let file = File::create(path).map_err(|e| DatabaseError::Other(e.to_string()))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &entries)
+            .map_err(|e| DatabaseError::Other(e.to_string()))?;
+        Ok(entries.len())
+    }
+
+    /// Put every entry of a `dump_table_to_json` file into table `T` in one
+    /// write transaction. Returns the number of entries loaded.
+    pub fn load_table_from_json<T: Table>(&self, path: &Path) -> Result<usize, DatabaseError> {
+        let file = File::open(path).map_err(|e| DatabaseError::Other(e.to_string()))?;
+        let entries: Vec<serde_json::Value> = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| DatabaseError::Other(e.to_string()))?;
+
+        let tx = self.tx_mut()?;
+        for entry in &entries {
+            let key_bytes = from_hex(json_str(entry, "key_hex")?)?;
+            let value_bytes = from_hex(json_str(entry, "value_hex")?)?;
+            let key = <T::Key as Decode>::decode(&key_bytes)?;
+            let value = <T::Value as Decompress>::decompress(&value_bytes)?;
+            tx.put::<T>(key, value)?;
+        }
+        tx.commit()?;
+        Ok(entries.len())
+    }
+
+    /// Copy a Reth MDBX database into a new ClawStore database at `dest`.
+    ///
+    /// Opens `mdbx_path` read-only and runs `migrate_from` over it. Returns
+    /// the new database and the number of entries copied.
+    #[cfg(feature = "mdbx")]
+    pub fn migrate_from_mdbx<P: AsRef<Path>>(
+        mdbx_path: &Path,
+        dest: P,
+        config: ClawConfig,
+    ) -> Result<(Self, usize), DatabaseError> {
+        let args = reth_db::mdbx::DatabaseArguments::new(reth_db_api::models::ClientVersion::default());
+        let source = reth_db::open_db_read_only(mdbx_path, args)
+            .map_err(|e| DatabaseError::Other(e.to_string()))?;
+        Self::migrate_from(&source, dest, config)
+    }
+
+    /// Copy every built-in Reth table of `source` into a new ClawStore
+    /// database at `dest`.
+    ///
+    /// Raw key and value bytes are copied as-is with `put_fast` (DupSort
+    /// duplicates under the `[encoded_key][subkey]` layout the dup cursors
+    /// read), followed by one `sync_wal`. Tables `source` does not have are
+    /// skipped. Returns the new database and the number of entries copied.
+    pub fn migrate_from<D: Database, P: AsRef<Path>>(
+        source: &D,
+        dest: P,
+        config: ClawConfig,
+    ) -> Result<(Self, usize), DatabaseError> {
+        let db = Self::open(dest, config)?;
+        let tx = source.tx()?;
+        let copier = TableCopier { tx: &tx, engine: &db.engine };
+        let mut total = 0;
+        for name in builtin_table_names() {
+            let Ok(table) = name.parse::<Tables>() else {
+                tracing::debug!(table = name, "not a Reth table in this version, skipping");
+                continue;
+            };
+            total += table.view(&copier)?;
+        }
+        db.engine.sync_wal().map_err(|e| DatabaseError::Other(e.to_string()))?;
+        Ok((db, total))
+    }
+
     /// Internal: get Arc to engine for transaction creation.
     pub(crate) fn engine_arc(&self) -> Arc<ClawStoreEngine> {
         Arc::clone(&self.engine)
@@ -52,7 +281,7 @@ impl std::fmt::Debug for ClawDatabase {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ClawDatabase")
             .field("path", &self.path)
-            .field("entries", &self.engine.len())
+            .field("entries", &self.total_entry_count())
             .finish()
     }
 }
@@ -62,19 +291,94 @@ impl Database for ClawDatabase {
     type TXMut = ClawWriteTx;
 
     fn tx(&self) -> Result<Self::TX, DatabaseError> {
-        Ok(ClawReadTx::new(self.engine_arc()))
+        Ok(ClawReadTx::new(self.engine_arc(), self.tx_ids.next()))
     }
 
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
-        Ok(ClawWriteTx::new(self.engine_arc()))
+        if self.engine.is_read_only() {
+            return Err(DatabaseError::Other("read-only mode".into()));
+        }
+        Ok(ClawWriteTx::new(self.engine_arc(), Arc::clone(&self.tx_ids)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Migration
+// ---------------------------------------------------------------------------
+
+/// Copies one table of a source transaction into the engine, raw bytes only.
+struct TableCopier<'a, TX> {
+    tx: &'a TX,
+    engine: &'a ClawStoreEngine,
+}
+
+impl<TX: DbTx> TableViewer<usize> for TableCopier<'_, TX> {
+    type Error = DatabaseError;
+
+    fn view<T: Table>(&self) -> Result<usize, DatabaseError> {
+        let mut cursor = match self.tx.cursor_read::<RawTable<T>>() {
+            Ok(cursor) => cursor,
+            Err(e) => {
+                tracing::debug!(table = T::NAME, error = %e, "table missing in source, skipping");
+                return Ok(0);
+            }
+        };
+        let mut copied = 0;
+        for row in cursor.walk(None)? {
+            let (key, value) = row?;
+            let (key_bytes, val_bytes): (&[u8], &[u8]) = (key.raw_key(), value.raw_value());
+            let stored = storage_key::<T>(key_bytes, val_bytes);
+            self.engine.put_fast(&stored, val_bytes).map_err(|e| {
+                DatabaseError::Other(e.to_string())
+            })?;
+            copied += 1;
+        }
+        Ok(copied)
     }
 }
 
+// ---------------------------------------------------------------------------
+// JSON dump helpers
+// ---------------------------------------------------------------------------
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, DatabaseError> {
+    if hex.len() % 2 != 0 {
+        return Err(DatabaseError::Other(format!("odd-length hex string: {}", hex)));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|e| DatabaseError::Other(format!("invalid hex {:?}: {}", hex, e)))
+        })
+        .collect()
+}
+
+/// String field `name` of a dumped entry.
+fn json_str<'a>(entry: &'a serde_json::Value, name: &str) -> Result<&'a str, DatabaseError> {
+    entry.get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| DatabaseError::Other(format!("dump entry has no string field {:?}", name)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_hex_roundtrip() {
+        let bytes = [0x00, 0x0f, 0xa5, 0xff];
+        assert_eq!(to_hex(&bytes), "000fa5ff");
+        assert_eq!(from_hex("000fa5ff").unwrap(), bytes);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+
     #[test]
     fn test_open_and_debug() {
         let dir = TempDir::new().unwrap();
@@ -90,4 +394,56 @@ mod tests {
         let _tx = db.tx().unwrap();
         let _tx_mut = db.tx_mut().unwrap();
     }
+
+    #[test]
+    fn test_table_stats_by_name() {
+        let dir = TempDir::new().unwrap();
+        let db = ClawDatabase::open(dir.path(), ClawConfig::default()).unwrap();
+        let prefixed = crate::table_ids::prefixed_key_for_name("Bytecodes", b"code");
+        db.engine().put(&prefixed, &[0u8; 24]).unwrap();
+
+        let stats = db.table_stats_by_name("Bytecodes");
+        assert_eq!(stats.entry_count, 1);
+        assert_eq!(stats.max_key_len, 4);
+        assert_eq!(stats.avg_val_len, 24.0);
+        assert_eq!(db.table_stats_by_name("Headers").entry_count, 0);
+    }
+
+    #[test]
+    fn test_verify_consistency_reports_checksum_error() {
+        let dir = TempDir::new().unwrap();
+        let db = ClawDatabase::open(dir.path(), ClawConfig::default()).unwrap();
+        db.engine().put(b"k", b"value").unwrap();
+        db.engine().start_trickle().unwrap();
+        db.engine().stop_trickle();
+        assert!(db.verify_consistency().unwrap().is_clean());
+
+        let data_file = clawstore_core::datafile::latest_data_file(&dir.path().join("data")).unwrap();
+        let mut bytes = std::fs::read(&data_file).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(&data_file, bytes).unwrap();
+
+        let report = db.verify_consistency().unwrap();
+        assert_eq!(report.checksum_errors, 1);
+        assert_eq!(report.ram_entries, 1);
+    }
+
+    #[test]
+    fn test_open_read_only() {
+        let dir = TempDir::new().unwrap();
+        {
+            let db = ClawDatabase::open(dir.path(), ClawConfig::default()).unwrap();
+            let prefixed = crate::table_ids::prefixed_key_for_name("Bytecodes", b"code");
+            db.engine().put(&prefixed, b"bytes").unwrap();
+        }
+
+        let db = ClawDatabase::open_read_only(dir.path()).unwrap();
+        assert_eq!(db.mode(), OpenMode::ReadOnly);
+        assert!(db.tx().is_ok());
+        assert_eq!(db.table_stats_by_name("Bytecodes").entry_count, 1);
+        match db.tx_mut() {
+            Err(DatabaseError::Other(msg)) => assert_eq!(msg, "read-only mode"),
+            other => panic!("expected read-only error, got {:?}", other.map(|_| ())),
+        }
+    }
 }
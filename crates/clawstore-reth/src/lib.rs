@@ -17,6 +17,9 @@
 pub mod db;
 pub mod tx;
 pub mod cursor;
+pub mod pool;
 pub mod table_ids;
 
 pub use db::ClawDatabase;
+pub use pool::{PooledReadTx, ReadTxPool};
+pub use tx::TxStats;
@@ -0,0 +1,189 @@
+//! Pooled read transactions with warm table snapshots.
+//!
+//! Every `cursor_read` builds a sorted snapshot of its table by scanning the
+//! whole engine, which dominates short-lived read transactions. The pool
+//! keeps one shared snapshot per configured table (`Config::pre_warm_tables`)
+//! and hands it to cursors of pooled transactions for as long as the engine
+//! has seen no write since it was built. After a write, the next checkout
+//! rebuilds the snapshots once for all pooled transactions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use clawstore_core::ClawStoreEngine;
+
+use crate::cursor::snapshot_table;
+use crate::table_ids::table_id_for_name;
+use crate::tx::{ClawReadTx, TxIds};
+
+/// Sorted table entries, keys without the table prefix byte.
+pub(crate) type TableSnapshot = Arc<BTreeMap<Vec<u8>, Vec<u8>>>;
+
+/// Table snapshots taken at one engine write generation.
+#[derive(Default)]
+pub(crate) struct WarmTables {
+    /// `ClawStoreEngine::write_generation` read before the snapshots were built
+    generation: u64,
+    tables: HashMap<u8, TableSnapshot>,
+}
+
+impl WarmTables {
+    fn build(engine: &ClawStoreEngine, table_ids: &[u8]) -> Self {
+        // Read the generation first: a write racing with the scans then
+        // makes these snapshots look stale, never fresher than they are.
+        let generation = engine.write_generation();
+        let tables = table_ids
+            .iter()
+            .map(|&id| (id, Arc::new(snapshot_table(engine, id))))
+            .collect();
+        Self { generation, tables }
+    }
+
+    /// Snapshot of `table_id`, if warmed and still current for `engine`.
+    pub(crate) fn get(&self, engine: &ClawStoreEngine, table_id: u8) -> Option<TableSnapshot> {
+        if engine.write_generation() != self.generation {
+            return None;
+        }
+        self.tables.get(&table_id).cloned()
+    }
+}
+
+/// Pool of reusable read transactions sharing warm table snapshots.
+///
+/// Created by `ClawDatabase::open` with `Config::read_tx_pool_size` idle
+/// transactions; use `ClawDatabase::pooled_tx` to check one out.
+pub struct ReadTxPool {
+    engine: Arc<ClawStoreEngine>,
+    /// The database's transaction IDs, one assigned per checkout
+    ids: Arc<TxIds>,
+    /// Transactions waiting to be checked out
+    idle: Mutex<Vec<ClawReadTx>>,
+    /// Idle transactions kept; extra returns are dropped
+    max_idle: usize,
+    /// Table IDs from `Config::pre_warm_tables`
+    warm_table_ids: Vec<u8>,
+    /// Current shared snapshots, rebuilt on checkout when stale
+    warm: Mutex<Arc<WarmTables>>,
+    /// Checkouts served from `idle`
+    reused: AtomicU64,
+}
+
+impl ReadTxPool {
+    pub(crate) fn new(
+        engine: Arc<ClawStoreEngine>,
+        ids: Arc<TxIds>,
+        max_idle: usize,
+        pre_warm_tables: &[String],
+    ) -> Self {
+        let mut warm_table_ids: Vec<u8> = pre_warm_tables.iter().map(|name| table_id_for_name(name)).collect();
+        warm_table_ids.sort_unstable();
+        warm_table_ids.dedup();
+
+        let warm = WarmTables::build(&engine, &warm_table_ids);
+        let idle = (0..max_idle).map(|_| ClawReadTx::new(Arc::clone(&engine), 0)).collect();
+        Self {
+            engine,
+            ids,
+            idle: Mutex::new(idle),
+            max_idle,
+            warm_table_ids,
+            warm: Mutex::new(Arc::new(warm)),
+            reused: AtomicU64::new(0),
+        }
+    }
+
+    /// Take a transaction, reusing an idle one when available.
+    pub fn checkout(self: &Arc<Self>) -> PooledReadTx {
+        let reused = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut tx = match reused {
+            Some(tx) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                tx
+            }
+            None => ClawReadTx::new(Arc::clone(&self.engine), 0),
+        };
+        tx.set_snapshot_id(self.ids.next());
+        tx.set_warm_tables(self.current_warm_tables());
+        PooledReadTx { tx: Some(tx), pool: Arc::clone(self) }
+    }
+
+    /// Idle transactions currently in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Checkouts served by an idle transaction rather than a new one.
+    pub fn reuse_count(&self) -> u64 {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    /// Shared warm snapshots, rebuilt first if the engine was written since.
+    fn current_warm_tables(&self) -> Option<Arc<WarmTables>> {
+        if self.warm_table_ids.is_empty() {
+            return None;
+        }
+        let mut warm = self.warm.lock().unwrap_or_else(|e| e.into_inner());
+        if warm.generation != self.engine.write_generation() {
+            *warm = Arc::new(WarmTables::build(&self.engine, &self.warm_table_ids));
+        }
+        Some(Arc::clone(&warm))
+    }
+
+    fn release(&self, mut tx: ClawReadTx) {
+        tx.reset_for_pool();
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < self.max_idle {
+            idle.push(tx);
+        }
+    }
+}
+
+impl std::fmt::Debug for ReadTxPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadTxPool")
+            .field("idle", &self.idle_count())
+            .field("max_idle", &self.max_idle)
+            .field("warm_tables", &self.warm_table_ids.len())
+            .finish()
+    }
+}
+
+/// Read transaction checked out of a `ReadTxPool`; returns to it on drop.
+///
+/// Derefs to `ClawReadTx`, so all `DbTx` methods except the consuming
+/// `commit`/`abort` are available — dropping it is the read-only commit.
+pub struct PooledReadTx {
+    /// Always `Some` until dropped
+    tx: Option<ClawReadTx>,
+    pool: Arc<ReadTxPool>,
+}
+
+impl Deref for PooledReadTx {
+    type Target = ClawReadTx;
+
+    fn deref(&self) -> &ClawReadTx {
+        self.tx.as_ref().expect("pooled transaction used after release")
+    }
+}
+
+impl DerefMut for PooledReadTx {
+    fn deref_mut(&mut self) -> &mut ClawReadTx {
+        self.tx.as_mut().expect("pooled transaction used after release")
+    }
+}
+
+impl Drop for PooledReadTx {
+    fn drop(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            self.pool.release(tx);
+        }
+    }
+}
+
+impl std::fmt::Debug for PooledReadTx {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledReadTx").finish()
+    }
+}
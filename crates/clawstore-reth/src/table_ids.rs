@@ -2,58 +2,223 @@
 //!
 //! Each Reth table gets a unique prefix byte, mapping the strongly-typed
 //! table system to ClawStore's flat namespace.
+//!
+//! Custom tables added on top of Reth can claim a fixed ID at startup via
+//! [`TableRegistry`]; anything else falls back to a hash-based ID.
 
 use reth_db_api::table::Table;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// Built-in Reth tables and their fixed prefix bytes.
+const BUILTIN_TABLES: [(&str, u8); 28] = [
+    ("CanonicalHeaders", 0x01),
+    ("HeaderNumbers", 0x02),
+    ("Headers", 0x03),
+    ("BlockBodyIndices", 0x04),
+    ("BlockOmmers", 0x05),
+    ("BlockWithdrawals", 0x06),
+    ("Transactions", 0x07),
+    ("TransactionHashNumbers", 0x08),
+    ("TransactionBlocks", 0x09),
+    ("TransactionSenders", 0x0A),
+    ("Receipts", 0x0B),
+    ("PlainAccountState", 0x0C),
+    ("PlainStorageState", 0x0D),
+    ("Bytecodes", 0x0E),
+    ("AccountsTrie", 0x0F),
+    ("StoragesTrie", 0x10),
+    ("HashedAccounts", 0x11),
+    ("HashedStorages", 0x12),
+    ("AccountsHistory", 0x13),
+    ("StoragesHistory", 0x14),
+    ("AccountChangeSets", 0x15),
+    ("StorageChangeSets", 0x16),
+    ("StageCheckpoints", 0x17),
+    ("StageCheckpointProgresses", 0x18),
+    ("PruneCheckpoints", 0x19),
+    ("VersionHistory", 0x1A),
+    ("ChainState", 0x1B),
+    ("Metadata", 0x1C),
+];
+
+/// IDs given to unknown tables by the hash-based fallback in [`table_id_for_name`].
+const HASH_FALLBACK_IDS: std::ops::RangeInclusive<u8> = 0xE0..=0xFF;
+
+/// Error returned when a custom table cannot be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationError {
+    /// The ID is already taken by a built-in or previously registered table
+    IdAlreadyUsed { id: u8, existing: String },
+    /// The name is already a built-in or previously registered table
+    NameAlreadyUsed { name: String, existing_id: u8 },
+    /// The ID is 0x00 or in the 0xE0..=0xFF range handed out to unknown tables
+    ReservedId { id: u8 },
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::IdAlreadyUsed { id, existing } => {
+                write!(f, "Table ID 0x{:02x} already used by {}", id, existing)
+            }
+            RegistrationError::NameAlreadyUsed { name, existing_id } => {
+                write!(f, "Table {} already registered with ID 0x{:02x}", name, existing_id)
+            }
+            RegistrationError::ReservedId { id } => {
+                write!(f, "Table ID 0x{:02x} is reserved", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+/// Runtime registry of user-defined table IDs.
+///
+/// Register custom tables once at startup, before opening any transaction
+/// that touches them, so every key gets the same deterministic prefix.
+#[derive(Debug, Default)]
+pub struct TableRegistry {
+    by_name: HashMap<String, u8>,
+}
+
+impl TableRegistry {
+    /// The process-wide registry consulted by [`table_id_for_name`].
+    ///
+    /// Read-mostly: lookups take the read lock, and only for names that are
+    /// not built-in tables.
+    pub fn global() -> &'static RwLock<TableRegistry> {
+        static REGISTRY: OnceLock<RwLock<TableRegistry>> = OnceLock::new();
+        REGISTRY.get_or_init(|| RwLock::new(TableRegistry::default()))
+    }
+
+    /// Assign `id` to the table `name`.
+    ///
+    /// Fails if either the name or the ID is already taken, including by
+    /// one of the built-in Reth tables, or if the ID is reserved: 0x00, or
+    /// 0xE0..=0xFF, where unregistered tables land by hash.
+    pub fn register(&mut self, name: &str, id: u8) -> Result<(), RegistrationError> {
+        if id == 0x00 || HASH_FALLBACK_IDS.contains(&id) {
+            return Err(RegistrationError::ReservedId { id });
+        }
+        if let Some(existing_id) = builtin_table_id(name).or_else(|| self.get(name)) {
+            return Err(RegistrationError::NameAlreadyUsed { name: name.to_string(), existing_id });
+        }
+        let builtin_owner = BUILTIN_TABLES.iter().find(|(_, tid)| *tid == id).map(|(n, _)| *n);
+        let registered_owner = self.by_name.iter().find(|(_, tid)| **tid == id).map(|(n, _)| n.as_str());
+        if let Some(existing) = builtin_owner.or(registered_owner) {
+            return Err(RegistrationError::IdAlreadyUsed { id, existing: existing.to_string() });
+        }
+        self.by_name.insert(name.to_string(), id);
+        Ok(())
+    }
+
+    /// Look up the ID of a registered custom table.
+    pub fn get(&self, name: &str) -> Option<u8> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Look up the registered custom table that owns `id`.
+    pub fn name_for_id(&self, id: u8) -> Option<&str> {
+        self.by_name.iter().find(|(_, tid)| **tid == id).map(|(name, _)| name.as_str())
+    }
+}
+
+/// Names of the built-in Reth tables, in table ID order.
+pub(crate) fn builtin_table_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_TABLES.iter().map(|(name, _)| *name)
+}
+
+/// Fixed ID of a built-in Reth table, if `name` is one.
+fn builtin_table_id(name: &str) -> Option<u8> {
+    BUILTIN_TABLES.iter().find(|(n, _)| *n == name).map(|(_, id)| *id)
+}
 
 /// Get the table ID prefix byte for a Reth table by name.
 ///
-/// Returns a unique u8 for each known Reth table. Unknown tables
-/// get a hash-based ID to avoid collisions.
+/// Checks the built-in Reth tables first, without locking, then the
+/// global [`TableRegistry`]. Unknown tables get a hash-based ID to avoid
+/// collisions.
 pub fn table_id_for_name(name: &str) -> u8 {
+    if let Some(id) = builtin_table_id(name) {
+        return id;
+    }
+    let registered = TableRegistry::global()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name);
+    if let Some(id) = registered {
+        return id;
+    }
+
+    // Hash-based fallback for unknown tables
+    let mut hash: u8 = 0xF0;
+    for b in name.bytes() {
+        hash = hash.wrapping_add(b);
+    }
+    // Ensure we're in HASH_FALLBACK_IDS, which registration rejects
+    0xE0 | (hash & 0x1F)
+}
+
+/// Name of the table with prefix byte `id`, the reverse of
+/// [`table_id_for_name`].
+///
+/// Checks the built-in Reth tables, then the global [`TableRegistry`].
+/// IDs that only unregistered tables reach through the hash-based
+/// fallback cannot be reversed and yield None.
+pub fn table_name_for_id(id: u8) -> Option<String> {
+    if let Some((name, _)) = BUILTIN_TABLES.iter().find(|(_, tid)| *tid == id) {
+        return Some(name.to_string());
+    }
+    TableRegistry::global()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .name_for_id(id)
+        .map(str::to_string)
+}
+
+/// Encoded subkey length for Reth's DupSort tables.
+///
+/// Duplicates are stored under `[encoded_key][subkey]`, where the subkey is
+/// the leading bytes of the compressed value (as in MDBX, where dup values
+/// sort by their subkey prefix). Returns None for unknown tables, in which
+/// case the whole compressed value is used.
+pub fn dup_subkey_len(name: &str) -> Option<usize> {
     match name {
-        "CanonicalHeaders" => 0x01,
-        "HeaderNumbers" => 0x02,
-        "Headers" => 0x03,
-        "BlockBodyIndices" => 0x04,
-        "BlockOmmers" => 0x05,
-        "BlockWithdrawals" => 0x06,
-        "Transactions" => 0x07,
-        "TransactionHashNumbers" => 0x08,
-        "TransactionBlocks" => 0x09,
-        "TransactionSenders" => 0x0A,
-        "Receipts" => 0x0B,
-        "PlainAccountState" => 0x0C,
-        "PlainStorageState" => 0x0D,
-        "Bytecodes" => 0x0E,
-        "AccountsTrie" => 0x0F,
-        "StoragesTrie" => 0x10,
-        "HashedAccounts" => 0x11,
-        "HashedStorages" => 0x12,
-        "AccountsHistory" => 0x13,
-        "StoragesHistory" => 0x14,
-        "AccountChangeSets" => 0x15,
-        "StorageChangeSets" => 0x16,
-        "StageCheckpoints" => 0x17,
-        "StageCheckpointProgresses" => 0x18,
-        "PruneCheckpoints" => 0x19,
-        "VersionHistory" => 0x1A,
-        "ChainState" => 0x1B,
-        "Metadata" => 0x1C,
-        _ => {
-            // Hash-based fallback for unknown tables
-            let mut hash: u8 = 0xF0;
-            for b in name.bytes() {
-                hash = hash.wrapping_add(b);
-            }
-            // Ensure we're in the 0xE0..=0xFF range to avoid collisions
-            0xE0 | (hash & 0x1F)
-        }
+        "PlainStorageState" | "HashedStorages" | "StorageChangeSets" => Some(32), // B256 slot
+        "AccountChangeSets" => Some(20), // Address
+        "StoragesTrie" => Some(65), // StoredNibblesSubKey
+        _ => None,
+    }
+}
+
+/// Subkey a DupSort duplicate with `compressed_value` is stored under:
+/// the first [`dup_subkey_len`] bytes of the value.
+pub fn dup_subkey<'a>(name: &str, compressed_value: &'a [u8]) -> &'a [u8] {
+    let subkey_len = dup_subkey_len(name).unwrap_or(compressed_value.len()).min(compressed_value.len());
+    &compressed_value[..subkey_len]
+}
+
+/// Engine key of an entry of `T`: `[table_id][encoded_key_bytes]`, followed
+/// by the value's subkey for DupSort tables.
+pub fn storage_key<T: Table>(key_bytes: &[u8], compressed_value: &[u8]) -> Vec<u8> {
+    let mut stored = prefixed_key::<T>(key_bytes);
+    if T::DUPSORT {
+        stored.extend_from_slice(dup_subkey(T::NAME, compressed_value));
     }
+    stored
 }
 
 /// Build a prefixed key: `[table_id][encoded_key_bytes]`
 pub fn prefixed_key<T: Table>(key_bytes: &[u8]) -> Vec<u8> {
-    let table_id = table_id_for_name(T::NAME);
+    prefixed_key_for_name(T::NAME, key_bytes)
+}
+
+/// Build a prefixed key from a table name, honouring registered custom tables.
+pub fn prefixed_key_for_name(name: &str, key_bytes: &[u8]) -> Vec<u8> {
+    let table_id = table_id_for_name(name);
     let mut prefixed = Vec::with_capacity(1 + key_bytes.len());
     prefixed.push(table_id);
     prefixed.extend_from_slice(key_bytes);
@@ -101,4 +266,49 @@ mod tests {
         assert_eq!(prefixed[0], 0x0C);
         assert_eq!(strip_prefix(&prefixed), key.as_slice());
     }
+
+    #[test]
+    fn test_register_custom_table() {
+        TableRegistry::global().write().unwrap().register("ClawTestCustomTable", 0x80).unwrap();
+
+        assert_eq!(table_id_for_name("ClawTestCustomTable"), 0x80);
+        assert_eq!(table_id_for_name("ClawTestCustomTable"), 0x80);
+        assert_eq!(prefixed_key_for_name("ClawTestCustomTable", b"k"), vec![0x80, b'k']);
+    }
+
+    #[test]
+    fn test_duplicate_registration_fails() {
+        let mut registry = TableRegistry::default();
+        registry.register("ClawTestDuplicate", 0x81).unwrap();
+
+        assert!(matches!(
+            registry.register("ClawTestDuplicate", 0x82),
+            Err(RegistrationError::NameAlreadyUsed { existing_id: 0x81, .. })
+        ));
+        assert!(matches!(
+            registry.register("ClawTestOther", 0x81),
+            Err(RegistrationError::IdAlreadyUsed { id: 0x81, .. })
+        ));
+        assert!(matches!(
+            registry.register("ClawTestOther", 0x0C),
+            Err(RegistrationError::IdAlreadyUsed { .. })
+        ));
+        assert!(matches!(
+            registry.register("Headers", 0x83),
+            Err(RegistrationError::NameAlreadyUsed { existing_id: 0x03, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reserved_ids_rejected() {
+        let mut registry = TableRegistry::default();
+        for id in [0x00, 0xE0, 0xF3, 0xFF] {
+            assert_eq!(
+                registry.register("ClawTestReserved", id),
+                Err(RegistrationError::ReservedId { id })
+            );
+        }
+        registry.register("ClawTestReserved", 0xDF).unwrap();
+        assert_eq!(registry.get("ClawTestReserved"), Some(0xDF));
+    }
 }
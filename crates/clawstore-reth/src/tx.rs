@@ -3,8 +3,12 @@
 //! Read transactions serve data directly from the ClawStore engine.
 //! Write transactions buffer changes and flush to the engine on commit.
 
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+use parking_lot::Mutex;
+
 use reth_db_api::{
     table::{Compress, DupSort, Encode, Table, TableImporter},
     transaction::{DbTx, DbTxMut},
@@ -12,10 +16,53 @@ use reth_db_api::{
 };
 
 
-use clawstore_core::ClawStoreEngine;
+use clawstore_core::{ClawStoreEngine, CrossTableWrite};
 
 use crate::cursor::{ClawCursor, ClawDupCursor, ClawCursorMut, ClawDupCursorMut};
-use crate::table_ids::table_id_for_name;
+use crate::pool::{TableSnapshot, WarmTables};
+use crate::table_ids::{dup_subkey, prefixed_key, storage_key, table_id_for_name, table_name_for_id};
+
+// ---------------------------------------------------------------------------
+// Transaction IDs
+// ---------------------------------------------------------------------------
+
+/// Transaction ID counters shared by every transaction of one `ClawDatabase`.
+///
+/// IDs start at 1 and increase with every transaction started, read or
+/// write, so they order transactions by start time.
+#[derive(Debug, Default)]
+pub(crate) struct TxIds {
+    /// Last ID handed out
+    last_assigned: AtomicU64,
+    /// ID of the most recently started write transaction
+    last_write_started: AtomicU64,
+    /// Highest ID of a committed write transaction
+    latest_committed: AtomicU64,
+}
+
+impl TxIds {
+    pub(crate) fn next(&self) -> u64 {
+        self.last_assigned.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn begin_write(&self) -> u64 {
+        let id = self.next();
+        self.last_write_started.fetch_max(id, Ordering::SeqCst);
+        id
+    }
+
+    /// Record the commit of write `id`; returns the ID of a write
+    /// transaction started after it, if any.
+    fn commit_write(&self, id: u64) -> Option<u64> {
+        self.latest_committed.fetch_max(id, Ordering::SeqCst);
+        let newer = self.last_write_started.load(Ordering::SeqCst);
+        (newer > id).then_some(newer)
+    }
+
+    pub(crate) fn latest_committed(&self) -> u64 {
+        self.latest_committed.load(Ordering::SeqCst)
+    }
+}
 
 // ---------------------------------------------------------------------------
 // Read-only transaction
@@ -24,18 +71,85 @@ use crate::table_ids::table_id_for_name;
 /// Read-only transaction backed by ClawStore.
 ///
 /// Reads go directly to the engine's RAM HashMap — no snapshot needed
-/// because ClawStore's RwLock provides consistent reads.
+/// because ClawStore's RwLock provides consistent reads. Transactions from
+/// `ClawDatabase::tx_with_start_snapshot` additionally hold a copy of the
+/// store taken at creation, for cursors that must not see later writes.
 pub struct ClawReadTx {
     engine: Arc<ClawStoreEngine>,
+    /// Position of this transaction in the database's start order
+    snapshot_id: u64,
     _long_read_safety: bool,
+    /// Whole-store copy (prefixed keys) taken when the transaction started
+    start_snapshot: Option<Arc<BTreeMap<Vec<u8>, Vec<u8>>>>,
+    /// Table snapshots shared by a `ReadTxPool`, used while still current
+    warm_tables: Option<Arc<WarmTables>>,
 }
 
 impl ClawReadTx {
-    pub(crate) fn new(engine: Arc<ClawStoreEngine>) -> Self {
-        Self { engine, _long_read_safety: true }
+    pub(crate) fn new(engine: Arc<ClawStoreEngine>, snapshot_id: u64) -> Self {
+        Self { engine, snapshot_id, _long_read_safety: true, start_snapshot: None, warm_tables: None }
+    }
+
+    /// ID assigned when the transaction started; later transactions of the
+    /// same database have higher IDs.
+    pub fn snapshot_id(&self) -> u64 {
+        self.snapshot_id
+    }
+
+    pub(crate) fn set_snapshot_id(&mut self, snapshot_id: u64) {
+        self.snapshot_id = snapshot_id;
+    }
+
+    pub(crate) fn set_warm_tables(&mut self, warm_tables: Option<Arc<WarmTables>>) {
+        self.warm_tables = warm_tables;
+    }
+
+    /// Clear per-checkout state before the transaction goes back to its pool.
+    pub(crate) fn reset_for_pool(&mut self) {
+        self._long_read_safety = true;
+        self.start_snapshot = None;
+        self.warm_tables = None;
+    }
+
+    /// Warm snapshot of `T` from the pool, if there is a current one.
+    fn warm_snapshot<T: Table>(&self) -> Option<TableSnapshot> {
+        self.warm_tables.as_ref()?.get(&self.engine, table_id_for_name(T::NAME))
+    }
+
+    /// Start a transaction that copies the whole store under one read lock.
+    ///
+    /// O(n) in the store size — only use it when snapshot-at-start cursors
+    /// are needed.
+    pub(crate) fn with_start_snapshot(engine: Arc<ClawStoreEngine>, snapshot_id: u64) -> Self {
+        let snapshot: BTreeMap<Vec<u8>, Vec<u8>> = engine
+            .read_view()
+            .iter_all_entries_unsorted()
+            .map(|(k, v)| (k.to_vec(), v.to_vec()))
+            .collect();
+        Self {
+            engine,
+            snapshot_id,
+            _long_read_safety: true,
+            start_snapshot: Some(Arc::new(snapshot)),
+            warm_tables: None,
+        }
+    }
+
+    /// Cursor over table `T` as it was when this transaction started.
+    ///
+    /// Unlike `cursor_read`, which snapshots the table when the cursor is
+    /// created, every cursor from this method sees the same start-of-tx
+    /// state and never observes later writes. Requires a transaction from
+    /// `ClawDatabase::tx_with_start_snapshot`.
+    pub fn cursor_read_snapshot_at_tx_start<T: Table>(&self) -> Result<ClawCursor<T>, DatabaseError> {
+        let snapshot = self.start_snapshot.as_ref().ok_or_else(|| {
+            DatabaseError::Other("transaction has no start snapshot; use tx_with_start_snapshot".into())
+        })?;
+        Ok(ClawCursor::from_store_snapshot(snapshot))
     }
 
     /// Get the raw value for a table-prefixed key from the engine.
+    #[cfg(test)]
     pub(crate) fn raw_get(&self, table_id: u8, key_bytes: &[u8]) -> Result<Option<Vec<u8>>, DatabaseError> {
         let mut prefixed = Vec::with_capacity(1 + key_bytes.len());
         prefixed.push(table_id);
@@ -48,11 +162,205 @@ impl ClawReadTx {
     pub(crate) fn engine_arc(&self) -> Arc<ClawStoreEngine> {
         Arc::clone(&self.engine)
     }
+
+    /// Look up one key in each of two tables under a single engine read lock.
+    ///
+    /// Matches Reth's common header-then-body lookup pattern: both values
+    /// come from the same consistent view of the store.
+    pub fn get_two<T1: Table, T2: Table>(
+        &self,
+        key1: T1::Key,
+        key2: T2::Key,
+    ) -> Result<(Option<T1::Value>, Option<T2::Value>), DatabaseError> {
+        let lookups = [
+            TableLookup::new::<T1>(key1.encode().as_ref()),
+            TableLookup::new::<T2>(key2.encode().as_ref()),
+        ];
+        let mut raw = get_lookups(&self.engine, &lookups)?;
+        let second = raw.pop().flatten();
+        let first = raw.pop().flatten();
+        Ok((decompress_value::<T1>(first)?, decompress_value::<T2>(second)?))
+    }
+
+    /// Look up several keys of the same table under a single engine read lock.
+    ///
+    /// Results are returned in the same order as `keys`.
+    pub fn get_many<T: Table>(&self, keys: &[T::Key]) -> Result<Vec<Option<T::Value>>, DatabaseError> {
+        let lookups: Vec<TableLookup> = keys.iter()
+            .map(|k| TableLookup::new::<T>(k.clone().encode().as_ref()))
+            .collect();
+        let raw = get_lookups(&self.engine, &lookups)?;
+        raw.into_iter().map(decompress_value::<T>).collect()
+    }
+
+    /// `get_many` returning each key next to its value.
+    ///
+    /// All keys are looked up under a single engine read lock and results
+    /// keep the input order, missing keys included as `None`.
+    pub fn get_batch<T: Table>(
+        &self,
+        keys: impl IntoIterator<Item = T::Key>,
+    ) -> Result<Vec<(T::Key, Option<T::Value>)>, DatabaseError> {
+        let keys: Vec<T::Key> = keys.into_iter().collect();
+        let values = self.get_many::<T>(&keys)?;
+        Ok(keys.into_iter().zip(values).collect())
+    }
+
+    /// `get_batch` without the keys, for callers that already hold them.
+    pub fn get_batch_values_only<T: Table>(
+        &self,
+        keys: impl IntoIterator<Item = T::Key>,
+    ) -> Result<Vec<Option<T::Value>>, DatabaseError> {
+        let lookups: Vec<TableLookup> = keys.into_iter()
+            .map(|k| TableLookup::new::<T>(k.encode().as_ref()))
+            .collect();
+        let raw = get_lookups(&self.engine, &lookups)?;
+        raw.into_iter().map(decompress_value::<T>).collect()
+    }
+
+    /// Whether `key` is present in table `T`, without fetching or
+    /// decompressing its value.
+    pub fn exists<T: Table>(&self, key: T::Key) -> Result<bool, DatabaseError> {
+        self.exists_by_encoded_key::<T>(&key.encode())
+    }
+
+    /// `exists` for an already-encoded key.
+    pub fn exists_by_encoded_key<T: Table>(
+        &self,
+        key: &<T::Key as Encode>::Encoded,
+    ) -> Result<bool, DatabaseError> {
+        TableLookup::new::<T>(key.as_ref()).exists(&self.engine)
+    }
+
+    /// Every entry of every table as `(table_id, encoded_key, compressed_value)`,
+    /// sorted by table ID, then key.
+    ///
+    /// The store is copied under a single engine read lock, so the walk is
+    /// one consistent view; later writes are not seen. O(n) in the store
+    /// size, for full exports and consistency checks.
+    pub fn walk_all_tables(&self) -> impl Iterator<Item = (u8, Vec<u8>, Vec<u8>)> + '_ {
+        let mut entries: Vec<(u8, Vec<u8>, Vec<u8>)> = {
+            let view = self.engine.read_view();
+            view.iter_all_entries_unsorted()
+                .filter_map(|(key, value)| {
+                    let (&table_id, key_bytes) = key.split_first()?;
+                    Some((table_id, key_bytes.to_vec(), value.to_vec()))
+                })
+                .collect()
+        };
+        entries.sort_unstable_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        entries.into_iter()
+    }
+
+    /// Names of the tables holding at least one entry, in table ID order.
+    ///
+    /// Tables whose ID cannot be mapped back to a name (see
+    /// [`table_name_for_id`]) are left out.
+    pub fn table_names_with_data(&self) -> Vec<String> {
+        let mut present = [false; 256];
+        for key in self.engine.read_view().iter_keys_unsorted() {
+            if let Some(&table_id) = key.first() {
+                present[table_id as usize] = true;
+            }
+        }
+        (0..=u8::MAX)
+            .filter(|&id| present[id as usize])
+            .filter_map(table_name_for_id)
+            .collect()
+    }
+}
+
+/// Decompress an optional raw value into the table's value type.
+fn decompress_value<T: Table>(raw: Option<Vec<u8>>) -> Result<Option<T::Value>, DatabaseError> {
+    match raw {
+        Some(bytes) => {
+            let val = <T::Value as reth_db_api::table::Decompress>::decompress(&bytes)?;
+            Ok(Some(val))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Lookup of one table key. DupSort tables store each duplicate under
+/// `[table_id][encoded_key][subkey]` (as cursors do), so their lookups
+/// resolve to the first duplicate, the one MDBX returns.
+struct TableLookup {
+    /// `[table_id][encoded_key]`
+    prefixed: Vec<u8>,
+    /// Table name, for DupSort tables only
+    dup_table: Option<&'static str>,
+}
+
+impl TableLookup {
+    fn new<T: Table>(encoded_key: &[u8]) -> Self {
+        Self { prefixed: prefixed_key::<T>(encoded_key), dup_table: T::DUPSORT.then_some(T::NAME) }
+    }
+
+    /// Whether the engine entry `(key, value)` is stored for this table key.
+    fn matches(&self, key: &[u8], value: &[u8]) -> bool {
+        match self.dup_table {
+            None => key == self.prefixed.as_slice(),
+            Some(name) => key
+                .strip_prefix(self.prefixed.as_slice())
+                .is_some_and(|subkey| subkey == dup_subkey(name, value)),
+        }
+    }
+
+    /// Engine keys and values of every duplicate of this key, in subkey
+    /// order. Empty for non-DupSort tables.
+    fn duplicates(&self, engine: &ClawStoreEngine) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let Some(name) = self.dup_table else { return Vec::new() };
+        engine.prefix_scan(&self.prefixed)
+            .into_iter()
+            .filter(|(subkey, value)| subkey.as_slice() == dup_subkey(name, value))
+            .map(|(subkey, value)| ([self.prefixed.as_slice(), subkey.as_slice()].concat(), value))
+            .collect()
+    }
+
+    /// Stored value, or the first duplicate's for DupSort tables.
+    fn get(&self, engine: &ClawStoreEngine) -> Result<Option<Vec<u8>>, DatabaseError> {
+        if self.dup_table.is_some() {
+            return Ok(self.duplicates(engine).into_iter().next().map(|(_, value)| value));
+        }
+        engine.get(&self.prefixed).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })
+    }
+
+    fn exists(&self, engine: &ClawStoreEngine) -> Result<bool, DatabaseError> {
+        if self.dup_table.is_some() {
+            return Ok(!self.duplicates(engine).is_empty());
+        }
+        Ok(engine.contains_key(&self.prefixed))
+    }
+}
+
+/// Values for several lookups under a single engine read lock, in order.
+///
+/// Lookups into DupSort tables need the first duplicate of each key, which
+/// takes a pass over the whole store; without them this is `get_many`.
+fn get_lookups(engine: &ClawStoreEngine, lookups: &[TableLookup]) -> Result<Vec<Option<Vec<u8>>>, DatabaseError> {
+    if lookups.iter().all(|lookup| lookup.dup_table.is_none()) {
+        let keys: Vec<&[u8]> = lookups.iter().map(|lookup| lookup.prefixed.as_slice()).collect();
+        return engine.get_many(&keys).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        });
+    }
+    let view = engine.read_view();
+    let mut found: Vec<Option<(&[u8], &[u8])>> = vec![None; lookups.len()];
+    for (key, value) in view.iter_all_entries_unsorted() {
+        for (lookup, slot) in lookups.iter().zip(found.iter_mut()) {
+            if lookup.matches(key, value) && slot.is_none_or(|(first, _)| key < first) {
+                *slot = Some((key, value));
+            }
+        }
+    }
+    Ok(found.into_iter().map(|slot| slot.map(|(_, value)| value.to_vec())).collect())
 }
 
 impl std::fmt::Debug for ClawReadTx {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ClawReadTx").finish()
+        f.debug_struct("ClawReadTx").field("snapshot_id", &self.snapshot_id).finish()
     }
 }
 
@@ -61,31 +369,16 @@ impl DbTx for ClawReadTx {
     type DupCursor<T: DupSort> = ClawDupCursor<T>;
 
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
-        let table_id = table_id_for_name(T::NAME);
-        let encoded = key.encode();
-        let raw = self.raw_get(table_id, encoded.as_ref())?;
-        match raw {
-            Some(bytes) => {
-                let val = <T::Value as reth_db_api::table::Decompress>::decompress(&bytes)?;
-                Ok(Some(val))
-            }
-            None => Ok(None),
-        }
+        let raw = TableLookup::new::<T>(key.encode().as_ref()).get(&self.engine)?;
+        decompress_value::<T>(raw)
     }
 
     fn get_by_encoded_key<T: Table>(
         &self,
         key: &<T::Key as Encode>::Encoded,
     ) -> Result<Option<T::Value>, DatabaseError> {
-        let table_id = table_id_for_name(T::NAME);
-        let raw = self.raw_get(table_id, key.as_ref())?;
-        match raw {
-            Some(bytes) => {
-                let val = <T::Value as reth_db_api::table::Decompress>::decompress(&bytes)?;
-                Ok(Some(val))
-            }
-            None => Ok(None),
-        }
+        let raw = TableLookup::new::<T>(key.as_ref()).get(&self.engine)?;
+        decompress_value::<T>(raw)
     }
 
     fn commit(self) -> Result<(), DatabaseError> {
@@ -98,10 +391,16 @@ impl DbTx for ClawReadTx {
     }
 
     fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        if let Some(snapshot) = self.warm_snapshot::<T>() {
+            return Ok(ClawCursor::from_table_snapshot(snapshot));
+        }
         Ok(ClawCursor::new(self.engine_arc()))
     }
 
     fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        if let Some(snapshot) = self.warm_snapshot::<T>() {
+            return Ok(ClawDupCursor::from_table_snapshot(snapshot));
+        }
         Ok(ClawDupCursor::new(self.engine_arc()))
     }
 
@@ -119,28 +418,209 @@ impl DbTx for ClawReadTx {
 // Read-write transaction
 // ---------------------------------------------------------------------------
 
+/// Prefixed key and previous value of every write made through a
+/// transaction, oldest first. Shared with the transaction's mutable cursors.
+pub(crate) type UndoLog = Arc<Mutex<Vec<(Vec<u8>, Option<Vec<u8>>)>>>;
+
 /// Read-write transaction backed by ClawStore.
 ///
 /// Uses fast writes (no per-op fsync) with a single WAL sync at commit.
 /// This gives batch-level durability: all writes in a transaction are
-/// either fully committed or fully lost on crash.
+/// either fully committed or fully lost on crash. Each write first reads
+/// the key's previous value into an undo log for `abort_with_rollback`.
 pub struct ClawWriteTx {
     engine: Arc<ClawStoreEngine>,
+    /// Counters shared with the database, for conflict detection on commit
+    ids: Arc<TxIds>,
+    /// Position of this transaction in the database's start order
+    snapshot_id: u64,
+    /// Operation counters for this transaction (a Mutex, not a Cell: reth
+    /// requires transactions to be `Sync`)
+    stats: Mutex<TxStats>,
+    /// Undo entries for `abort_with_rollback`, including writes made
+    /// through this transaction's mutable cursors
+    undo_log: UndoLog,
+}
+
+/// Per-transaction operation counts, see [`ClawWriteTx::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxStats {
+    /// Successful `put` / `put_encoded` calls
+    pub puts: u64,
+    /// Successful `delete` calls, whether or not the key existed
+    pub deletes: u64,
+    /// `get` / `get_by_encoded_key` / `exists` lookups, hits and misses alike
+    pub gets: u64,
+    /// Cursors opened with `cursor_write`
+    pub cursor_reads: u64,
+    /// Prefixed key plus value bytes written by `put` / `put_encoded`
+    pub bytes_written: u64,
 }
 
 impl ClawWriteTx {
-    pub(crate) fn new(engine: Arc<ClawStoreEngine>) -> Self {
-        Self { engine }
+    pub(crate) fn new(engine: Arc<ClawStoreEngine>, ids: Arc<TxIds>) -> Self {
+        let snapshot_id = ids.begin_write();
+        Self { engine, ids, snapshot_id, stats: Mutex::default(), undo_log: UndoLog::default() }
+    }
+
+    /// ID assigned when the transaction started; later transactions of the
+    /// same database have higher IDs.
+    pub fn snapshot_id(&self) -> u64 {
+        self.snapshot_id
+    }
+
+    /// Operation counts so far in this transaction.
+    pub fn stats(&self) -> TxStats {
+        *self.stats.lock()
+    }
+
+    fn update_stats(&self, f: impl FnOnce(&mut TxStats)) {
+        f(&mut self.stats.lock());
     }
 
     pub(crate) fn engine_arc(&self) -> Arc<ClawStoreEngine> {
         Arc::clone(&self.engine)
     }
+
+    /// Record the current value of `prefixed` before this transaction
+    /// overwrites or deletes it.
+    fn record_undo(&self, prefixed: &[u8]) -> Result<(), DatabaseError> {
+        let previous = self.engine.get(prefixed).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        self.undo_log.lock().push((prefixed.to_vec(), previous));
+        Ok(())
+    }
+
+    /// Engine key `prefixed` with its stored value, if present.
+    fn get_stored(&self, prefixed: Vec<u8>) -> Result<Option<(Vec<u8>, Vec<u8>)>, DatabaseError> {
+        let value = self.engine.get(&prefixed).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        Ok(value.map(|value| (prefixed, value)))
+    }
+
+    /// Undo every write this transaction made, restoring previous values
+    /// and removing keys it inserted.
+    ///
+    /// Writes are applied to RAM as they happen, so the undo log is
+    /// replayed newest first as a single durable engine batch: after a
+    /// crash either all of it or none of it is recovered. Covers `put`,
+    /// `delete`, `put_encoded`, `put_unless_exists`, `put_cross_table`,
+    /// `import` and writes through `cursor_write` / `cursor_dup_write`
+    /// cursors (cursors still open keep their old snapshot; call
+    /// `refresh_snapshot`). Previous values are read just before each
+    /// write, so a concurrent writer to the same keys is overwritten by the
+    /// rollback. The log is cleared, so the transaction can keep writing.
+    pub fn abort_with_rollback(&self) -> Result<(), DatabaseError> {
+        let undo: Vec<(Vec<u8>, Option<Vec<u8>>)> = self.undo_log.lock().drain(..).rev().collect();
+        self.engine.apply_batch(&undo).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })
+    }
+
+    /// Write a bundle of entries spanning several tables with one WAL sync.
+    ///
+    /// Intended for Reth state bundles (`PlainAccountState`,
+    /// `PlainStorageState`, `Receipts`, ...) that would otherwise pay one
+    /// sync per table. The writes are durable when this returns.
+    pub fn put_cross_table(&self, writes: &[CrossTableWrite]) -> Result<(), DatabaseError> {
+        for write in writes {
+            self.record_undo(&write.prefixed_key())?;
+        }
+        self.engine.put_cross_table(writes).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })
+    }
+
+    /// Bulk-load entries into table `T` with a single engine batch.
+    ///
+    /// Keys are encoded and values compressed up front, then written via
+    /// `engine.put_batch` — one WAL sync and one RAM write lock for the
+    /// whole iterator. Returns the number of entries imported.
+    pub fn import<T: Table>(
+        &self,
+        iter: impl Iterator<Item = (T::Key, T::Value)>,
+    ) -> Result<usize, DatabaseError> {
+        let buffer: Vec<(Vec<u8>, Vec<u8>)> = iter
+            .map(|(key, value)| {
+                let compressed = value.compress();
+                (storage_key::<T>(key.encode().as_ref(), compressed.as_ref()), compressed.as_ref().to_vec())
+            })
+            .collect();
+        for (key, _) in &buffer {
+            self.record_undo(key)?;
+        }
+        self.engine.put_batch(&buffer).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        Ok(buffer.len())
+    }
+
+    /// Write already-encoded key bytes and already-compressed value bytes to `T`.
+    ///
+    /// Skips `encode()`/`compress()` for callers that hold raw bytes, e.g.
+    /// from cursor iteration. Same durability as `put`: synced at commit.
+    pub fn put_encoded<T: Table>(&self, encoded_key: &[u8], compressed_value: &[u8]) -> Result<(), DatabaseError> {
+        let prefixed = storage_key::<T>(encoded_key, compressed_value);
+        self.record_undo(&prefixed)?;
+        self.engine.put_fast(&prefixed, compressed_value).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        self.update_stats(|s| {
+            s.puts += 1;
+            s.bytes_written += (prefixed.len() + compressed_value.len()) as u64;
+        });
+        Ok(())
+    }
+
+    /// Insert `key` into `T` only if it is absent, like `DbCursorRW::insert`
+    /// but returning `Ok(false)` instead of an error when the key exists.
+    ///
+    /// The check and the write happen under the engine's write lock, so of
+    /// concurrent callers inserting one key exactly one gets `true`. Same
+    /// durability as `put`: synced at commit.
+    pub fn put_unless_exists<T: Table>(&self, key: T::Key, value: T::Value) -> Result<bool, DatabaseError> {
+        let compressed_val = value.compress();
+        let prefixed = storage_key::<T>(key.encode().as_ref(), compressed_val.as_ref());
+        let inserted = self.engine.put_fast_if_absent(&prefixed, compressed_val.as_ref()).map_err(|e| {
+            DatabaseError::Other(e.to_string())
+        })?;
+        if inserted {
+            self.undo_log.lock().push((prefixed.clone(), None));
+            self.update_stats(|s| {
+                s.puts += 1;
+                s.bytes_written += (prefixed.len() + compressed_val.as_ref().len()) as u64;
+            });
+        }
+        Ok(inserted)
+    }
+
+    /// Whether `key` is present in table `T`, without fetching or
+    /// decompressing its value.
+    pub fn exists<T: Table>(&self, key: T::Key) -> Result<bool, DatabaseError> {
+        self.exists_by_encoded_key::<T>(&key.encode())
+    }
+
+    /// `exists` for an already-encoded key.
+    pub fn exists_by_encoded_key<T: Table>(
+        &self,
+        key: &<T::Key as Encode>::Encoded,
+    ) -> Result<bool, DatabaseError> {
+        let found = TableLookup::new::<T>(key.as_ref()).exists(&self.engine)?;
+        self.update_stats(|s| s.gets += 1);
+        Ok(found)
+    }
+
+    /// Raw compressed value stored under encoded key bytes in `T`, without decompressing.
+    pub fn get_by_encoded_key_raw<T: Table>(&self, encoded_key: &[u8]) -> Result<Option<Vec<u8>>, DatabaseError> {
+        TableLookup::new::<T>(encoded_key).get(&self.engine)
+    }
 }
 
 impl std::fmt::Debug for ClawWriteTx {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ClawWriteTx").finish()
+        f.debug_struct("ClawWriteTx").field("snapshot_id", &self.snapshot_id).finish()
     }
 }
 
@@ -150,57 +630,39 @@ impl DbTx for ClawWriteTx {
     type DupCursor<T: DupSort> = ClawDupCursor<T>;
 
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
-        let table_id = table_id_for_name(T::NAME);
-        let encoded = key.encode();
-        let mut prefixed = Vec::with_capacity(1 + encoded.as_ref().len());
-        prefixed.push(table_id);
-        prefixed.extend_from_slice(encoded.as_ref());
-
-        let raw = self.engine.get(&prefixed).map_err(|e| {
-            DatabaseError::Other(e.to_string())
-        })?;
-
-        match raw {
-            Some(bytes) => {
-                let val = <T::Value as reth_db_api::table::Decompress>::decompress(&bytes)?;
-                Ok(Some(val))
-            }
-            None => Ok(None),
-        }
+        let raw = TableLookup::new::<T>(key.encode().as_ref()).get(&self.engine)?;
+        self.update_stats(|s| s.gets += 1);
+        decompress_value::<T>(raw)
     }
 
     fn get_by_encoded_key<T: Table>(
         &self,
         key: &<T::Key as Encode>::Encoded,
     ) -> Result<Option<T::Value>, DatabaseError> {
-        let table_id = table_id_for_name(T::NAME);
-        let mut prefixed = Vec::with_capacity(1 + key.as_ref().len());
-        prefixed.push(table_id);
-        prefixed.extend_from_slice(key.as_ref());
-
-        let raw = self.engine.get(&prefixed).map_err(|e| {
-            DatabaseError::Other(e.to_string())
-        })?;
-
-        match raw {
-            Some(bytes) => {
-                let val = <T::Value as reth_db_api::table::Decompress>::decompress(&bytes)?;
-                Ok(Some(val))
-            }
-            None => Ok(None),
-        }
+        let raw = TableLookup::new::<T>(key.as_ref()).get(&self.engine)?;
+        self.update_stats(|s| s.gets += 1);
+        decompress_value::<T>(raw)
     }
 
     fn commit(self) -> Result<(), DatabaseError> {
+        tracing::debug!(stats = ?self.stats(), snapshot_id = self.snapshot_id, "ClawWriteTx commit");
         // Sync the WAL — one fsync for the entire transaction
         self.engine.sync_wal().map_err(|e| {
             DatabaseError::Other(e.to_string())
-        })
+        })?;
+        if let Some(newer) = self.ids.commit_write(self.snapshot_id) {
+            tracing::warn!(
+                snapshot_id = self.snapshot_id,
+                newer_write_id = newer,
+                "ClawWriteTx committed after a newer write transaction started; writes may conflict"
+            );
+        }
+        Ok(())
     }
 
     fn abort(self) {
-        // Writes are already in RAM — abort syncs to ensure consistency
-        // Future: implement true rollback with buffered writes
+        // Writes are already in RAM — abort syncs to ensure consistency.
+        // Use `abort_with_rollback` to undo them instead.
         let _ = self.engine.sync_wal();
     }
 
@@ -227,37 +689,47 @@ impl DbTxMut for ClawWriteTx {
     type DupCursorMut<T: DupSort> = ClawDupCursorMut<T>;
 
     fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let table_id = table_id_for_name(T::NAME);
         let encoded_key = key.encode();
         let compressed_val = value.compress();
-
-        let mut prefixed = Vec::with_capacity(1 + encoded_key.as_ref().len());
-        prefixed.push(table_id);
-        prefixed.extend_from_slice(encoded_key.as_ref());
+        // DupSort tables add a duplicate under `[table_id][key][subkey]`
+        let prefixed = storage_key::<T>(encoded_key.as_ref(), compressed_val.as_ref());
 
         // Fast write: WAL append without fsync. Durability comes at commit().
+        self.record_undo(&prefixed)?;
         self.engine.put_fast(&prefixed, compressed_val.as_ref()).map_err(|e| {
             DatabaseError::Other(e.to_string())
-        })
+        })?;
+        self.update_stats(|s| {
+            s.puts += 1;
+            s.bytes_written += (prefixed.len() + compressed_val.as_ref().len()) as u64;
+        });
+        Ok(())
     }
 
     fn delete<T: Table>(
         &self,
         key: T::Key,
-        _value: Option<T::Value>,
+        value: Option<T::Value>,
     ) -> Result<bool, DatabaseError> {
-        let table_id = table_id_for_name(T::NAME);
         let encoded_key = key.encode();
-        let mut prefixed = Vec::with_capacity(1 + encoded_key.as_ref().len());
-        prefixed.push(table_id);
-        prefixed.extend_from_slice(encoded_key.as_ref());
-
-        let existed = self.engine.contains_key(&prefixed);
-        if existed {
+        // DupSort: `Some(value)` deletes that duplicate, `None` every duplicate of the key
+        let doomed: Vec<(Vec<u8>, Vec<u8>)> = match value {
+            Some(value) if T::DUPSORT => {
+                let compressed_val = value.compress();
+                let prefixed = storage_key::<T>(encoded_key.as_ref(), compressed_val.as_ref());
+                self.get_stored(prefixed)?.into_iter().collect()
+            }
+            _ if T::DUPSORT => TableLookup::new::<T>(encoded_key.as_ref()).duplicates(&self.engine),
+            _ => self.get_stored(prefixed_key::<T>(encoded_key.as_ref()))?.into_iter().collect(),
+        };
+        let existed = !doomed.is_empty();
+        for (prefixed, previous) in doomed {
+            self.undo_log.lock().push((prefixed.clone(), Some(previous)));
             self.engine.delete(&prefixed).map_err(|e| {
                 DatabaseError::Other(e.to_string())
             })?;
         }
+        self.update_stats(|s| s.deletes += 1);
         Ok(existed)
     }
 
@@ -267,11 +739,12 @@ impl DbTxMut for ClawWriteTx {
     }
 
     fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
-        Ok(ClawCursorMut::new(self.engine_arc()))
+        self.update_stats(|s| s.cursor_reads += 1);
+        Ok(ClawCursorMut::new(self.engine_arc(), Arc::clone(&self.undo_log)))
     }
 
     fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
-        Ok(ClawDupCursorMut::new(self.engine_arc()))
+        Ok(ClawDupCursorMut::new(self.engine_arc(), Arc::clone(&self.undo_log)))
     }
 }
 
@@ -292,21 +765,45 @@ mod tests {
     #[test]
     fn test_read_tx_commit() {
         let (engine, _dir) = test_engine();
-        let tx = ClawReadTx::new(engine);
+        let tx = ClawReadTx::new(engine, 1);
         tx.commit().unwrap();
     }
 
     #[test]
     fn test_write_tx_commit() {
         let (engine, _dir) = test_engine();
-        let tx = ClawWriteTx::new(engine);
+        let tx = ClawWriteTx::new(engine, Arc::default());
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn test_transactions_are_send_sync() {
+        // reth-db-api requires `DbTx: Send + Sync` and `Database::TXMut: Send + Sync`
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ClawReadTx>();
+        assert_send_sync::<ClawWriteTx>();
+    }
+
+    #[test]
+    fn test_write_tx_put_cross_table() {
+        let (engine, _dir) = test_engine();
+        let tx = ClawWriteTx::new(Arc::clone(&engine), Arc::default());
+        tx.put_cross_table(&[
+            CrossTableWrite { prefix: 0x0C, key: b"acct".to_vec(), value: b"a".to_vec() },
+            CrossTableWrite { prefix: 0x0D, key: b"slot".to_vec(), value: b"s".to_vec() },
+            CrossTableWrite { prefix: 0x0B, key: b"rcpt".to_vec(), value: b"r".to_vec() },
+        ]).unwrap();
         tx.commit().unwrap();
+
+        let read = ClawReadTx::new(engine, 1);
+        assert_eq!(read.raw_get(0x0D, b"slot").unwrap(), Some(b"s".to_vec()));
+        assert_eq!(read.raw_get(0x0B, b"rcpt").unwrap(), Some(b"r".to_vec()));
     }
 
     #[test]
     fn test_raw_get_missing() {
         let (engine, _dir) = test_engine();
-        let tx = ClawReadTx::new(engine);
+        let tx = ClawReadTx::new(engine, 1);
         let result = tx.raw_get(0x01, b"nonexistent").unwrap();
         assert!(result.is_none());
     }
@@ -321,7 +818,7 @@ mod tests {
         engine.put(&key, b"account_data").unwrap();
 
         // Read via ClawReadTx
-        let tx = ClawReadTx::new(Arc::clone(&engine));
+        let tx = ClawReadTx::new(Arc::clone(&engine), 1);
         let result = tx.raw_get(0x0C, b"test_addr").unwrap();
         assert_eq!(result, Some(b"account_data".to_vec()));
     }
@@ -3,19 +3,23 @@
 //! These tests exercise the full Database -> DbTx -> Cursor pipeline
 //! using actual Reth table types (CanonicalHeaders, PlainAccountState, etc).
 
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
 use std::time::Instant;
 
 use alloy_primitives::{Address, B256, U256, address};
-use reth_db::tables::{CanonicalHeaders, HeaderNumbers, PlainAccountState};
+use reth_db::tables::{CanonicalHeaders, HeaderNumbers, PlainAccountState, PlainStorageState};
 use reth_db_api::{
-    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
     database::Database,
     transaction::{DbTx, DbTxMut},
 };
-use reth_primitives_traits::Account;
+use reth_primitives_traits::{Account, StorageEntry};
+use reth_storage_errors::db::DatabaseError;
 use tempfile::TempDir;
 
-use clawstore_core::Config;
+use clawstore_core::format::{serialize_entry, HEADER_SIZE};
+use clawstore_core::{BulkEntryBuffer, ClawStoreEngine, Config, Operation};
 use clawstore_reth::ClawDatabase;
 
 // ---------------------------------------------------------------------------
@@ -28,6 +32,34 @@ fn test_db() -> (ClawDatabase, TempDir) {
     (db, dir)
 }
 
+/// System allocator that counts allocations made by the current thread.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Allocations made by `f` on this thread.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
 // ---------------------------------------------------------------------------
 // Basic Table Operations
 // ---------------------------------------------------------------------------
@@ -123,6 +155,72 @@ fn test_table_isolation() {
     assert_eq!(entries_hn, 1);
 }
 
+#[test]
+fn test_counts_without_transaction() {
+    let (db, _dir) = test_db();
+    assert_eq!(db.total_entry_count(), 0);
+
+    let tx = db.tx_mut().unwrap();
+    for i in 0..5u64 {
+        tx.put::<CanonicalHeaders>(i, B256::repeat_byte(i as u8)).unwrap();
+    }
+    tx.put::<HeaderNumbers>(B256::repeat_byte(0x11), 1u64).unwrap();
+    tx.put::<HeaderNumbers>(B256::repeat_byte(0x22), 2u64).unwrap();
+    tx.commit().unwrap();
+
+    assert_eq!(db.table_cursor_count::<CanonicalHeaders>().unwrap(), 5);
+    assert_eq!(db.table_cursor_count::<HeaderNumbers>().unwrap(), 2);
+    assert_eq!(db.table_cursor_count::<PlainAccountState>().unwrap(), 0);
+    assert_eq!(db.total_entry_count(), 7);
+    assert!(format!("{:?}", db).contains("entries: 7"));
+}
+
+#[test]
+fn test_snapshot_ids_increase_across_threads() {
+    let (db, _dir) = test_db();
+    assert_eq!(db.latest_committed_id(), 0);
+
+    let per_thread: Vec<Vec<u64>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8).map(|t| {
+            let db = &db;
+            scope.spawn(move || {
+                (0..100).map(|i| {
+                    if (t + i) % 3 == 0 {
+                        let tx = db.tx_mut().unwrap();
+                        let id = tx.snapshot_id();
+                        tx.commit().unwrap();
+                        id
+                    } else {
+                        db.tx().unwrap().snapshot_id()
+                    }
+                }).collect()
+            })
+        }).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    // Strictly increasing within each thread, unique across all of them
+    for ids in &per_thread {
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+    let mut all: Vec<u64> = per_thread.into_iter().flatten().collect();
+    all.sort_unstable();
+    assert_eq!(all, (1..=800).collect::<Vec<u64>>());
+
+    // A pooled checkout also takes the next ID
+    assert_eq!(db.pooled_tx().snapshot_id(), 801);
+
+    // latest_committed_id follows the highest committed write
+    let older = db.tx_mut().unwrap();
+    let newer = db.tx_mut().unwrap();
+    let (older_id, newer_id) = (older.snapshot_id(), newer.snapshot_id());
+    assert!(older_id < newer_id);
+    newer.commit().unwrap();
+    assert_eq!(db.latest_committed_id(), newer_id);
+    older.commit().unwrap(); // logs a conflict warning
+    assert_eq!(db.latest_committed_id(), newer_id);
+}
+
 // ---------------------------------------------------------------------------
 // Delete
 // ---------------------------------------------------------------------------
@@ -153,6 +251,272 @@ fn test_delete() {
     assert!(!deleted);
 }
 
+#[test]
+fn test_write_tx_stats_counts_operations() {
+    let (db, _dir) = test_db();
+
+    let tx = db.tx_mut().unwrap();
+    tx.put::<CanonicalHeaders>(0u64, B256::ZERO).unwrap();
+    tx.put::<CanonicalHeaders>(1u64, B256::ZERO).unwrap();
+    tx.get::<CanonicalHeaders>(0u64).unwrap();
+    tx.get::<CanonicalHeaders>(42u64).unwrap();
+    tx.get::<CanonicalHeaders>(1u64).unwrap();
+    tx.delete::<CanonicalHeaders>(0u64, None).unwrap();
+    tx.cursor_write::<CanonicalHeaders>().unwrap();
+
+    let stats = tx.stats();
+    assert_eq!(stats.puts, 2);
+    assert_eq!(stats.gets, 3);
+    assert_eq!(stats.deletes, 1);
+    assert_eq!(stats.cursor_reads, 1);
+    // Each put: 1 table byte + 8 key bytes + 32 value bytes
+    assert_eq!(stats.bytes_written, 2 * 41);
+    tx.commit().unwrap();
+
+    // Counters are per transaction
+    let tx = db.tx_mut().unwrap();
+    assert_eq!(tx.stats(), clawstore_reth::TxStats::default());
+}
+
+#[test]
+fn test_exists_without_reading_value() {
+    let (db, _dir) = test_db();
+
+    let tx = db.tx_mut().unwrap();
+    tx.put::<CanonicalHeaders>(7u64, B256::repeat_byte(0x07)).unwrap();
+    assert!(tx.exists::<CanonicalHeaders>(7u64).unwrap());
+    assert!(!tx.exists::<CanonicalHeaders>(8u64).unwrap());
+    assert_eq!(tx.stats().gets, 2);
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    assert!(tx.exists::<CanonicalHeaders>(7u64).unwrap());
+    assert!(!tx.exists::<CanonicalHeaders>(8u64).unwrap());
+    assert!(tx.exists_by_encoded_key::<CanonicalHeaders>(&7u64.to_be_bytes()).unwrap());
+    // Same key, other table
+    assert!(!tx.exists::<HeaderNumbers>(B256::repeat_byte(0x07)).unwrap());
+
+    // No value copy or decompression: fewer allocations than get
+    let exists_allocs = count_allocations(|| {
+        tx.exists::<CanonicalHeaders>(7u64).unwrap();
+    });
+    let get_allocs = count_allocations(|| {
+        tx.get::<CanonicalHeaders>(7u64).unwrap();
+    });
+    assert!(exists_allocs < get_allocs, "exists: {exists_allocs}, get: {get_allocs}");
+}
+
+#[test]
+fn test_put_unless_exists() {
+    let (db, _dir) = test_db();
+
+    let tx = db.tx_mut().unwrap();
+    assert!(tx.put_unless_exists::<CanonicalHeaders>(1u64, B256::repeat_byte(0x01)).unwrap());
+    assert!(!tx.put_unless_exists::<CanonicalHeaders>(1u64, B256::repeat_byte(0x02)).unwrap());
+    assert_eq!(tx.stats().puts, 1);
+    tx.commit().unwrap();
+    assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1u64).unwrap(), Some(B256::repeat_byte(0x01)));
+
+    // Racing inserts of one key: exactly one wins, and its value is stored
+    for block in 100..120u64 {
+        let results: Vec<(bool, B256)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = [0xAAu8, 0xBB].into_iter().map(|byte| {
+                let db = &db;
+                scope.spawn(move || {
+                    let tx = db.tx_mut().unwrap();
+                    let value = B256::repeat_byte(byte);
+                    let inserted = tx.put_unless_exists::<CanonicalHeaders>(block, value).unwrap();
+                    tx.commit().unwrap();
+                    (inserted, value)
+                })
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let winners: Vec<B256> = results.iter().filter(|(inserted, _)| *inserted).map(|(_, v)| *v).collect();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(block).unwrap(), Some(winners[0]));
+    }
+}
+
+#[test]
+fn test_closure_transactions() {
+    let (db, _dir) = test_db();
+
+    // Ok commits and passes the closure's value through
+    let written = db.transaction(|tx| {
+        for block in 0..3u64 {
+            tx.put::<CanonicalHeaders>(block, B256::repeat_byte(block as u8))?;
+        }
+        Ok(3usize)
+    }).unwrap();
+    assert_eq!(written, 3);
+
+    let read = db.read_transaction(|tx| tx.get::<CanonicalHeaders>(2u64)).unwrap();
+    assert_eq!(read, Some(B256::repeat_byte(2)));
+
+    // Err aborts and is returned unchanged
+    let err = db.transaction(|_tx| -> Result<(), DatabaseError> {
+        Err(DatabaseError::Other("rejected".into()))
+    }).unwrap_err();
+    assert!(matches!(err, DatabaseError::Other(msg) if msg == "rejected"));
+    assert_eq!(db.read_transaction(|tx| tx.entries::<CanonicalHeaders>()).unwrap(), 3);
+}
+
+#[test]
+fn test_abort_with_rollback_restores_previous_state() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[1, 2, 3]);
+
+    let tx = db.tx_mut().unwrap();
+    tx.put::<CanonicalHeaders>(1, B256::repeat_byte(0xAA)).unwrap();
+    tx.put::<CanonicalHeaders>(1, B256::repeat_byte(0xBB)).unwrap();
+    tx.delete::<CanonicalHeaders>(2, None).unwrap();
+    tx.put::<CanonicalHeaders>(10, B256::repeat_byte(0xCC)).unwrap();
+    assert!(tx.put_unless_exists::<CanonicalHeaders>(11, B256::repeat_byte(0xDD)).unwrap());
+    assert_eq!(tx.get::<CanonicalHeaders>(1).unwrap(), Some(B256::repeat_byte(0xBB)));
+
+    tx.abort_with_rollback().unwrap();
+    for block in [1u64, 2, 3] {
+        assert_eq!(tx.get::<CanonicalHeaders>(block).unwrap(), Some(B256::from(U256::from(block))));
+    }
+    assert_eq!(tx.get::<CanonicalHeaders>(10).unwrap(), None);
+    assert_eq!(tx.get::<CanonicalHeaders>(11).unwrap(), None);
+
+    // The log was consumed: later writes roll back on their own
+    tx.put::<CanonicalHeaders>(3, B256::ZERO).unwrap();
+    tx.abort_with_rollback().unwrap();
+    tx.commit().unwrap();
+    assert_eq!(db.table_cursor_count::<CanonicalHeaders>().unwrap(), 3);
+    assert_eq!(
+        db.read_transaction(|tx| tx.get::<CanonicalHeaders>(3u64)).unwrap(),
+        Some(B256::from(U256::from(3u64)))
+    );
+}
+
+#[test]
+fn test_abort_with_rollback_undoes_cursor_writes() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[1, 2, 3]);
+    let addr = address!("00000000000000000000000000000000000000aa");
+
+    let tx = db.tx_mut().unwrap();
+    {
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        cursor.upsert(1, &B256::repeat_byte(0xAA)).unwrap();
+        cursor.insert(10, &B256::repeat_byte(0xCC)).unwrap();
+        cursor.seek_exact(2).unwrap();
+        cursor.delete_current().unwrap();
+        assert_eq!(cursor.delete_range(3..=3).unwrap(), 1);
+
+        let mut dup_cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        dup_cursor.upsert(addr, &slot(1)).unwrap();
+        dup_cursor.append_dup(addr, slot(2)).unwrap();
+    }
+
+    tx.abort_with_rollback().unwrap();
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    for block in [1u64, 2, 3] {
+        assert_eq!(tx.get::<CanonicalHeaders>(block).unwrap(), Some(B256::from(U256::from(block))));
+    }
+    assert_eq!(tx.get::<CanonicalHeaders>(10).unwrap(), None);
+    assert_eq!(tx.entries::<PlainStorageState>().unwrap(), 0);
+}
+
+#[test]
+fn test_dump_and_load_table_json() {
+    let (db, dir) = test_db();
+    put_headers(&db, &[0, 1, 2, 1000]);
+    let tx = db.tx_mut().unwrap();
+    for i in 0..5u64 {
+        let account = Account {
+            nonce: i,
+            balance: U256::from(i * 7),
+            bytecode_hash: (i % 2 == 0).then(|| B256::repeat_byte(i as u8)),
+        };
+        tx.put::<PlainAccountState>(Address::repeat_byte(i as u8), account).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let headers_json = dir.path().join("headers.json");
+    let accounts_json = dir.path().join("accounts.json");
+    assert_eq!(db.dump_table_to_json::<CanonicalHeaders>(&headers_json).unwrap(), 4);
+    assert_eq!(db.dump_table_to_json::<PlainAccountState>(&accounts_json).unwrap(), 5);
+    // Human-readable key rendering is kept next to the raw bytes
+    assert!(std::fs::read_to_string(&headers_json).unwrap().contains("\"key\": \"1000\""));
+
+    let (restored, _restored_dir) = test_db();
+    assert_eq!(restored.load_table_from_json::<CanonicalHeaders>(&headers_json).unwrap(), 4);
+    assert_eq!(restored.load_table_from_json::<PlainAccountState>(&accounts_json).unwrap(), 5);
+
+    let (src, dst) = (db.tx().unwrap(), restored.tx().unwrap());
+    for block in [0u64, 1, 2, 1000] {
+        assert_eq!(dst.get::<CanonicalHeaders>(block).unwrap(), src.get::<CanonicalHeaders>(block).unwrap());
+    }
+    for i in 0..5u8 {
+        let addr = Address::repeat_byte(i);
+        assert_eq!(dst.get::<PlainAccountState>(addr).unwrap(), src.get::<PlainAccountState>(addr).unwrap());
+    }
+    assert_eq!(dst.entries::<CanonicalHeaders>().unwrap(), 4);
+}
+
+// ---------------------------------------------------------------------------
+// Pooled read transactions
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_pooled_tx_reuse_and_warm_cursor() {
+    let dir = TempDir::new().unwrap();
+    let config = Config {
+        read_tx_pool_size: 2,
+        pre_warm_tables: vec!["CanonicalHeaders".to_string()],
+        ..Config::default()
+    };
+    let db = ClawDatabase::open(dir.path(), config).unwrap();
+
+    // A large unrelated table makes cold cursor snapshots scan many keys
+    let tx = db.tx_mut().unwrap();
+    for i in 0..50_000u64 {
+        tx.put::<HeaderNumbers>(B256::from(U256::from(i)), i).unwrap();
+    }
+    for i in 0..10u64 {
+        tx.put::<CanonicalHeaders>(i, B256::repeat_byte(i as u8)).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let pool = db.read_pool();
+    assert_eq!(pool.idle_count(), 2);
+    {
+        let tx = db.pooled_tx();
+        assert_eq!(pool.idle_count(), 1);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        assert_eq!(cursor.first().unwrap(), Some((0, B256::ZERO)));
+    }
+    drop(db.pooled_tx());
+    assert_eq!(pool.idle_count(), 2);
+    assert_eq!(pool.reuse_count(), 2);
+
+    // First cursor on a pre-warmed table shares the pool snapshot
+    let pooled = db.pooled_tx();
+    let start = Instant::now();
+    let warm_cursor = pooled.cursor_read::<CanonicalHeaders>().unwrap();
+    let warm = start.elapsed();
+    let plain = db.tx().unwrap();
+    let start = Instant::now();
+    let cold_cursor = plain.cursor_read::<CanonicalHeaders>().unwrap();
+    let cold = start.elapsed();
+    assert!(warm < cold, "warm {:?} should beat cold {:?}", warm, cold);
+    drop((warm_cursor, cold_cursor));
+
+    // A write makes the warm snapshot stale; cursors fall back to a fresh one
+    let tx = db.tx_mut().unwrap();
+    tx.put::<CanonicalHeaders>(99u64, B256::ZERO).unwrap();
+    tx.commit().unwrap();
+    let mut cursor = pooled.cursor_read::<CanonicalHeaders>().unwrap();
+    assert_eq!(cursor.last().unwrap().map(|(k, _)| k), Some(99));
+}
+
 // ---------------------------------------------------------------------------
 // Cursor Operations
 // ---------------------------------------------------------------------------
@@ -270,6 +634,76 @@ fn test_cursor_walk_range() {
     assert_eq!(count, 6); // 5, 6, 7, 8, 9, 10
 }
 
+#[test]
+fn test_walk_back_from_exclusive_and_inclusive() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &(0..=20).collect::<Vec<_>>());
+
+    let tx = db.tx().unwrap();
+    let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+
+    let keys: Vec<u64> = cursor.walk_back_from(10u64).unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(keys, (0..=9).rev().collect::<Vec<_>>());
+
+    let keys: Vec<u64> = cursor.walk_back_from_inclusive(10u64).unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(keys, (0..=10).rev().collect::<Vec<_>>());
+
+    // Past the end: both start at the last entry; at the start: nothing before 0
+    assert_eq!(cursor.walk_back_from(100u64).unwrap().next().unwrap().unwrap().0, 20);
+    assert_eq!(cursor.walk_back_from_inclusive(100u64).unwrap().next().unwrap().unwrap().0, 20);
+    assert!(cursor.walk_back_from(0u64).unwrap().next().is_none());
+    assert_eq!(cursor.walk_back_from_inclusive(0u64).unwrap().next().unwrap().unwrap().0, 0);
+}
+
+#[test]
+fn test_cursor_current_reuses_decoded_key() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &(0..10).collect::<Vec<_>>());
+
+    let tx = db.tx().unwrap();
+    let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+    let at_five = cursor.seek_exact(5u64).unwrap();
+    assert_eq!(cursor.key_decode_count(), 1);
+
+    for _ in 0..10_000 {
+        assert_eq!(cursor.current().unwrap(), at_five);
+    }
+    assert_eq!(cursor.key_decode_count(), 1);
+
+    // Seeking to the current key reuses it too; moving decodes again
+    assert_eq!(cursor.seek_exact(5u64).unwrap(), at_five);
+    assert_eq!(cursor.key_decode_count(), 1);
+    assert_eq!(cursor.next().unwrap().unwrap().0, 6);
+    assert_eq!(cursor.prev().unwrap(), at_five);
+    assert_eq!(cursor.key_decode_count(), 3);
+}
+
+#[test]
+fn test_cursor_collect_helpers() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[40, 10, 30, 0, 20]);
+
+    let tx = db.tx().unwrap();
+    let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+    let all = cursor.collect_all().unwrap();
+    let keys: Vec<u64> = all.iter().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec![0, 10, 20, 30, 40]);
+    assert_eq!(all[2].1, B256::from(U256::from(20u64)));
+
+    let first_two: Vec<u64> = cursor.collect_n(2).unwrap().into_iter().map(|(k, _)| k).collect();
+    assert_eq!(first_two, vec![0, 10]);
+    // Stops on the last entry collected
+    assert_eq!(cursor.current().unwrap().unwrap().0, 10);
+    assert!(cursor.collect_n(0).unwrap().is_empty());
+    assert_eq!(cursor.collect_n(100).unwrap().len(), 5);
+
+    let from: Vec<u64> = cursor.collect_from(20).unwrap().into_iter().map(|(k, _)| k).collect();
+    assert_eq!(from, vec![20, 30, 40]);
+    let from_gap: Vec<u64> = cursor.collect_from(25).unwrap().into_iter().map(|(k, _)| k).collect();
+    assert_eq!(from_gap, vec![30, 40]);
+    assert!(cursor.collect_from(41).unwrap().is_empty());
+}
+
 // ---------------------------------------------------------------------------
 // Cursor Write Operations
 // ---------------------------------------------------------------------------
@@ -296,6 +730,93 @@ fn test_cursor_upsert() {
     assert_eq!(val, B256::repeat_byte(0xFF));
 }
 
+fn put_headers(db: &ClawDatabase, blocks: &[u64]) {
+    let tx = db.tx_mut().unwrap();
+    for &i in blocks {
+        tx.put::<CanonicalHeaders>(i, B256::from(U256::from(i))).unwrap();
+    }
+    tx.commit().unwrap();
+}
+
+#[test]
+fn test_cursor_delete_then_next() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[1, 2, 3, 4]);
+
+    let tx = db.tx_mut().unwrap();
+    let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+    cursor.seek_exact(2u64).unwrap();
+    cursor.delete_current().unwrap();
+    assert_eq!(cursor.current().unwrap().unwrap().0, 3u64);
+    assert_eq!(cursor.next().unwrap().unwrap().0, 3u64);
+    assert_eq!(cursor.next().unwrap().unwrap().0, 4u64);
+
+    // Deleting the last entry leaves nothing after it
+    cursor.delete_current().unwrap();
+    assert_eq!(cursor.next().unwrap(), None);
+    assert_eq!(cursor.prev().unwrap().unwrap().0, 3u64);
+
+    // prev() right after a delete returns the entry before the deleted one
+    cursor.delete_current().unwrap();
+    assert_eq!(cursor.prev().unwrap().unwrap().0, 1u64);
+}
+
+#[test]
+fn test_cursor_snapshot_at_tx_start_ignores_later_writes() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[1, 2]);
+
+    let tx = db.tx_with_start_snapshot().unwrap();
+    put_headers(&db, &[3]);
+
+    let mut cursor = tx.cursor_read_snapshot_at_tx_start::<CanonicalHeaders>().unwrap();
+    let keys: Vec<u64> = cursor.walk(None).unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(keys, vec![1, 2]);
+
+    // cursor_read keeps snapshotting at cursor creation
+    let mut live = tx.cursor_read::<CanonicalHeaders>().unwrap();
+    let keys: Vec<u64> = live.walk(None).unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(keys, vec![1, 2, 3]);
+
+    put_headers(&db, &[4]);
+    let mut again = tx.cursor_read_snapshot_at_tx_start::<CanonicalHeaders>().unwrap();
+    assert_eq!(again.last().unwrap().unwrap().0, 2u64);
+    assert!(db.tx().unwrap().cursor_read_snapshot_at_tx_start::<CanonicalHeaders>().is_err());
+}
+
+#[test]
+fn test_cursor_insert_then_walk() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[10, 30]);
+
+    let tx = db.tx_mut().unwrap();
+    let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+    cursor.insert(20u64, &B256::repeat_byte(0x20)).unwrap();
+    cursor.insert(40u64, &B256::repeat_byte(0x40)).unwrap();
+
+    let keys: Vec<u64> = cursor.walk(None).unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(keys, vec![10, 20, 30, 40]);
+}
+
+#[test]
+fn test_cursor_upsert_then_prev() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[5, 15]);
+
+    let tx = db.tx_mut().unwrap();
+    let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+    cursor.seek_exact(15u64).unwrap();
+    cursor.upsert(10u64, &B256::repeat_byte(0x10)).unwrap();
+    assert_eq!(cursor.prev().unwrap().unwrap(), (10u64, B256::repeat_byte(0x10)));
+    assert_eq!(cursor.prev().unwrap().unwrap().0, 5u64);
+
+    // Writes made outside the cursor appear after a refresh
+    tx.put::<CanonicalHeaders>(1u64, B256::repeat_byte(0x01)).unwrap();
+    assert_eq!(cursor.prev().unwrap(), None);
+    cursor.refresh_snapshot();
+    assert_eq!(cursor.first().unwrap().unwrap().0, 1u64);
+}
+
 // ---------------------------------------------------------------------------
 // Account State Round-Trip (Complex Type)
 // ---------------------------------------------------------------------------
@@ -401,6 +922,122 @@ fn test_bulk_canonical_headers() {
     println!("  Entries: {}", entries);
 }
 
+#[test]
+fn test_bulk_entry_buffer_allocations() {
+    let count = 10_000u64;
+    let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..count)
+        .map(|i| (i.to_be_bytes().to_vec(), B256::from(U256::from(i)).to_vec()))
+        .collect();
+
+    let start = Instant::now();
+    let individual_allocs = count_allocations(|| {
+        for (key, value) in &entries {
+            let bytes = serialize_entry(key, value, Operation::Put).unwrap();
+            std::hint::black_box(bytes);
+        }
+    });
+    let individual_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut buffer = BulkEntryBuffer::new(0);
+    let bulk_allocs = count_allocations(|| {
+        buffer = BulkEntryBuffer::new(entries.len() * (HEADER_SIZE + 8 + 8 + 32));
+        for (key, value) in &entries {
+            buffer.append_entry(key, value, Operation::Put).unwrap();
+        }
+    });
+    let bulk_time = start.elapsed();
+    assert_eq!(buffer.len(), count as usize);
+
+    println!("\n=== BulkEntryBuffer vs serialize_entry ({} entries) ===", count);
+    println!("  serialize_entry:  {:?}, {} allocations", individual_time, individual_allocs);
+    println!("  BulkEntryBuffer:  {:?}, {} allocations", bulk_time, bulk_allocs);
+    assert!(individual_allocs >= count as usize);
+    // Buffer and offset vector growth only, independent of entry count
+    assert!(bulk_allocs < 64, "bulk: {bulk_allocs}");
+}
+
+#[test]
+fn test_put_many_fast_sync_once_throughput() {
+    let count = 10_000u64;
+    let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..count)
+        .map(|i| (i.to_be_bytes().to_vec(), B256::from(U256::from(i)).to_vec()))
+        .collect();
+
+    let dir = TempDir::new().unwrap();
+    let batched = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+    let start = Instant::now();
+    batched.put_many_fast_sync_once(entries.clone()).unwrap();
+    let batch_time = start.elapsed();
+    assert_eq!(batched.len(), count as usize);
+
+    let dir = TempDir::new().unwrap();
+    let individual = ClawStoreEngine::open(dir.path(), Config::default()).unwrap();
+    let start = Instant::now();
+    for (key, value) in &entries {
+        individual.put_fast(key, value).unwrap();
+    }
+    individual.sync_wal().unwrap();
+    let individual_time = start.elapsed();
+    assert_eq!(individual.len(), count as usize);
+
+    println!("\n=== put_many_fast_sync_once ({} entries) ===", count);
+    println!("  Batch:      {:?} ({:.0} entries/sec)",
+        batch_time, count as f64 / batch_time.as_secs_f64());
+    println!("  Individual: {:?} ({:.0} entries/sec)",
+        individual_time, count as f64 / individual_time.as_secs_f64());
+}
+
+#[test]
+fn test_get_throughput_under_write_contention() {
+    const READERS: u64 = 16;
+    const KEYS: u64 = 10_000;
+    const GETS_PER_READER: u64 = 100_000;
+
+    println!("\n=== get under contention ({} readers, 1 writer) ===", READERS);
+    for use_concurrent_hashmap in [false, true] {
+        let dir = TempDir::new().unwrap();
+        let config = Config { use_concurrent_hashmap, ..Config::default() };
+        let engine = std::sync::Arc::new(ClawStoreEngine::open(dir.path(), config).unwrap());
+        engine.put_many_fast_sync_once((0..KEYS).map(|i| (i.to_be_bytes().to_vec(), vec![0xCD; 32])))
+            .unwrap();
+
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let writer = {
+            let (engine, stop) = (engine.clone(), stop.clone());
+            std::thread::spawn(move || {
+                let mut writes = 0u64;
+                while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                    engine.put_fast(&(writes % KEYS).to_be_bytes(), &[0xEF; 32]).unwrap();
+                    writes += 1;
+                }
+                writes
+            })
+        };
+
+        let start = Instant::now();
+        let readers: Vec<_> = (0..READERS).map(|r| {
+            let engine = engine.clone();
+            std::thread::spawn(move || {
+                for i in 0..GETS_PER_READER {
+                    let key = ((i + r * 61) % KEYS).to_be_bytes();
+                    assert!(engine.get(&key).unwrap().is_some());
+                }
+            })
+        }).collect();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        let elapsed = start.elapsed();
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        let writes = writer.join().unwrap();
+
+        let label = if use_concurrent_hashmap { "DashMap" } else { "RwLock<HashMap>" };
+        println!("  {:<16} {:.0} gets/sec, {} concurrent writes",
+            label, (READERS * GETS_PER_READER) as f64 / elapsed.as_secs_f64(), writes);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Overwrite / Update Pattern
 // ---------------------------------------------------------------------------
@@ -434,3 +1071,372 @@ fn test_state_overwrite() {
     assert_eq!(account.nonce, 1);
     assert_eq!(account.balance, U256::from(90));
 }
+
+// ---------------------------------------------------------------------------
+// Multi-key Lookups
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_get_two_across_tables() {
+    let (db, _dir) = test_db();
+
+    let hash = B256::repeat_byte(0x42);
+    let tx = db.tx_mut().unwrap();
+    tx.put::<CanonicalHeaders>(7u64, hash).unwrap();
+    tx.put::<HeaderNumbers>(hash, 7u64).unwrap();
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    let locks_before = db.engine().read_lock_count();
+    let (canonical, number) = tx.get_two::<CanonicalHeaders, HeaderNumbers>(7u64, hash).unwrap();
+    assert_eq!(db.engine().read_lock_count() - locks_before, 1);
+    assert_eq!(canonical, Some(hash));
+    assert_eq!(number, Some(7u64));
+
+    // Missing keys come back as None independently
+    let (canonical, number) = tx.get_two::<CanonicalHeaders, HeaderNumbers>(8u64, hash).unwrap();
+    assert_eq!(canonical, None);
+    assert_eq!(number, Some(7u64));
+}
+
+#[test]
+fn test_get_many_preserves_order() {
+    let (db, _dir) = test_db();
+
+    let tx = db.tx_mut().unwrap();
+    for i in (0u64..10).step_by(2) {
+        tx.put::<CanonicalHeaders>(i, B256::from(U256::from(i))).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    let locks_before = db.engine().read_lock_count();
+    let values = tx.get_many::<CanonicalHeaders>(&[4, 3, 0, 8]).unwrap();
+    assert_eq!(db.engine().read_lock_count() - locks_before, 1);
+    assert_eq!(values, vec![
+        Some(B256::from(U256::from(4u64))),
+        None,
+        Some(B256::from(U256::from(0u64))),
+        Some(B256::from(U256::from(8u64))),
+    ]);
+}
+
+#[test]
+fn test_get_batch_positions() {
+    let (db, _dir) = test_db();
+    // Even block numbers exist, odd ones do not
+    let present: Vec<u64> = (0u64..100).step_by(2).collect();
+    put_headers(&db, &present);
+
+    let tx = db.tx().unwrap();
+    let results = tx.get_batch::<CanonicalHeaders>(0u64..100).unwrap();
+    assert_eq!(results.len(), 100);
+    for (i, (key, value)) in results.iter().enumerate() {
+        assert_eq!(*key, i as u64);
+        let expected = (i % 2 == 0).then(|| B256::from(U256::from(i as u64)));
+        assert_eq!(*value, expected);
+    }
+    assert_eq!(results.iter().filter(|(_, v)| v.is_some()).count(), 50);
+
+    let values = tx.get_batch_values_only::<CanonicalHeaders>([7u64, 6, 99, 98]).unwrap();
+    assert_eq!(values, vec![
+        None,
+        Some(B256::from(U256::from(6u64))),
+        None,
+        Some(B256::from(U256::from(98u64))),
+    ]);
+}
+
+#[test]
+fn test_walk_all_tables_groups_by_table_then_key() {
+    let (db, _dir) = test_db();
+    put_headers(&db, &[3, 1, 2]);
+    let addr = address!("00000000000000000000000000000000000000aa");
+    let tx = db.tx_mut().unwrap();
+    tx.put::<HeaderNumbers>(B256::repeat_byte(0x22), 2u64).unwrap();
+    tx.put::<HeaderNumbers>(B256::repeat_byte(0x11), 1u64).unwrap();
+    tx.put::<PlainStorageState>(addr, slot(2)).unwrap();
+    tx.put::<PlainStorageState>(addr, slot(1)).unwrap();
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    let entries: Vec<(u8, Vec<u8>, Vec<u8>)> = tx.walk_all_tables().collect();
+    let table_ids: Vec<u8> = entries.iter().map(|(id, _, _)| *id).collect();
+    assert_eq!(table_ids, vec![0x01, 0x01, 0x01, 0x02, 0x02, 0x0D, 0x0D]);
+    assert!(entries.windows(2).all(|w| (w[0].0, &w[0].1) < (w[1].0, &w[1].1)));
+
+    let blocks: Vec<u64> = entries[..3].iter()
+        .map(|(_, key, _)| u64::from_be_bytes(key.as_slice().try_into().unwrap()))
+        .collect();
+    assert_eq!(blocks, vec![1, 2, 3]);
+    assert_eq!(entries[3].1, B256::repeat_byte(0x11).to_vec());
+
+    assert_eq!(tx.table_names_with_data(), vec!["CanonicalHeaders", "HeaderNumbers", "PlainStorageState"]);
+}
+
+#[test]
+fn test_cursor_delete_range() {
+    let (db, _dir) = test_db();
+
+    let tx = db.tx_mut().unwrap();
+    for i in 0u64..=99 {
+        tx.put::<CanonicalHeaders>(i, B256::from(U256::from(i))).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let tx = db.tx_mut().unwrap();
+    let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+    let deleted = cursor.delete_range(10u64..=20u64).unwrap();
+    assert_eq!(deleted, 11);
+
+    // Cursor still navigates around the hole
+    assert_eq!(cursor.seek(10u64).unwrap().unwrap().0, 21u64);
+    assert_eq!(cursor.prev().unwrap().unwrap().0, 9u64);
+    assert_eq!(cursor.last().unwrap().unwrap().0, 99u64);
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    assert_eq!(tx.entries::<CanonicalHeaders>().unwrap(), 89);
+    for i in 10u64..=20 {
+        assert_eq!(tx.get::<CanonicalHeaders>(i).unwrap(), None);
+    }
+    assert!(tx.get::<CanonicalHeaders>(9u64).unwrap().is_some());
+    assert!(tx.get::<CanonicalHeaders>(21u64).unwrap().is_some());
+}
+
+#[test]
+fn test_cursor_delete_range_empty_or_inverted() {
+    use std::ops::Bound;
+
+    let (db, _dir) = test_db();
+    put_headers(&db, &[1, 2, 3, 4, 5]);
+
+    let tx = db.tx_mut().unwrap();
+    let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+    assert_eq!(cursor.delete_range((Bound::Included(4u64), Bound::Included(2u64))).unwrap(), 0);
+    assert_eq!(cursor.delete_range((Bound::Excluded(3u64), Bound::Excluded(3u64))).unwrap(), 0);
+    assert_eq!(cursor.delete_range((Bound::Excluded(3u64), Bound::Excluded(2u64))).unwrap(), 0);
+    assert_eq!(cursor.delete_range((Bound::Included(3u64), Bound::Excluded(3u64))).unwrap(), 0);
+    tx.commit().unwrap();
+
+    assert_eq!(db.table_cursor_count::<CanonicalHeaders>().unwrap(), 5);
+}
+
+#[test]
+fn test_put_encoded_matches_put() {
+    use reth_db_api::table::{Compress, Encode};
+
+    let (db, _dir) = test_db();
+    let tx = db.tx_mut().unwrap();
+
+    let addr = address!("00000000000000000000000000000000000000aa");
+    let account = Account { nonce: 7, balance: U256::from(1_000u64), bytecode_hash: None };
+    tx.put::<PlainAccountState>(addr, account).unwrap();
+    let via_put = tx.get_by_encoded_key_raw::<PlainAccountState>(addr.encode().as_ref()).unwrap().unwrap();
+
+    let other = address!("00000000000000000000000000000000000000bb");
+    tx.put_encoded::<PlainAccountState>(other.encode().as_ref(), account.compress().as_ref()).unwrap();
+    let via_encoded = tx.get_by_encoded_key_raw::<PlainAccountState>(other.encode().as_ref()).unwrap().unwrap();
+
+    assert_eq!(via_put, via_encoded);
+    assert_eq!(tx.get::<PlainAccountState>(other).unwrap(), Some(account));
+    assert_eq!(tx.get_by_encoded_key_raw::<PlainAccountState>(&[0u8; 20]).unwrap(), None);
+}
+
+// ---------------------------------------------------------------------------
+// Bulk Import
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_import_canonical_headers() {
+    let (db, _dir) = test_db();
+
+    let tx = db.tx_mut().unwrap();
+    let count = tx
+        .import::<CanonicalHeaders>((0..100_000u64).map(|i| (i, B256::from(U256::from(i)))))
+        .unwrap();
+    tx.commit().unwrap();
+    assert_eq!(count, 100_000);
+
+    let tx = db.tx().unwrap();
+    assert_eq!(tx.entries::<CanonicalHeaders>().unwrap(), 100_000);
+    for i in (0..100_000u64).step_by(997) {
+        assert_eq!(tx.get::<CanonicalHeaders>(i).unwrap(), Some(B256::from(U256::from(i))));
+    }
+    assert_eq!(tx.get::<CanonicalHeaders>(99_999).unwrap(), Some(B256::from(U256::from(99_999u64))));
+}
+
+// ---------------------------------------------------------------------------
+// DupSort (PlainStorageState)
+// ---------------------------------------------------------------------------
+
+fn slot(n: u8) -> StorageEntry {
+    StorageEntry { key: B256::repeat_byte(n), value: U256::from(n) }
+}
+
+#[test]
+fn test_dupsort_multiple_storage_slots() {
+    let (db, _dir) = test_db();
+    let addr_a = address!("00000000000000000000000000000000000000aa");
+    let addr_b = address!("00000000000000000000000000000000000000bb");
+
+    let tx = db.tx_mut().unwrap();
+    {
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        // Inserted out of order — duplicates must come back sorted by slot
+        cursor.upsert(addr_a, &slot(3)).unwrap();
+        cursor.upsert(addr_a, &slot(1)).unwrap();
+        cursor.upsert(addr_a, &slot(2)).unwrap();
+        cursor.upsert(addr_b, &slot(1)).unwrap();
+    }
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+
+    assert_eq!(cursor.seek_exact(addr_a).unwrap(), Some((addr_a, slot(1))));
+    assert_eq!(cursor.next_dup().unwrap(), Some((addr_a, slot(2))));
+    assert_eq!(cursor.next_dup().unwrap(), Some((addr_a, slot(3))));
+    assert_eq!(cursor.next_dup().unwrap(), None);
+
+    // next_no_dup skips the remaining slots of addr_a
+    cursor.seek_exact(addr_a).unwrap();
+    assert_eq!(cursor.next_no_dup().unwrap(), Some((addr_b, slot(1))));
+    assert_eq!(cursor.next_no_dup().unwrap(), None);
+
+    // seek_by_key_subkey lands on the first slot >= subkey
+    assert_eq!(cursor.seek_by_key_subkey(addr_a, B256::repeat_byte(2)).unwrap(), Some(slot(2)));
+    assert_eq!(cursor.seek_by_key_subkey(addr_b, B256::repeat_byte(2)).unwrap(), None);
+
+    let dups: Vec<_> = cursor.walk_dup(Some(addr_a), None).unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(dups.len(), 3);
+}
+
+#[test]
+fn test_dupsort_tx_methods_share_cursor_layout() {
+    let (db, _dir) = test_db();
+    let addr_a = address!("00000000000000000000000000000000000000aa");
+    let addr_b = address!("00000000000000000000000000000000000000bb");
+    let missing = address!("00000000000000000000000000000000000000cc");
+
+    let tx = db.tx_mut().unwrap();
+    {
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        cursor.upsert(addr_a, &slot(3)).unwrap();
+        cursor.upsert(addr_a, &slot(2)).unwrap();
+        cursor.upsert(addr_b, &slot(7)).unwrap();
+    }
+    // put adds a duplicate next to the cursor's, rather than a flat entry
+    tx.put::<PlainStorageState>(addr_a, slot(1)).unwrap();
+    assert_eq!(tx.get::<PlainStorageState>(addr_a).unwrap(), Some(slot(1)));
+    assert_eq!(tx.entries::<PlainStorageState>().unwrap(), 4);
+
+    // Some(value) deletes that duplicate only
+    assert!(tx.delete::<PlainStorageState>(addr_a, Some(slot(1))).unwrap());
+    assert!(!tx.delete::<PlainStorageState>(addr_a, Some(slot(1))).unwrap());
+    assert_eq!(tx.get::<PlainStorageState>(addr_a).unwrap(), Some(slot(2)));
+    tx.commit().unwrap();
+
+    let tx = db.tx().unwrap();
+    assert!(tx.exists::<PlainStorageState>(addr_b).unwrap());
+    assert!(!tx.exists::<PlainStorageState>(missing).unwrap());
+    assert_eq!(
+        tx.get_many::<PlainStorageState>(&[addr_b, missing, addr_a]).unwrap(),
+        vec![Some(slot(7)), None, Some(slot(2))]
+    );
+    let locks_before = db.engine().read_lock_count();
+    let (header, storage) = tx.get_two::<CanonicalHeaders, PlainStorageState>(1, addr_a).unwrap();
+    assert_eq!(db.engine().read_lock_count() - locks_before, 1);
+    assert_eq!((header, storage), (None, Some(slot(2))));
+    let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+    assert_eq!(cursor.seek_exact(addr_a).unwrap(), Some((addr_a, slot(2))));
+    assert_eq!(cursor.next_dup().unwrap(), Some((addr_a, slot(3))));
+    drop(tx);
+
+    // None deletes every duplicate of the key
+    let tx = db.tx_mut().unwrap();
+    assert!(tx.delete::<PlainStorageState>(addr_a, None).unwrap());
+    assert_eq!(tx.get::<PlainStorageState>(addr_a).unwrap(), None);
+    assert_eq!(tx.entries::<PlainStorageState>().unwrap(), 1);
+    tx.commit().unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// Crash Recovery
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_crash_recovery_with_concurrent_hashmap() {
+    let dir = TempDir::new().unwrap();
+    let config = Config { use_concurrent_hashmap: true, warm_on_open: true, ..Config::default() };
+    let addr = address!("0000000000000000000000000000000000000001");
+    {
+        let db = ClawDatabase::open(dir.path(), config.clone()).unwrap();
+        let tx = db.tx_mut().unwrap();
+        for i in 0u64..100 {
+            tx.put::<CanonicalHeaders>(i, B256::from(U256::from(i))).unwrap();
+        }
+        tx.put::<PlainAccountState>(addr, Account {
+            nonce: 1,
+            balance: U256::from(90),
+            bytecode_hash: None,
+        }).unwrap();
+        tx.commit().unwrap();
+
+        // Everything so far reaches the data files; the deletes only the WAL
+        db.engine().start_trickle().unwrap();
+        db.engine().stop_trickle();
+        let tx = db.tx_mut().unwrap();
+        for i in 50u64..100 {
+            tx.delete::<CanonicalHeaders>(i, None).unwrap();
+        }
+        tx.commit().unwrap();
+    }
+
+    let db = ClawDatabase::open(dir.path(), config).unwrap();
+    let tx = db.tx().unwrap();
+    assert_eq!(tx.entries::<CanonicalHeaders>().unwrap(), 50);
+    assert_eq!(tx.get::<CanonicalHeaders>(49).unwrap(), Some(B256::from(U256::from(49u64))));
+    assert_eq!(tx.get::<CanonicalHeaders>(50).unwrap(), None);
+    assert_eq!(tx.get::<PlainAccountState>(addr).unwrap().unwrap().nonce, 1);
+    let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+    assert_eq!(cursor.last().unwrap().map(|(number, _)| number), Some(49));
+}
+
+// ---------------------------------------------------------------------------
+// Migration from MDBX
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_migrate_from_mdbx_database() {
+    let mdbx_dir = TempDir::new().unwrap();
+    let args = reth_db::mdbx::DatabaseArguments::new(reth_db_api::models::ClientVersion::default());
+    let mdbx = reth_db::init_db(mdbx_dir.path(), args).unwrap();
+    let addr = address!("00000000000000000000000000000000000000aa");
+    let hash = B256::repeat_byte(0x42);
+    {
+        let tx = mdbx.tx_mut().unwrap();
+        for i in 0u64..10 {
+            tx.put::<CanonicalHeaders>(i, B256::from(U256::from(i))).unwrap();
+        }
+        tx.put::<HeaderNumbers>(hash, 7).unwrap();
+        tx.put::<PlainStorageState>(addr, slot(1)).unwrap();
+        tx.put::<PlainStorageState>(addr, slot(2)).unwrap();
+        tx.commit().unwrap();
+    }
+
+    let claw_dir = TempDir::new().unwrap();
+    let (db, copied) = ClawDatabase::migrate_from(&mdbx, claw_dir.path(), Config::default()).unwrap();
+    assert_eq!(copied, 13);
+    assert_eq!(db.table_cursor_count::<CanonicalHeaders>().unwrap(), 10);
+
+    let tx = db.tx().unwrap();
+    assert_eq!(tx.get::<CanonicalHeaders>(9).unwrap(), Some(B256::from(U256::from(9u64))));
+    assert_eq!(tx.get::<HeaderNumbers>(hash).unwrap(), Some(7));
+    let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+    assert_eq!(cursor.seek_exact(addr).unwrap(), Some((addr, slot(1))));
+    assert_eq!(cursor.next_dup().unwrap(), Some((addr, slot(2))));
+    assert_eq!(cursor.next_dup().unwrap(), None);
+}